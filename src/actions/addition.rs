@@ -1,50 +1,172 @@
+use std::collections::HashMap;
+
+use chrono::Local;
 use rusqlite::Connection;
 
 use crate::{
-    actions::display,
+    actions::{
+        display,
+        modify::{
+            get_rowid_from_cache,
+            validate_cache,
+        },
+    },
     args::{
+        cron,
         parser::{
             RecordCommand,
             TaskCommand,
         },
         timestr,
     },
+    config::get_config,
     db::{
-        crud::insert_item,
+        crud::{
+            get_item,
+            insert_item,
+        },
+        goal,
         item::{
             Item,
+            CLOSED_STATUS_CODES,
             RECORD,
+            RECURRING_TASK,
             TASK,
         },
     },
 };
 
 pub fn handle_taskcmd(conn: &Connection, cmd: &TaskCommand) -> Result<(), String> {
-    let content = cmd.content.clone();
-    let target_timestr = cmd.timestr.clone().unwrap_or_else(|| "today".to_string());
+    let config = get_config();
+    add_task(conn, cmd, &config.category_defaults, config.max_content_length())
+}
+
+// Records further ahead than this need --force.
+const FUTURE_RECORD_LIMIT: i64 = 365 * 86400;
+
+pub fn handle_recordcmd(conn: &Connection, cmd: &RecordCommand) -> Result<(), String> {
+    if let Some(target) = cmd.goal {
+        set_goal(conn, cmd, target)?;
+        if cmd.content.is_none() {
+            return Ok(());
+        }
+    }
+    let config = get_config();
+    add_record(conn, cmd, &config.category_defaults, config.max_content_length())
+}
+
+fn set_goal(conn: &Connection, cmd: &RecordCommand, target: usize) -> Result<(), String> {
+    let category = cmd
+        .category
+        .as_deref()
+        .ok_or("--goal requires --category")?;
+    goal::set(conn, category, target).map_err(|e| e.to_string())?;
+    if !cmd.quiet {
+        if target == 0 {
+            display::print_bold(&format!("Removed daily goal for '{}'", category));
+        } else {
+            display::print_bold(&format!("Daily goal for '{}' set to {}", category, target));
+        }
+    }
+    Ok(())
+}
+
+// Content over the limit is an error, unless truncate is set,
+// then it is cut to the limit on a character boundary.
+pub fn limit_content(content: String, max_length: usize, truncate: bool) -> Result<String, String> {
+    let length = content.chars().count();
+    if length <= max_length {
+        return Ok(content);
+    }
+    if truncate {
+        return Ok(content.chars().take(max_length).collect());
+    }
+    Err(format!(
+        "Content is {} characters, over the limit of {}; shorten it, pass --truncate, or raise max_content_length in config",
+        length, max_length
+    ))
+}
+
+// An explicit timestr wins over the configured default for the category.
+fn resolve_timestr(
+    timestr: &Option<String>,
+    category: &str,
+    category_defaults: &HashMap<String, String>,
+) -> Option<String> {
+    timestr
+        .clone()
+        .or_else(|| category_defaults.get(category).cloned())
+}
+
+fn add_task(
+    conn: &Connection,
+    cmd: &TaskCommand,
+    category_defaults: &HashMap<String, String>,
+    max_content_length: usize,
+) -> Result<(), String> {
+    let content = limit_content(cmd.content.clone(), max_content_length, cmd.truncate)?;
     let category: String = cmd
         .category
         .clone()
         .unwrap_or_else(|| "default".to_string());
+    let target_timestr = resolve_timestr(&cmd.timestr, &category, category_defaults)
+        .unwrap_or_else(|| "today".to_string());
+    if cmd.status.is_some_and(|s| s >= 240) {
+        return Err(
+            "--status requires a concrete status: ongoing|done|cancelled|duplicate|suspended|removed|pending|in_progress"
+                .to_string(),
+        );
+    }
 
-    match timestr::to_unix_epoch(&target_timestr) {
+    let do_date = match &cmd.do_date {
+        Some(t) => Some(timestr::to_unix_epoch(t)?),
+        None => None,
+    };
+    let target_time = match cmd.before_recurring {
+        Some(index) => Ok(before_recurring_time(conn, index, cmd.lead.unwrap_or(0))?),
+        None => timestr::to_unix_epoch(&target_timestr),
+    };
+    match target_time {
         Ok(target_time) => {
-            let new_task =
+            let mut new_task =
                 Item::with_target_time(TASK.to_string(), category, content, Some(target_time));
+            new_task.note = cmd.note.clone();
+            new_task.reference = cmd.reference.clone();
+            new_task.estimate_minutes = cmd.estimate;
+            new_task.do_date = do_date;
+            new_task.remind_before_minutes = cmd.remind;
+            new_task.status = cmd.status.unwrap_or(0);
+            if CLOSED_STATUS_CODES.contains(&new_task.status) {
+                new_task.closed_time = Some(new_task.create_time);
+            }
             insert_item(conn, &new_task).map_err(|e| e.to_string())?;
 
-            display::print_bold("Inserted Task:");
-            display::print_items(&[new_task], false);
+            if !cmd.quiet {
+                display::print_bold("Inserted Task:");
+                display::print_items(&[new_task], false);
+            }
             Ok(())
         }
         Err(_) => match timestr::parse_recurring_timestr(&target_timestr) {
+            Ok(_) if cmd.status.is_some() => {
+                Err("Cannot set status for recurring tasks".to_string())
+            }
+            Ok(_) if do_date.is_some() => {
+                Err("Cannot set a do date for recurring tasks".to_string())
+            }
             Ok(cron_schedule) => {
-                let new_recurring_task =
+                let mut new_recurring_task =
                     Item::create_recurring_task(category, content, cron_schedule, target_timestr);
+                new_recurring_task.note = cmd.note.clone();
+                new_recurring_task.reference = cmd.reference.clone();
+                new_recurring_task.estimate_minutes = cmd.estimate;
+                new_recurring_task.remind_before_minutes = cmd.remind;
                 insert_item(conn, &new_recurring_task).map_err(|e| e.to_string())?;
 
-                display::print_bold("Inserted Recurring Task:");
-                display::print_items(&[new_recurring_task], false);
+                if !cmd.quiet {
+                    display::print_bold("Inserted Recurring Task:");
+                    display::print_items(&[new_recurring_task], false);
+                }
                 Ok(())
             }
             Err(_) => Err(format!(
@@ -55,24 +177,60 @@ pub fn handle_taskcmd(conn: &Connection, cmd: &TaskCommand) -> Result<(), String
     }
 }
 
-pub fn handle_recordcmd(conn: &Connection, cmd: &RecordCommand) -> Result<(), String> {
-    let content = cmd.content.clone();
+// The next occurrence of the cached recurring task, brought forward by the lead minutes.
+fn before_recurring_time(conn: &Connection, index: usize, lead: i64) -> Result<i64, String> {
+    validate_cache(conn)?;
+    let row_id = get_rowid_from_cache(conn, index)?;
+    let item = get_item(conn, row_id).map_err(|e| e.to_string())?;
+    if item.action != RECURRING_TASK {
+        return Err(format!(
+            "Index {} is a {}, --before-recurring needs a recurring task",
+            index, item.action
+        ));
+    }
+    let cron_schedule = item
+        .cron_schedule
+        .ok_or_else(|| format!("Recurring task at index {} has no schedule", index))?;
+    let next = cron::get_next_occurrence(&cron_schedule)?;
+    lead.checked_mul(60)
+        .and_then(|secs| next.checked_sub(secs))
+        .ok_or_else(|| format!("Lead of {} minutes is too large", lead))
+}
+
+fn add_record(
+    conn: &Connection,
+    cmd: &RecordCommand,
+    category_defaults: &HashMap<String, String>,
+    max_content_length: usize,
+) -> Result<(), String> {
+    let content = cmd.content.clone().ok_or("Record content is required")?;
+    let content = limit_content(content, max_content_length, cmd.truncate)?;
     let category: String = cmd
         .category
         .clone()
         .unwrap_or_else(|| "default".to_string());
-    let new_record = match &cmd.timestr {
+    let mut new_record = match resolve_timestr(&cmd.timestr, &category, category_defaults) {
         Some(t) => {
-            let create_time = timestr::to_unix_epoch(t)?;
+            let create_time = timestr::to_unix_epoch(&t)?;
+            if !cmd.force && create_time > Local::now().timestamp() + FUTURE_RECORD_LIMIT {
+                return Err(format!(
+                    "Record time '{}' is more than a year ahead, likely a typo; pass --force to record it anyway",
+                    t
+                ));
+            }
             Item::with_create_time(RECORD.to_string(), category, content, create_time)
         }
         None => Item::new(RECORD.to_string(), category, content),
     };
+    new_record.note = cmd.note.clone();
+    new_record.reference = cmd.reference.clone();
 
     insert_item(conn, &new_record).map_err(|e| e.to_string())?;
 
-    display::print_bold("Inserted Record:");
-    display::print_items(&[new_record], false);
+    if !cmd.quiet {
+        display::print_bold("Inserted Record:");
+        display::print_items(&[new_record], false);
+    }
     Ok(())
 }
 
@@ -81,13 +239,18 @@ mod tests {
     use super::*;
     use crate::{
         db::{
-            crud::query_items,
-            item::{
-                ItemQuery,
-                RECURRING_TASK,
+            cache,
+            crud::{
+                count_items,
+                query_items,
             },
+            item::ItemQuery,
+        },
+        tests::{
+            get_test_conn,
+            insert_recurring_task,
+            insert_task,
         },
-        tests::get_test_conn,
     };
 
     #[test]
@@ -96,6 +259,16 @@ mod tests {
             content: String::from("complete testing of addition.rs"),
             category: None,
             timestr: None,
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
         };
         let (conn, _temp_file) = get_test_conn();
         handle_taskcmd(&conn, &tc).unwrap();
@@ -112,6 +285,16 @@ mod tests {
             content: String::from("complete testing of addition.rs"),
             category: Some("fun".to_string()),
             timestr: Some("tomorrow".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
         };
         let (conn, _temp_file) = get_test_conn();
         handle_taskcmd(&conn, &tc).unwrap();
@@ -134,9 +317,15 @@ mod tests {
     #[test]
     fn test_record() {
         let rc = RecordCommand {
-            content: String::from("100ML"),
+            content: Some(String::from("100ML")),
             category: Some("feeding".to_string()),
             timestr: None,
+            note: None,
+            reference: None,
+            quiet: false,
+            truncate: false,
+            goal: None,
+            force: false,
         };
         let (conn, _temp_file) = get_test_conn();
         handle_recordcmd(&conn, &rc).unwrap();
@@ -161,6 +350,16 @@ mod tests {
             content: String::from("Daily standup"),
             category: Some("work".to_string()),
             timestr: Some("Daily 9AM".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
         };
         handle_taskcmd(&conn, &daily).unwrap();
 
@@ -168,6 +367,16 @@ mod tests {
             content: String::from("Weekly meeting"),
             category: Some("meetings".to_string()),
             timestr: Some("Weekly Monday-Friday 2PM".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
         };
         handle_taskcmd(&conn, &weekly).unwrap();
 
@@ -175,6 +384,16 @@ mod tests {
             content: String::from("Monthly review"),
             category: Some("admin".to_string()),
             timestr: Some("Monthly 1st".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
         };
         handle_taskcmd(&conn, &monthly).unwrap();
 
@@ -206,6 +425,16 @@ mod tests {
             content: String::from("Finish report"),
             category: Some("work".to_string()),
             timestr: Some("tomorrow".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
         };
         handle_taskcmd(&conn, &regular_task).unwrap();
 
@@ -213,6 +442,16 @@ mod tests {
             content: String::from("Check emails"),
             category: Some("work".to_string()),
             timestr: Some("Daily 9AM".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
         };
         handle_taskcmd(&conn, &recurring_task).unwrap();
 
@@ -226,15 +465,477 @@ mod tests {
         assert_eq!(recurring_items[0].content, "Check emails");
     }
 
+    #[test]
+    fn test_category_default_time() {
+        let (conn, _temp_file) = get_test_conn();
+        let defaults = HashMap::from([
+            ("gym".to_string(), "today 6PM".to_string()),
+            ("feeding".to_string(), "yesterday 10PM".to_string()),
+        ]);
+
+        let gym = TaskCommand {
+            content: String::from("leg day"),
+            category: Some("gym".to_string()),
+            timestr: None,
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        add_task(&conn, &gym, &defaults, 10000).unwrap();
+        let explicit = TaskCommand {
+            content: String::from("cardio"),
+            category: Some("gym".to_string()),
+            timestr: Some("tomorrow".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        add_task(&conn, &explicit, &defaults, 10000).unwrap();
+        let other = TaskCommand {
+            content: String::from("write report"),
+            category: Some("work".to_string()),
+            timestr: None,
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        add_task(&conn, &other, &defaults, 10000).unwrap();
+
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].target_time, Some(timestr::to_unix_epoch("today 6PM").unwrap()));
+        assert_eq!(tasks[1].target_time, Some(timestr::to_unix_epoch("tomorrow").unwrap()));
+        assert_eq!(tasks[2].target_time, Some(timestr::to_unix_epoch("today").unwrap()));
+
+        let feeding = RecordCommand {
+            content: Some(String::from("100ML")),
+            category: Some("feeding".to_string()),
+            timestr: None,
+            note: None,
+            reference: None,
+            quiet: false,
+            truncate: false,
+            goal: None,
+            force: false,
+        };
+        add_record(&conn, &feeding, &defaults, 10000).unwrap();
+        let note = RecordCommand {
+            content: Some(String::from("standup notes")),
+            category: Some("work".to_string()),
+            timestr: None,
+            note: None,
+            reference: None,
+            quiet: false,
+            truncate: false,
+            goal: None,
+            force: false,
+        };
+        add_record(&conn, &note, &defaults, 10000).unwrap();
+
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].create_time,
+            timestr::to_unix_epoch("yesterday 10PM").unwrap()
+        );
+        // No category default, so the record is created now
+        assert!((chrono::Local::now().timestamp() - records[1].create_time).abs() < 60);
+    }
+
+    #[test]
+    fn test_note() {
+        let (conn, _temp_file) = get_test_conn();
+        let tc = TaskCommand {
+            content: String::from("Plan offsite"),
+            category: Some("work".to_string()),
+            timestr: None,
+            status: None,
+            note: Some("book venue\nsend invites".to_string()),
+            reference: None,
+            estimate: None,
+            quiet: true,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        handle_taskcmd(&conn, &tc).unwrap();
+        let rc = RecordCommand {
+            content: Some(String::from("Offsite planned")),
+            category: Some("work".to_string()),
+            timestr: None,
+            note: None,
+            reference: None,
+            quiet: true,
+            truncate: false,
+            goal: None,
+            force: false,
+        };
+        handle_recordcmd(&conn, &rc).unwrap();
+
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks[0].content, "Plan offsite");
+        assert_eq!(tasks[0].note, Some("book venue\nsend invites".to_string()));
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap();
+        assert!(records[0].note.is_none());
+    }
+
+    #[test]
+    fn test_future_record_needs_force() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut rc = RecordCommand {
+            content: Some(String::from("Ran 5k")),
+            category: Some("gym".to_string()),
+            timestr: Some("2099/01/01".to_string()),
+            note: None,
+            reference: None,
+            quiet: true,
+            truncate: false,
+            goal: None,
+            force: false,
+        };
+        let err = handle_recordcmd(&conn, &rc).unwrap_err();
+        assert!(err.contains("more than a year ahead"), "{}", err);
+        assert_eq!(count_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap(), 0);
+
+        // Backdating and the near future need no --force
+        for timestr in ["yesterday", "tomorrow"] {
+            rc.timestr = Some(timestr.to_string());
+            handle_recordcmd(&conn, &rc).unwrap();
+        }
+
+        rc.timestr = Some("2099/01/01".to_string());
+        rc.force = true;
+        handle_recordcmd(&conn, &rc).unwrap();
+        assert_eq!(count_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_reference() {
+        let (conn, _temp_file) = get_test_conn();
+        let tc = TaskCommand {
+            content: String::from("Fix login bug"),
+            category: Some("work".to_string()),
+            timestr: None,
+            status: None,
+            note: None,
+            reference: Some("https://example.com/issues/42".to_string()),
+            estimate: None,
+            quiet: true,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        handle_taskcmd(&conn, &tc).unwrap();
+        let rc = RecordCommand {
+            content: Some(String::from("Reviewed design doc")),
+            category: Some("work".to_string()),
+            timestr: None,
+            note: None,
+            reference: Some("DOC-7".to_string()),
+            quiet: true,
+            truncate: false,
+            goal: None,
+            force: false,
+        };
+        handle_recordcmd(&conn, &rc).unwrap();
+
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks[0].content, "Fix login bug");
+        assert_eq!(tasks[0].reference, Some("https://example.com/issues/42".to_string()));
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap();
+        assert_eq!(records[0].reference, Some("DOC-7".to_string()));
+    }
+
+    #[test]
+    fn test_estimate() {
+        let (conn, _temp_file) = get_test_conn();
+        let tc = TaskCommand {
+            content: String::from("Write report"),
+            category: None,
+            timestr: None,
+            status: None,
+            note: None,
+            reference: None,
+            estimate: Some(90),
+            quiet: true,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        handle_taskcmd(&conn, &tc).unwrap();
+        let daily = TaskCommand {
+            content: String::from("Daily standup"),
+            timestr: Some("Daily 9AM".to_string()),
+            estimate: Some(15),
+            ..tc
+        };
+        handle_taskcmd(&conn, &daily).unwrap();
+
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks[0].estimate_minutes, Some(90));
+        let recurring = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK)).unwrap();
+        assert_eq!(recurring[0].estimate_minutes, Some(15));
+    }
+
+    #[test]
+    fn test_do_date() {
+        let (conn, _temp_file) = get_test_conn();
+        let tc = TaskCommand {
+            content: String::from("File taxes"),
+            category: None,
+            timestr: Some("2099/04/15".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: true,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: Some("today".to_string()),
+        };
+        handle_taskcmd(&conn, &tc).unwrap();
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks[0].do_date, Some(timestr::to_unix_epoch("today").unwrap()));
+        assert_eq!(tasks[0].target_time, Some(timestr::to_unix_epoch("2099/04/15").unwrap()));
+
+        let daily = TaskCommand {
+            timestr: Some("Daily 9AM".to_string()),
+            ..tc
+        };
+        let err = handle_taskcmd(&conn, &daily).unwrap_err();
+        assert!(err.contains("Cannot set a do date"), "{}", err);
+    }
+
+    #[test]
+    fn test_remind() {
+        let (conn, _temp_file) = get_test_conn();
+        let tc = TaskCommand {
+            content: String::from("Renew passport"),
+            category: None,
+            timestr: Some("2099/04/15".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: true,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: Some(24 * 60),
+            do_date: None,
+        };
+        handle_taskcmd(&conn, &tc).unwrap();
+        let daily = TaskCommand {
+            content: String::from("Water plants"),
+            timestr: Some("Daily 9AM".to_string()),
+            remind: Some(30),
+            ..tc
+        };
+        handle_taskcmd(&conn, &daily).unwrap();
+
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks[0].remind_before_minutes, Some(24 * 60));
+        let recurring = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK)).unwrap();
+        assert_eq!(recurring[0].remind_before_minutes, Some(30));
+    }
+
+    #[test]
+    fn test_task_with_status() {
+        let (conn, _temp_file) = get_test_conn();
+        let tc = TaskCommand {
+            content: String::from("Renew passport"),
+            category: None,
+            timestr: Some("eom".to_string()),
+            status: Some(6),
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: true,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        handle_taskcmd(&conn, &tc).unwrap();
+        let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].status, 6);
+
+        let mut tc = tc;
+        for aggregate in [253, 254, 255] {
+            tc.status = Some(aggregate);
+            let result = handle_taskcmd(&conn, &tc);
+            assert!(result.unwrap_err().contains("concrete status"));
+        }
+
+        let tc = TaskCommand {
+            content: String::from("Daily standup"),
+            category: None,
+            timestr: Some("Daily 9AM".to_string()),
+            status: Some(4),
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: true,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        let result = handle_taskcmd(&conn, &tc);
+        assert_eq!(result.unwrap_err(), "Cannot set status for recurring tasks");
+        let items = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK)).unwrap();
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn test_limit_content() {
+        assert_eq!(limit_content("abcde".to_string(), 5, false).unwrap(), "abcde");
+        let err = limit_content("abcdef".to_string(), 5, false).unwrap_err();
+        assert_eq!(
+            err,
+            "Content is 6 characters, over the limit of 5; shorten it, pass --truncate, or raise max_content_length in config"
+        );
+        assert_eq!(limit_content("abcdef".to_string(), 5, true).unwrap(), "abcde");
+        // Counted and cut by characters, not bytes
+        assert_eq!(limit_content("ééééé".to_string(), 5, false).unwrap(), "ééééé");
+        assert_eq!(limit_content("éééééé".to_string(), 5, true).unwrap(), "ééééé");
+    }
+
+    #[test]
+    fn test_content_length_on_insert() {
+        let (conn, _temp_file) = get_test_conn();
+        let defaults = HashMap::new();
+        let mut tc = TaskCommand {
+            content: "x".repeat(11),
+            category: None,
+            timestr: None,
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: true,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
+        };
+        let result = add_task(&conn, &tc, &defaults, 10);
+        assert!(result.unwrap_err().contains("over the limit of 10"));
+        assert!(query_items(&conn, &ItemQuery::new()).unwrap().is_empty());
+
+        tc.truncate = true;
+        add_task(&conn, &tc, &defaults, 10).unwrap();
+        let rc = RecordCommand {
+            content: Some("y".repeat(10)),
+            category: None,
+            timestr: None,
+            note: None,
+            reference: None,
+            quiet: true,
+            truncate: false,
+            goal: None,
+            force: false,
+        };
+        add_record(&conn, &rc, &defaults, 10).unwrap();
+
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks[0].content, "x".repeat(10));
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap();
+        assert_eq!(records[0].content, "y".repeat(10));
+    }
+
     #[test]
     fn test_invalid_timestr() {
         let tc = TaskCommand {
             content: String::from("Task"),
             category: None,
             timestr: Some("InvalidTimestr".to_string()),
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: false,
+            truncate: false,
+            before_recurring: None,
+            lead: None,
+            remind: None,
+            do_date: None,
         };
         let (conn, _temp_file) = get_test_conn();
         let result = handle_taskcmd(&conn, &tc);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_before_recurring() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        insert_task(&conn, "work", "finish report", "tomorrow");
+        let mut items = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK)).unwrap();
+        items.extend(query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap());
+        cache::store(&conn, &items).unwrap();
+
+        let mut tc = TaskCommand {
+            content: String::from("prepare standup notes"),
+            category: Some("work".to_string()),
+            timestr: None,
+            status: None,
+            note: None,
+            reference: None,
+            estimate: None,
+            quiet: true,
+            truncate: false,
+            before_recurring: Some(1),
+            lead: Some(30),
+            remind: None,
+            do_date: None,
+        };
+        handle_taskcmd(&conn, &tc).unwrap();
+        let next_standup = cron::get_next_occurrence("0 9 * * *").unwrap();
+        let prep = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        let prep = prep.iter().find(|t| t.content == "prepare standup notes").unwrap();
+        assert_eq!(prep.target_time, Some(next_standup - 30 * 60));
+
+        tc.lead = Some(i64::MAX / 2);
+        let result = handle_taskcmd(&conn, &tc);
+        assert!(result.unwrap_err().contains("is too large"));
+
+        tc.lead = Some(30);
+        tc.before_recurring = Some(2);
+        let result = handle_taskcmd(&conn, &tc);
+        assert!(result.unwrap_err().contains("--before-recurring needs a recurring task"));
+    }
 }
@@ -4,10 +4,32 @@ mod table;
 
 pub use crate::actions::display::{
     print::{
+        color_enabled,
         print_bold,
         print_items,
+        print_items_with_type,
+        print_records_by_day,
+        print_records_with_gaps,
         print_red,
+        print_tasks_by_day,
+        print_task_list,
+        set_color,
+    },
+    row::{
+        format_countdown,
+        format_gap,
+        set_date_format,
+        set_show_seconds,
+        translate_status,
+        DateFormat,
+        DisplayRow,
+    },
+    table::{
+        is_plain,
+        print_table,
+        set_plain,
+        set_table_width,
+        table_width,
+        MIN_TABLE_WIDTH,
     },
-    row::DisplayRow,
-    table::print_table,
 };
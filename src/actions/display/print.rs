@@ -1,11 +1,33 @@
+use std::{
+    io::{
+        self,
+        IsTerminal,
+    },
+    sync::OnceLock,
+};
+
+use chrono::NaiveDate;
+
 use crate::{
     actions::display::{
+        format_countdown,
+        format_gap,
+        is_plain,
         print_table,
+        row::timestamp_to_display_string,
         DisplayRow,
     },
-    db::item::Item,
+    args::timestr,
+    db::item::{
+        Item,
+        CLOSED_STATUS_CODES,
+        RECORD,
+        RECURRING_TASK_RECORD,
+    },
 };
 
+static COLOR: OnceLock<Option<bool>> = OnceLock::new();
+
 // For quick debug purposes
 #[allow(dead_code)]
 pub fn debug_print_items(header: &str, items: &[Item]) {
@@ -15,12 +37,47 @@ pub fn debug_print_items(header: &str, items: &[Item]) {
     }
 }
 
+// Headers, summaries and notices, left out of --plain output so only rows remain.
 pub fn print_bold(text: &str) {
-    println!("\x1b[1m{}\x1b[0m", text);
+    if is_plain() {
+        return;
+    }
+    if color_enabled() {
+        println!("\x1b[1m{}\x1b[0m", text);
+    } else {
+        println!("{}", text);
+    }
 }
 
 pub fn print_red(text: &str) {
-    println!("\x1b[91m{}\x1b[0m", text);
+    if color_enabled() {
+        println!("\x1b[91m{}\x1b[0m", text);
+    } else {
+        println!("{}", text);
+    }
+}
+
+// Set once from --color, auto leaves it to NO_COLOR and the terminal.
+pub fn set_color(mode: &str) {
+    let forced = match mode {
+        "always" => Some(true),
+        "never" => Some(false),
+        _ => None,
+    };
+    let _ = COLOR.set(forced);
+}
+
+pub fn color_enabled() -> bool {
+    resolve_color(
+        COLOR.get().copied().flatten(),
+        std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty()),
+        io::stdout().is_terminal(),
+    )
+}
+
+// --color always|never, then NO_COLOR (https://no-color.org), then whether stdout is a terminal.
+fn resolve_color(forced: Option<bool>, no_color: bool, is_terminal: bool) -> bool {
+    forced.unwrap_or(!no_color && is_terminal)
 }
 
 // print items in a table.
@@ -31,11 +88,16 @@ pub fn print_items(items: &[Item], is_list: bool) {
     let has_records = items.iter().any(|i| i.action == "record" || i.action == "recurring_task_record");
     let has_tasks = items.iter().any(|i| i.action == "task" || i.action == "recurring_task");
 
-    for (index, item) in items.iter().enumerate() {
-        let indexstr = if is_list {
-            format!("{}", index + 1)
-        } else {
+    // Rows without an id, like expanded recurring occurrences, are not indexed
+    let mut index = 0;
+    for item in items {
+        let indexstr = if !is_list {
             "N/A".to_string()
+        } else if item.id.is_none() {
+            "-".to_string()
+        } else {
+            index += 1;
+            format!("{}", index)
         };
         // Check each item's actual type instead of using a global boolean
         let item_is_record = item.action == "record" || item.action == "recurring_task_record";
@@ -57,3 +119,188 @@ pub fn print_items(items: &[Item], is_list: bool) {
 
     print_table(&results, time_header);
 }
+
+// print items of any type with a column telling tasks and records apart.
+pub fn print_items_with_type(items: &[Item]) {
+    let results: Vec<DisplayRow> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let indexstr = format!("{}", index + 1);
+            let is_record = item.action == RECORD || item.action == RECURRING_TASK_RECORD;
+            let mut row = if is_record {
+                DisplayRow::from_record(indexstr, item)
+            } else {
+                DisplayRow::from_task(indexstr, item)
+            };
+            let kind = if is_record { "record" } else { "task" };
+            row.kind = Some(kind.to_string());
+            row
+        })
+        .collect();
+    print_table(&results, "Time");
+}
+
+// print records with the time since the previous record in the same category,
+// gaps line up with records, None for the first record of a category.
+pub fn print_records_with_gaps(records: &[Item], gaps: &[Option<i64>]) {
+    let results: Vec<DisplayRow> = records
+        .iter()
+        .zip(gaps)
+        .enumerate()
+        .map(|(index, (record, gap))| {
+            let mut row = DisplayRow::from_record(format!("{}", index + 1), record);
+            if let Some(gap) = gap {
+                row.timestr.push_str(&format!(" ({})", format_gap(*gap)));
+            }
+            row
+        })
+        .collect();
+    print_table(&results, "Created At");
+}
+
+// print tasks with the extras of list task: the time each closed task was closed
+// after its status, and a countdown from now to the deadline of open tasks.
+pub fn print_task_list(tasks: &[Item], show_closed: bool, countdown_from: Option<i64>) {
+    let mut index = 0;
+    let results: Vec<DisplayRow> = tasks
+        .iter()
+        .map(|task| {
+            let indexstr = if task.id.is_none() {
+                "-".to_string()
+            } else {
+                index += 1;
+                format!("{}", index)
+            };
+            let mut row = DisplayRow::from_task(indexstr, task);
+            if let Some(closed_time) = task.closed_time.filter(|_| show_closed) {
+                row.timestr
+                    .push_str(&format!(" closed {}", timestamp_to_display_string(closed_time, true)));
+            }
+            row.countdown = countdown_from.map(|now| task_countdown(task, now));
+            row
+        })
+        .collect();
+    print_table(&results, "Deadline");
+}
+
+// Empty for closed tasks and for recurring tasks listed by their schedule.
+fn task_countdown(task: &Item, now: i64) -> String {
+    match task.target_time {
+        Some(target_time) if !CLOSED_STATUS_CODES.contains(&task.status) => {
+            format_countdown(target_time - now)
+        }
+        _ => String::new(),
+    }
+}
+
+// print records in a section per local calendar day, each under a bold header
+// with the day's count. Indices run across sections to match the cache.
+pub fn print_records_by_day(records: &[Item]) {
+    let mut start = 0;
+    while start < records.len() {
+        let day = local_date(records[start].create_time);
+        let len = records[start..]
+            .iter()
+            .take_while(|r| local_date(r.create_time) == day)
+            .count();
+        let noun = if len == 1 { "record" } else { "records" };
+        let header = match day {
+            Some(day) => day.format("%Y-%m-%d %a").to_string(),
+            None => "Invalid date".to_string(),
+        };
+        print_bold(&format!("{} ({} {})", header, len, noun));
+        let results: Vec<DisplayRow> = records[start..start + len]
+            .iter()
+            .enumerate()
+            .map(|(offset, record)| {
+                DisplayRow::from_record(format!("{}", start + offset + 1), record)
+            })
+            .collect();
+        print_table(&results, "Created At");
+        start += len;
+    }
+}
+
+// print tasks under a bold header for every local day from first_day to last_day,
+// including days without tasks. Tasks are in due order, indices run across days.
+pub fn print_tasks_by_day(tasks: &[Item], first_day: NaiveDate, last_day: NaiveDate) {
+    let mut index = 0;
+    let mut start = 0;
+    for day in first_day.iter_days().take_while(|d| *d <= last_day) {
+        let len = tasks[start..]
+            .iter()
+            .take_while(|t| t.target_time.and_then(local_date) == Some(day))
+            .count();
+        let header = day.format("%Y-%m-%d %a");
+        if len == 0 {
+            print_bold(&header.to_string());
+            println!("  No tasks");
+            continue;
+        }
+        let noun = if len == 1 { "task" } else { "tasks" };
+        print_bold(&format!("{} ({} {})", header, len, noun));
+        let results: Vec<DisplayRow> = tasks[start..start + len]
+            .iter()
+            .map(|task| {
+                let indexstr = if task.id.is_none() {
+                    "-".to_string()
+                } else {
+                    index += 1;
+                    format!("{}", index)
+                };
+                DisplayRow::from_task(indexstr, task)
+            })
+            .collect();
+        print_table(&results, "Deadline");
+        start += len;
+    }
+}
+
+fn local_date(timestamp: i64) -> Option<NaiveDate> {
+    timestr::timestamp_to_naive(timestamp).map(|dt| dt.date())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_countdown() {
+        let now = 1_750_000_000;
+        let mut task = Item::with_target_time(
+            "task".to_string(),
+            "work".to_string(),
+            "report".to_string(),
+            Some(now - 2 * 86400 - 3 * 3600),
+        );
+        assert_eq!(task_countdown(&task, now), "-2d3h");
+        task.target_time = Some(now + 3 * 3600 + 15 * 60);
+        assert_eq!(task_countdown(&task, now), "+3h15m");
+        task.status = 6;
+        assert_eq!(task_countdown(&task, now), "+3h15m");
+        task.status = 1;
+        assert_eq!(task_countdown(&task, now), "");
+
+        let recurring = Item::create_recurring_task(
+            "life".to_string(),
+            "diary".to_string(),
+            "0 21 * * *".to_string(),
+            "Daily 9PM".to_string(),
+        );
+        assert_eq!(task_countdown(&recurring, now), "");
+    }
+
+    #[test]
+    fn test_resolve_color() {
+        // always and never win over everything
+        for (no_color, is_terminal) in [(false, false), (true, true), (true, false), (false, true)] {
+            assert!(resolve_color(Some(true), no_color, is_terminal));
+            assert!(!resolve_color(Some(false), no_color, is_terminal));
+        }
+        // auto colors a terminal unless NO_COLOR is set
+        assert!(resolve_color(None, false, true));
+        assert!(!resolve_color(None, true, true));
+        assert!(!resolve_color(None, false, false));
+    }
+}
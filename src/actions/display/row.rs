@@ -1,18 +1,80 @@
+use std::{
+    fmt,
+    sync::OnceLock,
+};
+
 use chrono::{
+    format::{
+        Item as StrftimeItem,
+        StrftimeItems,
+    },
+    DateTime,
     Datelike,
     Local,
     TimeZone,
     Timelike,
+    Utc,
     Weekday,
 };
 
-use crate::db::item::Item;
+use crate::{
+    args::timestr::format_duration_minutes,
+    config::get_config,
+    db::item::Item,
+};
+
+const ISO_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+static DATE_FORMAT: OnceLock<DateFormat> = OnceLock::new();
+static SHOW_SECONDS: OnceLock<bool> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateFormat {
+    Human,
+    Iso,
+    Custom(String),
+}
+
+impl DateFormat {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "human" => return Ok(DateFormat::Human),
+            "iso" => return Ok(DateFormat::Iso),
+            _ => {}
+        }
+        // Require a specifier so a misspelled keyword is not printed literally
+        if !s.contains('%') || StrftimeItems::new(s).any(|i| matches!(i, StrftimeItem::Error)) {
+            return Err(format!(
+                "Invalid date format '{}', expected human, iso or a strftime format like '%Y-%m-%d %H:%M'",
+                s
+            ));
+        }
+        Ok(DateFormat::Custom(s.to_string()))
+    }
+}
+
+// Only the first call takes effect, it is set once at startup.
+pub fn set_date_format(format: DateFormat) {
+    let _ = DATE_FORMAT.set(format);
+}
+
+// Same as above, seconds only affect the human date format.
+pub fn set_show_seconds(show_seconds: bool) {
+    let _ = SHOW_SECONDS.set(show_seconds);
+}
 
 pub struct DisplayRow {
     pub index: String,
     pub category: String,
     pub content: String,
     pub timestr: String,
+    pub completed: bool,
+    /// ANSI color code of the category cell, from config.
+    pub category_color: Option<u8>,
+    /// task or record, only set when listing mixed items.
+    pub kind: Option<String>,
+    /// time left until the deadline, only set by list task --countdown.
+    pub countdown: Option<String>,
 }
 
 impl DisplayRow {
@@ -22,12 +84,23 @@ impl DisplayRow {
 
         let mut timestr = if task.action == "recurring_task" {
             category.push_str(" (Recurring)");
-            task.human_schedule
-                .clone()
-                .unwrap_or_else(|| "No schedule".to_string())
+            match task.target_time {
+                // An expanded occurrence of the schedule
+                Some(target_time) => timestamp_to_display_string(target_time, false),
+                None => task
+                    .human_schedule
+                    .clone()
+                    .unwrap_or_else(|| "No schedule".to_string()),
+            }
         } else {
-            timestamp_to_display_string(task.target_time.unwrap(), false)
+            match task.target_time {
+                Some(target_time) => timestamp_to_display_string(target_time, false),
+                None => "No deadline".to_string(),
+            }
         };
+        if task.pinned {
+            category.push_str(" [pin]");
+        }
 
         if task.status != 0 {
             let status_str = translate_status(task.status);
@@ -35,12 +108,19 @@ impl DisplayRow {
         } else if task.recurring_interval_complete {
             timestr.push_str(" (fulfilled)");
         }
+        if let Some(minutes) = task.estimate_minutes {
+            timestr.push_str(&format!(" [~{}]", format_duration_minutes(minutes)));
+        }
 
         DisplayRow {
             index,
             category,
             content,
             timestr,
+            completed: task.status == 1 || task.recurring_interval_complete,
+            category_color: get_config().category_color(&task.category),
+            kind: None,
+            countdown: None,
         }
     }
 
@@ -57,17 +137,48 @@ impl DisplayRow {
             category,
             content,
             timestr,
+            completed: false,
+            category_color: get_config().category_color(&record.category),
+            kind: None,
+            countdown: None,
         }
     }
 }
 
-fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
-    let dt = match Local.timestamp_opt(timestamp, 0) {
+pub(super) fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
+    let format = DATE_FORMAT.get().unwrap_or(&DateFormat::Human);
+    let show_seconds = *SHOW_SECONDS.get().unwrap_or(&false);
+    match get_config().timezone() {
+        Some(tz) => format_timestamp(&tz, timestamp, is_record, format, show_seconds),
+        None => format_timestamp(&Local, timestamp, is_record, format, show_seconds),
+    }
+}
+
+fn format_timestamp<T: TimeZone>(
+    tz: &T,
+    timestamp: i64,
+    is_record: bool,
+    format: &DateFormat,
+    show_seconds: bool,
+) -> String
+where
+    T::Offset: fmt::Display,
+{
+    let dt = match tz.timestamp_opt(timestamp, 0) {
         chrono::LocalResult::Single(dt) => dt,
         _ => return "Invalid timestamp".to_string(),
     };
 
-    let now = Local::now();
+    match format {
+        DateFormat::Human => humanize(dt, is_record, show_seconds),
+        DateFormat::Iso => dt.format(ISO_FORMAT).to_string(),
+        DateFormat::Custom(f) => dt.format(f).to_string(),
+    }
+}
+
+fn humanize<T: TimeZone>(dt: DateTime<T>, is_record: bool, show_seconds: bool) -> String {
+    let now = Utc::now().with_timezone(&dt.timezone());
+    let second = show_seconds.then(|| dt.second());
     let is_end_of_day = dt.hour() == 23 && dt.minute() == 59 && dt.second() == 59;
 
     // If timestamp is today, use "Today" string.
@@ -75,7 +186,7 @@ fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
         if is_end_of_day {
             return "Today".to_string();
         } else {
-            let hour_format = format_hour(dt.hour(), dt.minute());
+            let hour_format = format_hour(dt.hour(), dt.minute(), second);
             return format!("Today {}", hour_format);
         }
     }
@@ -87,7 +198,7 @@ fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
             dt.year(),
             dt.month(),
             dt.day(),
-            format_hour(dt.hour(), dt.minute())
+            format_hour(dt.hour(), dt.minute(), second)
         );
     }
 
@@ -108,7 +219,7 @@ fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
         if is_end_of_day {
             return "Tomorrow".to_string();
         } else {
-            let hour_format = format_hour(dt.hour(), dt.minute());
+            let hour_format = format_hour(dt.hour(), dt.minute(), second);
             return format!("Tomorrow {}", hour_format);
         }
     }
@@ -125,15 +236,15 @@ fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
             Weekday::Sun => "Sunday",
         };
 
-        // Check if it's next week (different week number)
-        let dt_week = dt.iso_week().week();
-        let now_week = now.iso_week().week();
-        let prefix = if dt_week != now_week { "Next " } else { "" };
+        let week_start = get_config().week_start();
+        let is_next_week = dt.date_naive().week(week_start).first_day()
+            != now.date_naive().week(week_start).first_day();
+        let prefix = if is_next_week { "Next " } else { "" };
 
         if is_end_of_day {
             return format!("{}{}", prefix, weekday);
         } else {
-            let hour_format = format_hour(dt.hour(), dt.minute());
+            let hour_format = format_hour(dt.hour(), dt.minute(), second);
             return format!("{}{} {}", prefix, weekday, hour_format);
         }
     }
@@ -142,12 +253,12 @@ fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
     if is_end_of_day {
         format!("{}/{}", dt.month(), dt.day())
     } else {
-        let hour_format = format_hour(dt.hour(), dt.minute());
+        let hour_format = format_hour(dt.hour(), dt.minute(), second);
         format!("{}/{} {}", dt.month(), dt.day(), hour_format)
     }
 }
 
-fn format_hour(hour: u32, minute: u32) -> String {
+fn format_hour(hour: u32, minute: u32, second: Option<u32>) -> String {
     let hour12 = if hour == 0 {
         12
     } else if hour > 12 {
@@ -156,10 +267,40 @@ fn format_hour(hour: u32, minute: u32) -> String {
         hour
     };
     let period = if hour < 12 { "AM" } else { "PM" };
-    format!("{}:{:02}{}", hour12, minute, period)
+    match second {
+        Some(second) => format!("{}:{:02}:{:02}{}", hour12, minute, second, period),
+        None => format!("{}:{:02}{}", hour12, minute, period),
+    }
+}
+
+// Time since a previous record, like +3h12m, days are kept for long gaps.
+pub fn format_gap(seconds: i64) -> String {
+    format!("+{}", format_span(seconds.max(0)))
+}
+
+// Time left until a deadline, like +3h12m, or -2d5h once it has passed.
+pub fn format_countdown(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_span(seconds.abs()))
 }
 
-fn translate_status(status: u8) -> String {
+fn format_span(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d{}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h{}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+// The label from status_labels in config, or the built-in name.
+pub fn translate_status(status: u8) -> String {
+    if let Some(label) = get_config().status_label(status) {
+        return label.to_string();
+    }
     match status {
         0 => "ongoing".to_string(),
         1 => "completed".to_string(),
@@ -168,6 +309,102 @@ fn translate_status(status: u8) -> String {
         4 => "suspended".to_string(),
         5 => "removed".to_string(),
         6 => "pending".to_string(),
+        7 => "in progress".to_string(),
         _ => "unknown".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn timestamp(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        let naive = NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap();
+        Local.from_local_datetime(&naive).unwrap().timestamp()
+    }
+
+    #[test]
+    fn test_parse_date_format() {
+        assert_eq!(DateFormat::parse("human").unwrap(), DateFormat::Human);
+        assert_eq!(DateFormat::parse("ISO").unwrap(), DateFormat::Iso);
+        assert_eq!(
+            DateFormat::parse("%d.%m.%Y").unwrap(),
+            DateFormat::Custom("%d.%m.%Y".to_string())
+        );
+        assert!(DateFormat::parse("isoo").is_err());
+        assert!(DateFormat::parse("%Y-%Q").is_err());
+    }
+
+    #[test]
+    fn test_status_display() {
+        let mut task = Item::with_target_time(
+            "task".to_string(),
+            "work".to_string(),
+            "write report".to_string(),
+            Some(timestamp(2030, 1, 15, 9, 0)),
+        );
+        task.status = 7;
+        let row = DisplayRow::from_task("1".to_string(), &task);
+        assert!(row.timestr.ends_with(" (in progress)"), "{}", row.timestr);
+        assert_eq!(translate_status(6), "pending");
+        assert_eq!(translate_status(8), "unknown");
+    }
+
+    #[test]
+    fn test_format_gap() {
+        assert_eq!(format_gap(45), "+0m");
+        assert_eq!(format_gap(25 * 60), "+25m");
+        assert_eq!(format_gap(3 * 3600 + 12 * 60 + 30), "+3h12m");
+        assert_eq!(format_gap(2 * 86400 + 5 * 3600 + 59 * 60), "+2d5h");
+        assert_eq!(format_gap(-60), "+0m");
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(3 * 3600 + 30), "+3h0m");
+        assert_eq!(format_countdown(-(2 * 86400 + 3600)), "-2d1h");
+        assert_eq!(format_countdown(-90), "-1m");
+        assert_eq!(format_countdown(0), "+0m");
+    }
+
+    #[test]
+    fn test_iso_format() {
+        let ts = timestamp(2030, 1, 15, 14, 30);
+        assert_eq!(format_timestamp(&Local, ts, false, &DateFormat::Iso, false), "2030-01-15T14:30:00");
+        assert_eq!(format_timestamp(&Local, ts, true, &DateFormat::Iso, false), "2030-01-15T14:30:00");
+    }
+
+    #[test]
+    fn test_custom_format() {
+        let ts = timestamp(2030, 1, 15, 9, 5);
+        let format = DateFormat::Custom("%d.%m.%Y %H:%M".to_string());
+        assert_eq!(format_timestamp(&Local, ts, false, &format, false), "15.01.2030 09:05");
+        // Human format is left unchanged
+        assert_eq!(format_timestamp(&Local, ts, true, &DateFormat::Human, false), "2030/1/15 9:05AM");
+    }
+
+    #[test]
+    fn test_timezone_display() {
+        let ts = Utc.with_ymd_and_hms(2030, 1, 15, 5, 30, 0).unwrap().timestamp();
+        let tokyo = chrono_tz::Tz::Asia__Tokyo;
+        assert_eq!(format_timestamp(&tokyo, ts, true, &DateFormat::Iso, false), "2030-01-15T14:30:00");
+        assert_eq!(format_timestamp(&Utc, ts, true, &DateFormat::Iso, false), "2030-01-15T05:30:00");
+        assert_eq!(format_timestamp(&tokyo, ts, true, &DateFormat::Human, false), "2030/1/15 2:30PM");
+        let format = DateFormat::Custom("%H:%M %Z".to_string());
+        assert_eq!(format_timestamp(&tokyo, ts, false, &format, false), "14:30 JST");
+    }
+
+    #[test]
+    fn test_show_seconds() {
+        let ts = timestamp(2030, 1, 15, 9, 5);
+        assert_eq!(format_timestamp(&Local, ts, true, &DateFormat::Human, false), "2030/1/15 9:05AM");
+        assert_eq!(format_timestamp(&Local, ts + 1, true, &DateFormat::Human, false), "2030/1/15 9:05AM");
+        assert_eq!(format_timestamp(&Local, ts, true, &DateFormat::Human, true), "2030/1/15 9:05:00AM");
+        assert_eq!(format_timestamp(&Local, ts + 1, true, &DateFormat::Human, true), "2030/1/15 9:05:01AM");
+    }
+}
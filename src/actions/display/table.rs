@@ -1,4 +1,7 @@
-use std::cmp;
+use std::{
+    cmp,
+    sync::OnceLock,
+};
 
 use terminal_size::{
     terminal_size,
@@ -9,36 +12,96 @@ use unicode_width::{
     UnicodeWidthStr,
 };
 
-use crate::actions::display::DisplayRow;
+use crate::actions::display::{
+    color_enabled,
+    DisplayRow,
+};
+
+const INDEX_WIDTH: usize = 7;
+const CATEGORY_WIDTH: usize = 20;
+const TIMESTR_WIDTH: usize = 20;
+const MARGIN: usize = 10;
+const KIND_WIDTH: usize = 8 + 2;
+const COUNTDOWN_WIDTH: usize = 8 + 2;
+// Narrowest width that still leaves a content column, with the type and countdown columns shown.
+pub const MIN_TABLE_WIDTH: usize =
+    INDEX_WIDTH + KIND_WIDTH + CATEGORY_WIDTH + TIMESTR_WIDTH + COUNTDOWN_WIDTH + 5 + MARGIN + 1;
 
-pub fn print_table(rows: &[DisplayRow], time_header: &str) {
-    let terminal_width = if let Some((Width(w), _)) = terminal_size() {
+static TABLE_WIDTH: OnceLock<usize> = OnceLock::new();
+static PLAIN: OnceLock<bool> = OnceLock::new();
+
+// Set once from --width, before any table is printed.
+pub fn set_table_width(width: usize) {
+    let _ = TABLE_WIDTH.set(width);
+}
+
+// Set once from --plain, rows are then printed tab separated without borders.
+pub fn set_plain(plain: bool) {
+    let _ = PLAIN.set(plain);
+}
+
+pub fn is_plain() -> bool {
+    *PLAIN.get().unwrap_or(&false)
+}
+
+// --width, then COLUMNS, then the detected terminal width.
+pub fn table_width() -> usize {
+    if let Some(width) = TABLE_WIDTH.get() {
+        return *width;
+    }
+    if let Some(width) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|w| *w >= MIN_TABLE_WIDTH)
+    {
+        return width;
+    }
+    if let Some((Width(w), _)) = terminal_size() {
         w as usize
     } else {
         120 // Default if unable to detect
-    };
+    }
+}
+
+pub fn print_table(rows: &[DisplayRow], time_header: &str) {
+    if is_plain() {
+        for row in rows {
+            println!("{}", plain_row(row));
+        }
+        return;
+    }
+    let terminal_width = table_width();
 
     // Define column widths
-    let index_width = 7;
-    let category_width = 20;
-    let timestr_width = 20;
-    let margin = 10;
+    let index_width = INDEX_WIDTH;
+    let category_width = CATEGORY_WIDTH;
+    let timestr_width = TIMESTR_WIDTH;
+    let margin = MARGIN;
+    // Type column is only shown when rows carry a kind, with its own delimiter.
+    let show_kind = rows.iter().any(|r| r.kind.is_some());
+    let kind_width = if show_kind { KIND_WIDTH } else { 0 };
+    // Same for the countdown column, after the time column.
+    let show_countdown = rows.iter().any(|r| r.countdown.is_some());
+    let countdown_width = if show_countdown { COUNTDOWN_WIDTH } else { 0 };
 
     // Calculate content width
     // Total used: column widths + 5 delimiters (|) + margin
-    let content_width =
-        terminal_width.saturating_sub(index_width + category_width + timestr_width + 5 + margin);
+    let content_width = terminal_width.saturating_sub(
+        index_width + kind_width + category_width + timestr_width + countdown_width + 5 + margin,
+    );
 
     let separator_width = terminal_width - margin + 4;
 
     // Print table header
     println!("{:-<width$}", "", width = separator_width);
     println!(
-        "| {:<index_width$}| {:<category_width$}| {:<content_width$}| {:<timestr_width$}|",
+        "| {:<index_width$}| {}{:<category_width$}| {:<content_width$}| {:<timestr_width$}|{}",
         "Index",
+        kind_cell("Type", show_kind),
         "Category",
         "Content",
         time_header,
+        countdown_cell("Left", show_countdown),
         index_width = index_width,
         category_width = category_width,
         content_width = content_width,
@@ -47,6 +110,8 @@ pub fn print_table(rows: &[DisplayRow], time_header: &str) {
     println!("{:-<width$}", "", width = separator_width);
 
     for row in rows {
+        let kind = row.kind.as_deref().unwrap_or("");
+        let countdown = row.countdown.as_deref().unwrap_or("");
         let wrapped_index = wrap(&row.index, index_width);
         let wrapped_category = wrap(&row.category, category_width);
         let wrapped_content = wrap(&row.content, content_width);
@@ -80,12 +145,25 @@ pub fn print_table(rows: &[DisplayRow], time_header: &str) {
                 ""
             };
 
+            let content_cell = if row.completed && color_enabled() {
+                strikethrough(content_line, content_width)
+            } else {
+                pad_string(content_line, content_width)
+            };
+
+            let category_cell = match row.category_color {
+                Some(code) if color_enabled() => colorize(category_line, category_width, code),
+                _ => pad_string(category_line, category_width),
+            };
+
             println!(
-                "| {}| {}| {}| {}|",
+                "| {}| {}{}| {}| {}|{}",
                 pad_string(index_line, index_width),
-                pad_string(category_line, category_width),
-                pad_string(content_line, content_width),
-                pad_string(timestr_line, timestr_width)
+                kind_cell(if i == 0 { kind } else { "" }, show_kind),
+                category_cell,
+                content_cell,
+                pad_string(timestr_line, timestr_width),
+                countdown_cell(if i == 0 { countdown } else { "" }, show_countdown)
             );
         }
 
@@ -94,6 +172,64 @@ pub fn print_table(rows: &[DisplayRow], time_header: &str) {
     }
 }
 
+// index, type when present, category, content, timestr and countdown when present joined by tabs,
+// with tabs, newlines and backslashes in the fields escaped.
+fn plain_row(row: &DisplayRow) -> String {
+    let mut fields = vec![row.index.as_str()];
+    fields.extend(row.kind.as_deref());
+    fields.extend([row.category.as_str(), row.content.as_str(), row.timestr.as_str()]);
+    fields.extend(row.countdown.as_deref());
+    fields
+        .iter()
+        .map(|f| escape_field(f))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+fn escape_field(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+// The optional countdown column, including its delimiter.
+fn countdown_cell(countdown: &str, show_countdown: bool) -> String {
+    if show_countdown {
+        format!(" {}|", pad_string(countdown, COUNTDOWN_WIDTH - 2))
+    } else {
+        String::new()
+    }
+}
+
+// The optional type column, including its delimiter.
+fn kind_cell(kind: &str, show_kind: bool) -> String {
+    if show_kind {
+        format!("{}| ", pad_string(kind, KIND_WIDTH - 2))
+    } else {
+        String::new()
+    }
+}
+
+// Dim and strike through the text only, leaving the padding plain.
+fn strikethrough(s: &str, width: usize) -> String {
+    let padded = pad_string(s, width);
+    if s.is_empty() {
+        return padded;
+    }
+    format!("\x1b[2;9m{}\x1b[0m{}", s, &padded[s.len()..])
+}
+
+// Color the text only, same as above.
+fn colorize(s: &str, width: usize, code: u8) -> String {
+    let padded = pad_string(s, width);
+    if s.is_empty() {
+        return padded;
+    }
+    format!("\x1b[{}m{}\x1b[0m{}", code, s, &padded[s.len()..])
+}
+
 fn pad_string(s: &str, width: usize) -> String {
     let term_width = UnicodeWidthStr::width(s);
     if term_width >= width {
@@ -189,6 +325,29 @@ fn wrap(text: &str, max_length: usize) -> Vec<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_colorize() {
+        assert_eq!(colorize("work", 8, 34), "\x1b[34mwork\x1b[0m    ");
+        assert_eq!(colorize("", 4, 34), "    ");
+    }
+
+    #[test]
+    fn test_plain_row() {
+        let mut row = DisplayRow {
+            index: "1".to_string(),
+            category: "work".to_string(),
+            content: "line one\nline\ttwo C:\\tmp".to_string(),
+            timestr: "Today".to_string(),
+            completed: false,
+            category_color: None,
+            kind: None,
+            countdown: None,
+        };
+        assert_eq!(plain_row(&row), "1\twork\tline one\\nline\\ttwo C:\\\\tmp\tToday");
+        row.kind = Some("task".to_string());
+        assert_eq!(plain_row(&row).split('\t').count(), 5);
+    }
+
     #[test]
     fn test_wrap_function() {
         let test_cases = vec![
@@ -3,34 +3,91 @@ use rusqlite::Connection;
 use crate::{
     actions::{
         addition,
+        display::{
+            self,
+            DateFormat,
+        },
         list,
         modify,
         ops,
     },
     args::parser::{
+        default_list_command,
         Action,
         CliArgs,
         ListCommand,
         OpsCommand,
     },
+    config::get_config,
+    db::debug,
 };
 
 pub fn handle_commands(conn: &Connection, args: CliArgs) -> Result<(), String> {
+    set_date_format(args.date_format.as_deref())?;
+    display::set_show_seconds(args.seconds);
+    display::set_color(&args.color);
+    if let Some(width) = args.width {
+        display::set_table_width(width);
+    }
+    display::set_plain(args.plain);
+    debug::set_verbose(args.verbose);
     match args.arguments {
         Action::Task(cmd) => addition::handle_taskcmd(conn, &cmd),
         Action::Record(cmd) => addition::handle_recordcmd(conn, &cmd),
         Action::Done(cmd) => modify::handle_donecmd(conn, &cmd),
+        Action::Cancel(cmd) => modify::handle_cancelcmd(conn, &cmd),
+        Action::Start(cmd) => modify::handle_startcmd(conn, &cmd),
+        Action::Pin(cmd) => modify::handle_pincmd(conn, &cmd, true),
+        Action::Unpin(cmd) => modify::handle_pincmd(conn, &cmd, false),
         Action::Delete(cmd) => modify::handle_deletecmd(conn, &cmd),
         Action::Update(cmd) => modify::handle_updatecmd(conn, &cmd),
-        Action::List(list_cmd) => match list_cmd {
-            ListCommand::Task(cmd) => list::handle_listtasks(conn, cmd),
-            ListCommand::Record(cmd) => list::handle_listrecords(conn, cmd),
-            ListCommand::Show(cmd) => list::handle_showcontent(conn, cmd),
-        },
+        Action::List(list_args) => {
+            let list_cmd = match list_args.command {
+                Some(cmd) => cmd,
+                None => default_list_command(get_config().default_list())?,
+            };
+            match list_cmd {
+                ListCommand::Task(cmd) => list::handle_listtasks(conn, cmd),
+                ListCommand::Record(cmd) => list::handle_listrecords(conn, cmd),
+                ListCommand::Done(cmd) => list::handle_listdone(conn, cmd),
+                ListCommand::Show(cmd) => list::handle_showcontent(conn, cmd),
+            }
+        }
+        Action::Recent(cmd) => list::handle_recent(conn, &cmd),
+        Action::CountDue => list::handle_countdue(conn),
         Action::Ops(ops_cmd) => match ops_cmd {
             OpsCommand::Stat(cmd) => ops::handle_statcmd(conn, &cmd),
             OpsCommand::Batch(cmd) => ops::handle_batchcmd(conn, &cmd),
             OpsCommand::Backup(cmd) => ops::handle_backupcmd(&cmd),
+            OpsCommand::Sed(cmd) => ops::handle_sedcmd(conn, &cmd),
+            OpsCommand::Review(cmd) => ops::handle_reviewcmd(conn, &cmd),
+            OpsCommand::CronCheck(cmd) => ops::handle_croncheckcmd(&cmd),
+            OpsCommand::Export(cmd) => ops::handle_exportcmd(conn, &cmd),
+            OpsCommand::Import(cmd) => ops::handle_importcmd(conn, &cmd),
+            OpsCommand::Dump(cmd) => ops::handle_dumpcmd(conn, &cmd),
+            OpsCommand::Load(cmd) => ops::handle_loadcmd(conn, &cmd),
+            OpsCommand::Migrate => ops::handle_migratecmd(conn),
+            OpsCommand::RescheduleOverdue(cmd) => ops::handle_rescheduleoverduecmd(conn, &cmd),
+            OpsCommand::Carryover(cmd) => ops::handle_carryovercmd(conn, &cmd),
+            OpsCommand::Remind(cmd) => ops::handle_remindcmd(conn, &cmd),
+            OpsCommand::PurgeCache => ops::handle_purgecachecmd(conn),
+            OpsCommand::Dedupe(cmd) => ops::handle_dedupecmd(conn, &cmd),
+            OpsCommand::On(cmd) => ops::handle_oncmd(conn, &cmd),
+            OpsCommand::Vacuum => ops::handle_vacuumcmd(conn),
+            OpsCommand::Gaps(cmd) => ops::handle_gapscmd(conn, &cmd),
+            OpsCommand::Recat => ops::handle_recatcmd(conn),
         },
     }
 }
+
+// The command line flag takes precedence over config.
+// An invalid config format was already reset to human when loading.
+fn set_date_format(flag: Option<&str>) -> Result<(), String> {
+    let config_format = &get_config().date_format;
+    let format = match flag {
+        Some(f) => DateFormat::parse(f)?,
+        None => DateFormat::parse(config_format).unwrap_or(DateFormat::Human),
+    };
+    display::set_date_format(format);
+    Ok(())
+}
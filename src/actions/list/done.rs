@@ -0,0 +1,115 @@
+use rusqlite::Connection;
+
+use super::COMPLETED_PREFIXES;
+use crate::{
+    actions::display,
+    args::{
+        parser::ListDoneCommand,
+        timestr,
+    },
+    db::{
+        cache,
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+            RECURRING_TASK_RECORD,
+        },
+    },
+};
+
+pub fn handle_listdone(conn: &Connection, cmd: ListDoneCommand) -> Result<(), String> {
+    let records = query_completed(conn, &cmd)?;
+    if records.is_empty() {
+        display::print_bold("No completed tasks found");
+        return Ok(());
+    }
+
+    if !cmd.no_cache {
+        cache::clear(conn).map_err(|e| e.to_string())?;
+        cache::store(conn, &records).map_err(|e| e.to_string())?;
+    }
+
+    display::print_bold("Recently Completed:");
+    display::print_items(&records, true);
+    Ok(())
+}
+
+/// Completion records created by `done` within the last `days`, newest first.
+fn query_completed(conn: &Connection, cmd: &ListDoneCommand) -> Result<Vec<Item>, String> {
+    let cutoff_timestamp = if cmd.calendar_days {
+        timestr::calendar_days_before_to_unix_epoch(cmd.days)?
+    } else {
+        timestr::days_before_to_unix_epoch(cmd.days)?
+    };
+    let mut record_query = ItemQuery::new()
+        .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+        .with_content_like("Completed")
+        .with_create_time_min(cutoff_timestamp);
+    if let Some(cat) = &cmd.category {
+        record_query = record_query.with_category(cat);
+    }
+
+    let mut records: Vec<Item> = query_items(conn, &record_query)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|item| COMPLETED_PREFIXES.iter().any(|p| item.content.starts_with(p)))
+        .collect();
+    records.sort_by(|a, b| b.create_time.cmp(&a.create_time).then(b.id.cmp(&a.id)));
+    records.truncate(cmd.limit);
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_record,
+        insert_recurring_record,
+        insert_recurring_task,
+    };
+
+    #[test]
+    fn test_query_completed() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "work", "Completed Task: old report", "yesterday 9AM");
+        insert_record(&conn, "work", "Completed Task: new report", "yesterday 10AM");
+        insert_record(&conn, "work", "Task Completed manually", "yesterday 11AM");
+        insert_record(&conn, "home", "Completed Task: dishes", "yesterday 12PM");
+        insert_record(&conn, "home", "Completed Task: too old", "2020/01/01");
+        let task_id = insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        insert_recurring_record(&conn, "work", "Completed Recurring Task: standup", task_id, 1000);
+
+        let cmd = ListDoneCommand {
+            days: 7,
+            calendar_days: false,
+            category: None,
+            limit: 100,
+            no_cache: false,
+        };
+        let results = query_completed(&conn, &cmd).unwrap();
+        let contents: Vec<&str> = results.iter().map(|i| i.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            vec![
+                "Completed Recurring Task: standup",
+                "Completed Task: dishes",
+                "Completed Task: new report",
+                "Completed Task: old report",
+            ]
+        );
+
+        let cmd = ListDoneCommand {
+            days: 7,
+            calendar_days: false,
+            category: Some("work".to_string()),
+            limit: 2,
+            no_cache: false,
+        };
+        let results = query_completed(&conn, &cmd).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].content, "Completed Task: new report");
+    }
+}
@@ -1,17 +1,30 @@
+mod done;
+mod recent;
 mod records;
 mod tasks;
 
+pub use done::handle_listdone;
+pub use recent::handle_recent;
 pub use records::handle_listrecords;
+use nanoserde::SerJson;
 use rusqlite::Connection;
+pub use tasks::handle_countdue;
 pub use tasks::handle_listtasks;
 pub use tasks::query_all_tasks;
+pub(crate) use tasks::mark_recurring_task_by_completion;
 
 use crate::{
-    args::parser::ShowContentCommand,
+    actions::display,
+    args::{
+        parser::ShowContentCommand,
+        timestr,
+    },
     db::{
         cache,
         crud::get_item,
         item::{
+            tag_list,
+            Item,
             Offset,
             RECORD,
             RECURRING_TASK,
@@ -24,8 +37,12 @@ use crate::{
 // Shared constants
 pub(crate) const CREATE_TIME_COL: &str = "create_time";
 pub(crate) const TARGET_TIME_COL: &str = "target_time";
-pub(crate) const OPEN_STATUS_CODES: &[u8] = &[0, 4, 6];
-pub(crate) const CLOSED_STATUS_CODES: &[u8] = &[1, 2, 3, 5];
+pub(crate) use crate::db::item::{
+    CLOSED_STATUS_CODES,
+    OPEN_STATUS_CODES,
+};
+// Content prefixes of the records done creates
+pub(crate) const COMPLETED_PREFIXES: &[&str] = &["Completed Task:", "Completed Recurring Task:"];
 
 // Shared function for showing content
 pub fn handle_showcontent(conn: &Connection, cmd: ShowContentCommand) -> Result<(), String> {
@@ -44,10 +61,89 @@ pub fn handle_showcontent(conn: &Connection, cmd: ShowContentCommand) -> Result<
     };
 
     let item = get_item(conn, item_id).map_err(|e| e.to_string())?;
-    println!("{}", item.content);
+    if cmd.json {
+        println!("{}", item.serialize_json());
+    } else if cmd.full {
+        println!("{}", format_item_details(&item));
+    } else {
+        println!("{}", item.content);
+    }
     Ok(())
 }
 
+fn format_item_details(item: &Item) -> String {
+    let format_time = |t: i64| match timestr::timestamp_to_naive(t) {
+        Some(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => "Invalid timestamp".to_string(),
+    };
+
+    let mut fields = vec![
+        ("Id", item.id.map_or_else(|| "N/A".to_string(), |id| id.to_string())),
+        ("Action", item.action.clone()),
+        ("Category", item.category.clone()),
+        ("Source", item.source.clone()),
+    ];
+    // Status only has meaning for regular tasks
+    if item.action == TASK {
+        fields.push(("Status", display::translate_status(item.status)));
+    }
+    fields.push(("Created", format_time(item.create_time)));
+    if let Some(modify_time) = item.modify_time {
+        fields.push(("Modified", format_time(modify_time)));
+    }
+    if let Some(target_time) = item.target_time {
+        fields.push(("Due", format_time(target_time)));
+    }
+    if let Some(do_date) = item.do_date {
+        fields.push(("Do", format_time(do_date)));
+    }
+    if let Some(closed_time) = item.closed_time {
+        fields.push(("Closed", format_time(closed_time)));
+    }
+    if let (Some(human), Some(cron)) = (&item.human_schedule, &item.cron_schedule) {
+        fields.push(("Schedule", format!("{} ({})", human, cron)));
+    }
+    if let Some(good_until) = item.good_until {
+        fields.push(("Good Until", format_time(good_until)));
+    }
+    if let Some(minutes) = item.estimate_minutes {
+        fields.push(("Estimate", timestr::format_duration_minutes(minutes)));
+    }
+    if let Some(minutes) = item.remind_before_minutes {
+        fields.push(("Remind", format!("{} before", timestr::format_duration_minutes(minutes))));
+    }
+    if let Some(reference) = &item.reference {
+        fields.push(("Reference", reference.clone()));
+    }
+    let tags = tag_list(item.tags.as_deref());
+    if !tags.is_empty() {
+        fields.push(("Tags", tags.join(", ")));
+    }
+
+    let mut lines: Vec<String> = fields
+        .into_iter()
+        .map(|(label, value)| format!("{:<11} {}", format!("{}:", label), value))
+        .collect();
+    lines.push("Content:".to_string());
+    lines.extend(item.content.lines().map(|l| format!("  {}", l)));
+    if let Some(note) = &item.note {
+        lines.push("Note:".to_string());
+        lines.extend(note.lines().map(|l| format!("  {}", l)));
+    }
+    lines.join("\n")
+}
+
+// Shared function for listing multi-line content by its first line, only for
+// display, the items are not written back.
+pub(crate) fn first_lines_only(mut items: Vec<Item>) -> Vec<Item> {
+    for item in &mut items {
+        if let Some(first_line) = item.content.lines().next() {
+            item.content = first_line.to_string();
+        }
+    }
+    items
+}
+
 // Shared function for pagination
 pub(crate) fn handle_next_page(conn: &Connection) -> Offset {
     let offset_index = match cache::get_next_index(conn) {
@@ -64,7 +160,9 @@ pub(crate) fn handle_next_page(conn: &Connection) -> Offset {
     };
     let id = end_item.id.unwrap();
     if end_item.action == TASK {
-        return Offset::TargetTime(end_item.target_time.unwrap(), id);
+        // A task without a deadline is listed before any due time
+        let target_time = end_item.target_time.unwrap_or(i64::MIN);
+        return Offset::PinnedTargetTime(end_item.pinned, target_time, id);
     } else if end_item.action == RECURRING_TASK {
         return Offset::Id(id);
     } else if end_item.action == RECORD || end_item.action == RECURRING_TASK_RECORD {
@@ -0,0 +1,66 @@
+use rusqlite::Connection;
+
+use super::CREATE_TIME_COL;
+use crate::{
+    actions::display,
+    args::parser::RecentCommand,
+    db::{
+        cache,
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+        },
+    },
+};
+
+pub fn handle_recent(conn: &Connection, cmd: &RecentCommand) -> Result<(), String> {
+    let items = query_recent(conn, cmd.count)?;
+    if items.is_empty() {
+        display::print_bold("No items found");
+        return Ok(());
+    }
+
+    cache::clear(conn).map_err(|e| e.to_string())?;
+    cache::store(conn, &items).map_err(|e| e.to_string())?;
+
+    display::print_bold("Recently Created:");
+    display::print_items_with_type(&items);
+    Ok(())
+}
+
+/// The last `count` items created, of any type, newest first.
+fn query_recent(conn: &Connection, count: usize) -> Result<Vec<Item>, String> {
+    let query = ItemQuery::new()
+        .with_order_by(CREATE_TIME_COL)
+        .with_order_desc()
+        .with_limit(count);
+    query_items(conn, &query).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_record,
+        insert_task,
+    };
+
+    #[test]
+    fn test_query_recent() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "feeding", "100ML", "2025/02/23 2PM");
+        insert_record(&conn, "feeding", "110ML", "2025/02/23 5PM");
+        insert_task(&conn, "work", "write report", "tomorrow");
+
+        let items = query_recent(&conn, 2).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "write report");
+        assert_eq!(items[1].content, "110ML");
+
+        let items = query_recent(&conn, 10).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[2].content, "100ML");
+    }
+}
@@ -1,30 +1,57 @@
+use std::collections::HashMap;
+
 use rusqlite::Connection;
 
 use super::{
+    first_lines_only,
     handle_next_page,
     CREATE_TIME_COL,
 };
 use crate::{
-    actions::display,
+    actions::{
+        display,
+        modify::{
+            get_rowid_from_cache,
+            validate_cache,
+        },
+    },
     args::{
         parser::ListRecordCommand,
         timestr,
     },
     db::{
         cache,
-        crud::query_items,
+        crud::{
+            count_items,
+            get_item,
+            query_items,
+        },
+        goal,
         item::{
             Item,
             ItemQuery,
             Offset,
             RECORD,
+            RECURRING_TASK,
             RECURRING_TASK_RECORD,
         },
     },
 };
 
+const PROGRESS_BAR_WIDTH: usize = 20;
+
 pub fn handle_listrecords(conn: &Connection, cmd: ListRecordCommand) -> Result<(), String> {
-    let records = match query_records(conn, &cmd) {
+    // clap requires a category with --today-progress
+    if cmd.today_progress
+        && let Some(category) = &cmd.category
+    {
+        return print_today_progress(conn, category);
+    }
+    let recurring_task_id = match cmd.recurring {
+        Some(index) => Some(recurring_task_id(conn, index)?),
+        None => None,
+    };
+    let records = match query_records(conn, &cmd, recurring_task_id) {
         Ok(records) => records,
         Err(estr) => {
             display::print_bold(&estr);
@@ -36,33 +63,134 @@ pub fn handle_listrecords(conn: &Connection, cmd: ListRecordCommand) -> Result<(
         return Ok(());
     }
 
-    cache::clear(conn).map_err(|e| e.to_string())?;
-    if records.len() == cmd.limit {
-        cache::store_with_next(conn, &records)
-    } else {
-        cache::store(conn, &records)
+    if !cmd.no_cache {
+        cache::clear(conn).map_err(|e| e.to_string())?;
+        if records.len() == cmd.limit {
+            cache::store_with_next(conn, &records)
+        } else {
+            cache::store(conn, &records)
+        }
+        .map_err(|e| e.to_string())?;
     }
-    .map_err(|e| e.to_string())?;
 
+    let records = if cmd.titles_only {
+        first_lines_only(records)
+    } else {
+        records
+    };
     display::print_bold("Records List:");
-    display::print_items(&records, true);
+    if cmd.gaps {
+        display::print_records_with_gaps(&records, &record_gaps(&records));
+    } else if cmd.by_day {
+        display::print_records_by_day(&records);
+    } else {
+        display::print_items(&records, true);
+    }
     Ok(())
 }
 
-fn query_records(conn: &Connection, cmd: &ListRecordCommand) -> Result<Vec<Item>, String> {
-    let mut record_query = ItemQuery::new().with_actions(vec![RECORD, RECURRING_TASK_RECORD]);
+fn print_today_progress(conn: &Connection, category: &str) -> Result<(), String> {
+    let target = goal::get(conn, category)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| {
+            format!(
+                "No daily goal for '{}', set one with: tascli record -c {} --goal <count>",
+                category, category
+            )
+        })?;
+    let count = count_today(conn, category)?;
+    let progress = format!("{}: {}", category, format_progress(count, target));
+    // The progress is all there is to print, so --plain keeps it
+    if display::is_plain() {
+        println!("{}", progress);
+    } else {
+        display::print_bold(&progress);
+    }
+    Ok(())
+}
+
+fn count_today(conn: &Connection, category: &str) -> Result<usize, String> {
+    let (start, end) = timestr::day_bounds_to_unix_epoch("today")?;
+    let query = ItemQuery::new()
+        .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+        .with_category(category)
+        .with_create_time_range(Some(start - 1), Some(end));
+    count_items(conn, &query).map_err(|e| e.to_string())
+}
+
+// Like "5/8 today [############--------]", the bar stays full past the goal.
+fn format_progress(count: usize, target: usize) -> String {
+    let filled = count.min(target) * PROGRESS_BAR_WIDTH / target;
+    format!(
+        "{}/{} today [{}{}]",
+        count,
+        target,
+        "#".repeat(filled),
+        "-".repeat(PROGRESS_BAR_WIDTH - filled)
+    )
+}
+
+// Seconds since the previous record of the same category, records are ordered by create_time.
+// The first record of each category in the listing has no gap.
+fn record_gaps(records: &[Item]) -> Vec<Option<i64>> {
+    let mut last_seen: HashMap<&str, i64> = HashMap::new();
+    records
+        .iter()
+        .map(|record| {
+            last_seen
+                .insert(record.category.as_str(), record.create_time)
+                .map(|previous| record.create_time - previous)
+        })
+        .collect()
+}
+
+// The recurring task at an index of the previous listing, whose records list record --recurring shows.
+fn recurring_task_id(conn: &Connection, index: usize) -> Result<i64, String> {
+    validate_cache(conn)?;
+    let row_id = get_rowid_from_cache(conn, index)?;
+    let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {}", e))?;
+    if item.action != RECURRING_TASK {
+        return Err(format!("--recurring needs a recurring task, found {}", item.action));
+    }
+    Ok(row_id)
+}
+
+// Records of a recurring task are created in the order of the iterations they fulfil,
+// so create_time ordering also orders them by good_until.
+fn query_records(
+    conn: &Connection,
+    cmd: &ListRecordCommand,
+    recurring_task_id: Option<i64>,
+) -> Result<Vec<Item>, String> {
+    let mut record_query = match recurring_task_id {
+        Some(id) => ItemQuery::new()
+            .with_action(RECURRING_TASK_RECORD)
+            .with_recurring_task_id(id),
+        None => ItemQuery::new().with_actions(vec![RECORD, RECURRING_TASK_RECORD]),
+    };
     if let Some(cat) = &cmd.category {
         record_query = record_query.with_category(cat);
     }
     if let Some(search_term) = &cmd.search {
-        record_query = record_query.with_content_like(search_term);
+        record_query = if cmd.exact {
+            record_query.with_content_exact(search_term)
+        } else {
+            record_query.with_content_like(search_term)
+        };
+    }
+    if let Some(source) = &cmd.source {
+        record_query = record_query.with_source(source);
     }
     if let Some(days) = cmd.days {
-        let cutoff_timestamp = timestr::days_before_to_unix_epoch(days);
+        let cutoff_timestamp = if cmd.calendar_days {
+            timestr::calendar_days_before_to_unix_epoch(days)?
+        } else {
+            timestr::days_before_to_unix_epoch(days)?
+        };
         record_query = record_query.with_create_time_min(cutoff_timestamp);
     }
     if let Some(starting_time) = &cmd.starting_time {
-        let starting_timestamp = timestr::to_unix_epoch(starting_time)?;
+        let starting_timestamp = timestr::range_start_to_unix_epoch(starting_time)?;
         record_query = record_query.with_create_time_min(starting_timestamp);
     }
     if let Some(ending_time) = &cmd.ending_time {
@@ -93,6 +221,7 @@ mod tests {
         insert_record,
         insert_recurring_record,
         insert_recurring_task,
+        insert_task,
     };
 
     impl ListRecordCommand {
@@ -100,11 +229,20 @@ mod tests {
             ListRecordCommand {
                 category: None,
                 days: None,
+                calendar_days: false,
                 limit: 100,
                 starting_time: None,
                 ending_time: None,
                 next_page: false,
                 search: None,
+                exact: false,
+                gaps: false,
+                today_progress: false,
+                source: None,
+                no_cache: false,
+                by_day: false,
+                titles_only: false,
+                recurring: None,
             }
         }
 
@@ -160,17 +298,121 @@ mod tests {
         let list_timeframe_start_only =
             ListRecordCommand::default_test().with_starting_time("yesterday 8PM");
 
-        let results = query_records(&conn, &listfeeding).unwrap();
+        let results = query_records(&conn, &listfeeding, None).unwrap();
         assert_eq!(results.len(), 3);
-        let results = query_records(&conn, &list_all).unwrap();
+        let results = query_records(&conn, &list_all, None).unwrap();
         assert_eq!(results.len(), 4);
-        let results = query_records(&conn, &list_timeframe).unwrap();
+        let results = query_records(&conn, &list_timeframe, None).unwrap();
         assert_eq!(results.len(), 2);
-        let results = query_records(&conn, &list_timeframe_start_only).unwrap();
+        let results = query_records(&conn, &list_timeframe_start_only, None).unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].category, "feeding")
     }
 
+    #[test]
+    fn test_query_records_date_only_bounds() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "log", "day before", "2025/03/13 11:59PM");
+        insert_record(&conn, "log", "midnight", "2025/03/14 00:00");
+        insert_record(&conn, "log", "morning", "2025/03/14 9AM");
+        insert_record(&conn, "log", "late", "2025/03/14 11:59PM");
+        insert_record(&conn, "log", "day after", "2025/03/15 00:00");
+        let contents = |cmd: &ListRecordCommand| {
+            let records = query_records(&conn, cmd, None).unwrap();
+            records.into_iter().map(|r| r.content).collect::<Vec<_>>()
+        };
+
+        // Date only bounds take in the whole day on both ends
+        let whole_day = ListRecordCommand::default_test()
+            .with_starting_time("2025/03/14")
+            .with_ending_time("2025/03/14");
+        assert_eq!(contents(&whole_day), vec!["midnight", "morning", "late"]);
+        let from_day = ListRecordCommand::default_test().with_starting_time("2025/03/14");
+        assert_eq!(contents(&from_day), vec!["midnight", "morning", "late", "day after"]);
+
+        // A start with a time stays exclusive, an end with a time inclusive
+        let timed = ListRecordCommand::default_test()
+            .with_starting_time("2025/03/14 00:00")
+            .with_ending_time("2025/03/14 9AM");
+        assert_eq!(contents(&timed), vec!["morning"]);
+    }
+
+    #[test]
+    fn test_count_today() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "water", "glass", "today 00:00");
+        insert_record(&conn, "water", "glass", "today 00:01");
+        insert_record(&conn, "water", "glass", "yesterday 11PM");
+        insert_record(&conn, "coffee", "cup", "today 00:01");
+
+        assert_eq!(count_today(&conn, "water").unwrap(), 2);
+        assert_eq!(count_today(&conn, "coffee").unwrap(), 1);
+        assert_eq!(count_today(&conn, "steps").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_format_progress() {
+        assert_eq!(format_progress(0, 8), "0/8 today [--------------------]");
+        assert_eq!(format_progress(5, 8), "5/8 today [############--------]");
+        assert_eq!(format_progress(8, 8), "8/8 today [####################]");
+        // Going past the goal keeps the bar full
+        assert_eq!(format_progress(10, 8), "10/8 today [####################]");
+    }
+
+    #[test]
+    fn test_record_gaps() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "feeding", "100ML", "2025/02/23 2PM");
+        insert_record(&conn, "sleep", "nap", "2025/02/23 3PM");
+        insert_record(&conn, "feeding", "110ML", "2025/02/23 5:12PM");
+        insert_record(&conn, "feeding", "100ML", "2025/02/23 9PM");
+        insert_record(&conn, "sleep", "night", "2025/02/23 10:30PM");
+
+        let list_all = ListRecordCommand::default_test().with_starting_time("2025/02/22");
+        let results = query_records(&conn, &list_all, None).unwrap();
+        let gaps = record_gaps(&results);
+        assert_eq!(
+            gaps,
+            vec![None, None, Some(3 * 3600 + 12 * 60), Some(3 * 3600 + 48 * 60), Some(7 * 3600 + 30 * 60)]
+        );
+        let shown: Vec<String> = gaps.iter().flatten().map(|g| display::format_gap(*g)).collect();
+        assert_eq!(shown, vec!["+3h12m", "+3h48m", "+7h30m"]);
+    }
+
+    #[test]
+    fn test_query_records_of_recurring_task() {
+        let (conn, _temp_file) = get_test_conn();
+        let diary = insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+        let gym = insert_recurring_task(&conn, "life", "gym", "Weekly Monday 7AM");
+        let task = insert_task(&conn, "life", "renew passport", "tomorrow");
+        for day in 1..=3 {
+            insert_recurring_record(&conn, "life", "Completed Recurring Task: diary", diary, day * 86400);
+        }
+        insert_recurring_record(&conn, "life", "Completed Recurring Task: gym", gym, 86400);
+        insert_record(&conn, "life", "diary", "today");
+
+        let items: Vec<Item> = [diary, gym, task].iter().map(|id| get_item(&conn, *id).unwrap()).collect();
+        cache::store(&conn, &items).unwrap();
+
+        let cmd = ListRecordCommand::default_test();
+        let diary_id = recurring_task_id(&conn, 1).unwrap();
+        assert_eq!(diary_id, diary);
+        let records = query_records(&conn, &cmd, Some(diary_id)).unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|r| r.recurring_task_id == Some(diary)));
+        let good_until: Vec<Option<i64>> = records.iter().map(|r| r.good_until).collect();
+        assert_eq!(good_until, vec![Some(86400), Some(2 * 86400), Some(3 * 86400)]);
+
+        let records = query_records(&conn, &cmd, Some(recurring_task_id(&conn, 2).unwrap())).unwrap();
+        assert_eq!(records.len(), 1);
+
+        assert_eq!(
+            recurring_task_id(&conn, 3).unwrap_err(),
+            "--recurring needs a recurring task, found task"
+        );
+        assert_eq!(recurring_task_id(&conn, 4).unwrap_err(), "index 4 does not exist");
+    }
+
     #[test]
     fn test_query_records_with_recurring() {
         let (conn, _temp_file) = get_test_conn();
@@ -188,7 +430,7 @@ mod tests {
 
         // Query all records (should include both record and recurring_task_record)
         let list_all = ListRecordCommand::default_test().with_days(2);
-        let results = query_records(&conn, &list_all).unwrap();
+        let results = query_records(&conn, &list_all, None).unwrap();
         assert_eq!(results.len(), 6); // 3 regular records + 3 recurring records
 
         // Verify we have both action types
@@ -204,7 +446,7 @@ mod tests {
         let list_feeding = ListRecordCommand::default_test()
             .with_days(2)
             .with_category("feeding");
-        let results = query_records(&conn, &list_feeding).unwrap();
+        let results = query_records(&conn, &list_feeding, None).unwrap();
         assert_eq!(results.len(), 4); // 2 regular feeding + 2 recurring feeding
         for record in &results {
             assert_eq!(record.category, "feeding");
@@ -215,11 +457,21 @@ mod tests {
         let list_bottle = ListRecordCommand::default_test()
             .with_days(2)
             .with_search("bottle");
-        let results = query_records(&conn, &list_bottle).unwrap();
+        let results = query_records(&conn, &list_bottle, None).unwrap();
         assert_eq!(results.len(), 2); // 2 recurring records with "bottle"
         for record in &results {
             assert!(record.content.contains("bottle"));
         }
+
+        // Exact search leaves out records that only contain the text
+        insert_record(&conn, "feeding", "bottle", "today 00:01");
+        let mut exact_bottle = ListRecordCommand::default_test()
+            .with_days(2)
+            .with_search("bottle");
+        exact_bottle.exact = true;
+        let results = query_records(&conn, &exact_bottle, None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "bottle");
     }
 
     #[test]
@@ -246,20 +498,20 @@ mod tests {
             .with_starting_time("2025/02/21")
             .with_ending_time("2025/02/27");
 
-        let results = query_records(&conn, &list_record).unwrap();
+        let results = query_records(&conn, &list_record, None).unwrap();
         cache::clear(&conn).unwrap();
         cache::store_with_next(&conn, &results).unwrap();
         assert_eq!(results.len(), 11);
         assert!(results.iter().all(|i| i.content.contains("A")));
 
         let list_record_next = list_record.with_next_page();
-        let results = query_records(&conn, &list_record_next).unwrap();
+        let results = query_records(&conn, &list_record_next, None).unwrap();
         cache::clear(&conn).unwrap();
         cache::store_with_next(&conn, &results).unwrap();
         assert_eq!(results.len(), 11);
         assert!(results.iter().all(|i| i.content.contains("B")));
 
-        let results = query_records(&conn, &list_record_next).unwrap();
+        let results = query_records(&conn, &list_record_next, None).unwrap();
         cache::clear(&conn).unwrap();
         cache::store(&conn, &results).unwrap();
         assert_eq!(results.len(), 0);
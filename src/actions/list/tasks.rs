@@ -1,7 +1,13 @@
-use chrono::Local;
+use std::collections::BTreeMap;
+
+use chrono::{
+    Local,
+    NaiveDate,
+};
 use rusqlite::Connection;
 
 use super::{
+    first_lines_only,
     handle_next_page,
     CLOSED_STATUS_CODES,
     OPEN_STATUS_CODES,
@@ -14,9 +20,16 @@ use crate::{
         parser::ListTaskCommand,
         timestr,
     },
+    config::{
+        get_config,
+        Config,
+    },
     db::{
         cache,
-        crud::query_items,
+        crud::{
+            count_items,
+            query_items,
+        },
         item::{
             Item,
             ItemQuery,
@@ -25,10 +38,27 @@ use crate::{
             RECURRING_TASK_RECORD,
             TASK,
         },
+        ops::count_items_by_category,
     },
 };
 
-pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), String> {
+pub fn handle_listtasks(conn: &Connection, mut cmd: ListTaskCommand) -> Result<(), String> {
+    if cmd.histogram {
+        return print_category_histogram(conn, &cmd);
+    }
+    let stalest_first = cmd.sort == "staleness";
+    if stalest_first && !cmd.overdue && !cmd.overdue_only {
+        return Err("--sort staleness needs --overdue or --overdue-only".to_string());
+    }
+    if cmd.agenda {
+        if recurring_window(&cmd)?.1.is_none() {
+            return Err(
+                "--agenda needs a window end: timestr, --days, --due-before, --due or --until".to_string(),
+            );
+        }
+        // Recurring tasks show up on every day they occur
+        cmd.expand = true;
+    }
     let (all_tasks, recurring_hit_limit, last_queried_recurring) = match query_all_tasks(conn, &cmd)
     {
         Ok(result) => result,
@@ -40,40 +70,234 @@ pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), S
 
     if all_tasks.is_empty() {
         display::print_bold("No tasks found");
-        return Ok(());
+        return print_overdue_footer(conn, &cmd);
     }
 
-    // given we have filtering, the cache must store
-    // all items queried even if they had been filtered
-    let mut cache_items = all_tasks.clone();
-    if recurring_hit_limit {
-        if let Some(last_queried) = &last_queried_recurring {
-            if all_tasks.last().map(|t| t.id) != Some(last_queried.id) {
-                cache_items.push(last_queried.clone());
-            }
+    // given we have filtering and ordering, the cache must store
+    // the last item queried even if it is not displayed last
+    let page_anchor = if cmd.expand {
+        None
+    } else if recurring_hit_limit {
+        last_queried_recurring
+    } else {
+        all_tasks.last().cloned()
+    };
+    // An agenda stays in due order to group by day
+    let all_tasks = if cmd.agenda {
+        all_tasks
+    } else if stalest_first {
+        order_stalest_first(all_tasks, get_config().overdue_cutoff(Local::now().timestamp()))
+    } else {
+        order_pinned_first(all_tasks)
+    };
+    let all_tasks = if cmd.include_done && !cmd.agenda {
+        order_done_last(all_tasks)
+    } else {
+        all_tasks
+    };
+    // Expanded occurrence rows have no id and are left out of the index cache
+    let mut cache_items: Vec<Item> =
+        all_tasks.iter().filter(|t| t.id.is_some()).cloned().collect();
+    if let Some(anchor) = &page_anchor
+        && all_tasks.last().map(|t| t.id) != Some(anchor.id)
+    {
+        cache_items.push(anchor.clone());
+    }
+
+    if !cmd.no_cache {
+        cache::clear(conn).map_err(|e| e.to_string())?;
+        if !cmd.expand && (recurring_hit_limit || all_tasks.len() == cmd.limit) {
+            cache::store_with_next(conn, &cache_items)
+        } else {
+            cache::store(conn, &cache_items)
         }
+        .map_err(|e| e.to_string())?;
     }
 
-    cache::clear(conn).map_err(|e| e.to_string())?;
-    if recurring_hit_limit || cache_items.len() == cmd.limit {
-        cache::store_with_next(conn, &cache_items)
+    let all_tasks = if cmd.titles_only {
+        first_lines_only(all_tasks)
+    } else {
+        all_tasks
+    };
+    display::print_bold("Tasks List:");
+    if cmd.agenda {
+        let (first_day, last_day) = agenda_days(&cmd, &all_tasks)?;
+        display::print_tasks_by_day(&all_tasks, first_day, last_day);
+    } else if cmd.show_closed || cmd.countdown {
+        let countdown_from = cmd.countdown.then(|| Local::now().timestamp());
+        display::print_task_list(&all_tasks, cmd.show_closed, countdown_from);
     } else {
-        cache::store(conn, &cache_items)
+        display::print_items(&all_tasks, true);
     }
-    .map_err(|e| e.to_string())?;
+    if cmd.budget {
+        display::print_bold(&format_budget(&all_tasks)?);
+    }
+    display::print_bold(&format_status_summary(&all_tasks));
+    print_overdue_footer(conn, &cmd)
+}
 
-    display::print_bold("Tasks List:");
-    display::print_items(&all_tasks, true);
+// The days of the window, widened to any listed task outside of it like overdue ones,
+// but never past --until.
+fn agenda_days(cmd: &ListTaskCommand, tasks: &[Item]) -> Result<(NaiveDate, NaiveDate), String> {
+    let local_date = |timestamp: i64| {
+        timestr::timestamp_to_naive(timestamp)
+            .map(|dt| dt.date())
+            .ok_or_else(|| format!("Invalid timestamp: {}", timestamp))
+    };
+    // Both window bounds are exclusive
+    let (start, end) = recurring_window(cmd)?;
+    let mut first_day = match start {
+        Some(start) => local_date(start + 1)?,
+        None => timestr::today(),
+    };
+    let mut last_day = match end {
+        Some(end) => local_date(end - 1)?,
+        None => first_day,
+    };
+    for target_time in tasks.iter().filter_map(|t| t.target_time) {
+        let day = local_date(target_time)?;
+        first_day = first_day.min(day);
+        last_day = last_day.max(day);
+    }
+    if let Some(t) = &cmd.until {
+        last_day = last_day.min(local_date(timestr::to_unix_epoch(t)?)?);
+    }
+    Ok((first_day, last_day))
+}
+
+// Totals estimates of the listed tasks, tasks without one count as zero.
+fn format_budget(tasks: &[Item]) -> Result<String, String> {
+    let estimates: Vec<i64> = tasks.iter().filter_map(|t| t.estimate_minutes).collect();
+    let total = estimates
+        .iter()
+        .try_fold(0i64, |total, m| total.checked_add(*m))
+        .ok_or("Estimates of the listed tasks are too large to total")?;
+    Ok(format!(
+        "Budget: {} ({}/{} tasks estimated)",
+        timestr::format_duration_minutes(total),
+        estimates.len(),
+        tasks.len()
+    ))
+}
+
+// Counts of the listed tasks per status as their rows show it, in status code order,
+// recurring tasks done for the interval count as fulfilled after ongoing.
+fn format_status_summary(tasks: &[Item]) -> String {
+    let mut counts: BTreeMap<(u8, bool), usize> = BTreeMap::new();
+    for task in tasks {
+        let fulfilled = task.status == 0 && task.recurring_interval_complete;
+        *counts.entry((task.status, fulfilled)).or_default() += 1;
+    }
+    counts
+        .iter()
+        .map(|((status, fulfilled), count)| {
+            let label = if *fulfilled {
+                "fulfilled".to_string()
+            } else {
+                display::translate_status(*status)
+            };
+            format!("{} {}", count, label)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Open regular tasks per category, the category and search filters still apply.
+fn print_category_histogram(conn: &Connection, cmd: &ListTaskCommand) -> Result<(), String> {
+    let mut query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec());
+    if let Some(cat) = &cmd.category {
+        query = query.with_category(cat);
+    }
+    if let Some(search_term) = &cmd.search {
+        query = query.with_content_like(search_term);
+    }
+    let counts = count_items_by_category(conn, &query).map_err(|e| e.to_string())?;
+    if counts.is_empty() {
+        display::print_bold("No open tasks found");
+        return Ok(());
+    }
+    display::print_bold("Open Tasks by Category:");
+    for line in format_histogram(&counts, display::table_width()) {
+        println!("{}", line);
+    }
     Ok(())
 }
 
+// Lines like "work | ######## (8)", the largest count spans the width left after
+// the labels and counts, others are scaled to it but keep at least one mark.
+fn format_histogram(counts: &[(String, usize)], width: usize) -> Vec<String> {
+    let label_width = counts.iter().map(|(c, _)| c.chars().count()).max().unwrap_or(0);
+    let max_count = counts.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    let count_width = max_count.to_string().len() + 3;
+    let bar_width = width.saturating_sub(label_width + 3 + count_width).max(1);
+    counts
+        .iter()
+        .map(|(category, count)| {
+            let bar = (count * bar_width / max_count.max(1)).max(1);
+            format!("{:<label_width$} | {} ({})", category, "#".repeat(bar), count)
+        })
+        .collect()
+}
+
+// A single count query, printed only when something is overdue.
+fn print_overdue_footer(conn: &Connection, cmd: &ListTaskCommand) -> Result<(), String> {
+    if display::is_plain() {
+        return Ok(());
+    }
+    let overdue_cutoff = get_config().overdue_cutoff(Local::now().timestamp());
+    let count = count_overdue_tasks(conn, cmd.category.as_deref(), overdue_cutoff)?;
+    if count > 0 {
+        display::print_red(&format!("{} overdue", count));
+    }
+    Ok(())
+}
+
+fn count_overdue_tasks(
+    conn: &Connection,
+    category: Option<&str>,
+    overdue_cutoff: i64,
+) -> Result<usize, String> {
+    let mut query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_max(overdue_cutoff);
+    if let Some(cat) = category {
+        query = query.with_category(cat);
+    }
+    count_items(conn, &query).map_err(|e| e.to_string())
+}
+
+// Prints a bare number for shell prompts, with a single count query.
+pub fn handle_countdue(conn: &Connection) -> Result<(), String> {
+    let end_of_today = timestr::to_unix_epoch("today")?;
+    println!("{}", count_due_tasks(conn, end_of_today)?);
+    Ok(())
+}
+
+// Open tasks due by the given time, including overdue ones.
+fn count_due_tasks(conn: &Connection, due_by: i64) -> Result<usize, String> {
+    let query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_max(due_by);
+    count_items(conn, &query).map_err(|e| e.to_string())
+}
+
 pub fn query_all_tasks(
     conn: &Connection,
     cmd: &ListTaskCommand,
 ) -> Result<(Vec<Item>, bool, Option<Item>), String> {
-    let recurring_tasks = query_recurring_tasks(conn, cmd)?;
+    // Recurring tasks are never overdue, have no do date and always a schedule
+    let recurring_tasks = if cmd.overdue_only || cmd.do_date.is_some() || cmd.no_deadline {
+        Vec::new()
+    } else {
+        query_recurring_tasks(conn, cmd)?
+    };
 
-    let recurring_hit_limit = recurring_tasks.len() == cmd.limit;
+    // Expanded listings are not paged
+    let recurring_hit_limit = !cmd.expand && recurring_tasks.len() == cmd.limit;
     let last_queried_recurring = if recurring_hit_limit {
         recurring_tasks.last().cloned()
     } else {
@@ -82,6 +306,11 @@ pub fn query_all_tasks(
 
     // Mark completion status for all recurring tasks
     let recurring_tasks = mark_recurring_task_by_completion(conn, recurring_tasks)?;
+    let recurring_tasks = if cmd.expand {
+        expand_recurring_tasks(recurring_tasks, cmd)?
+    } else {
+        recurring_tasks
+    };
     let recurring_tasks = if cmd.status == 255 {
         recurring_tasks
     } else if cmd.status == 253 || cmd.status == 1 {
@@ -90,6 +319,8 @@ pub fn query_all_tasks(
             .into_iter()
             .filter(|t| t.recurring_interval_complete)
             .collect()
+    } else if cmd.include_done {
+        recurring_tasks
     } else {
         // All other statuses show only incomplete tasks
         recurring_tasks
@@ -97,7 +328,19 @@ pub fn query_all_tasks(
             .filter(|t| !t.recurring_interval_complete)
             .collect()
     };
-    let recurring_tasks = filter_recurring_task_by_time(recurring_tasks, cmd)?;
+    // Recurring tasks count as done for the interval or ongoing
+    let recurring_tasks = match excluded_status_codes(cmd)? {
+        Some(excluded) => recurring_tasks
+            .into_iter()
+            .filter(|t| !excluded.contains(&(t.recurring_interval_complete as u8)))
+            .collect(),
+        None => recurring_tasks,
+    };
+    let recurring_tasks = if cmd.expand {
+        recurring_tasks
+    } else {
+        filter_recurring_task_by_time(recurring_tasks, cmd)?
+    };
 
     let all_tasks = if recurring_hit_limit {
         recurring_tasks
@@ -106,6 +349,9 @@ pub fn query_all_tasks(
         let mut all_tasks = Vec::new();
         all_tasks.extend(recurring_tasks);
         all_tasks.extend(regular_tasks);
+        if cmd.expand {
+            all_tasks.sort_by_key(|t| t.target_time);
+        }
         all_tasks.truncate(cmd.limit);
         all_tasks
     };
@@ -123,6 +369,9 @@ fn query_recurring_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec
     if let Some(search_term) = &cmd.search {
         query = query.with_content_like(search_term);
     }
+    if let Some(source) = &cmd.source {
+        query = query.with_source(source);
+    }
     let mut offset = Offset::None;
     if cmd.next_page {
         offset = handle_next_page(conn);
@@ -144,33 +393,91 @@ fn query_recurring_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec
     query_items(conn, &query).map_err(|e| e.to_string())
 }
 
+// Exclusive (start, end) bounds on occurrence times, None when unbounded.
+fn recurring_window(cmd: &ListTaskCommand) -> Result<(Option<i64>, Option<i64>), String> {
+    let mut target_interval_end: Option<i64> = Option::None;
+    if let Some(t) = &cmd.timestr {
+        target_interval_end = Some(timestr::to_unix_epoch(t)?);
+    } else if let Some(days) = cmd.days {
+        target_interval_end = Some(timestr::days_after_to_unix_epoch(days)?);
+    } else if let Some(t) = &cmd.due_before {
+        target_interval_end = Some(timestr::to_unix_epoch(t)?);
+    }
+    let mut target_interval_start = match &cmd.due_after {
+        Some(t) => Some(timestr::range_start_to_unix_epoch(t)?),
+        None => None,
+    };
+    if let Some(day) = &cmd.due {
+        let (start, end) = timestr::day_bounds_to_unix_epoch(day)?;
+        target_interval_start = Some(start - 1);
+        target_interval_end = Some(end + 1);
+    }
+    if let Some(t) = &cmd.until {
+        // Occurrences at the until time itself are kept
+        let until_end = timestr::to_unix_epoch(t)? + 1;
+        target_interval_end = Some(target_interval_end.map_or(until_end, |end| end.min(until_end)));
+    }
+    Ok((target_interval_start, target_interval_end))
+}
+
 fn filter_recurring_task_by_time(
     recurring_tasks: Vec<Item>,
     cmd: &ListTaskCommand,
 ) -> Result<Vec<Item>, String> {
     let mut filtered_tasks: Vec<Item> = Vec::new();
-    let mut target_interval_end: Option<i64> = Option::None;
-    if let Some(t) = &cmd.timestr {
-        target_interval_end = Some(timestr::to_unix_epoch(t)?);
-    } else if let Some(days) = cmd.days {
-        target_interval_end = Some(timestr::days_after_to_unix_epoch(days));
-    }
-    match target_interval_end {
-        Some(et) => {
-            for recurring_task in recurring_tasks {
-                let cron_schedule = recurring_task.cron_schedule.as_ref().unwrap();
-                let next_occurrence = cron::get_next_occurrence(cron_schedule)?;
-                if next_occurrence < et {
-                    filtered_tasks.push(recurring_task);
-                }
+    let (target_interval_start, target_interval_end) = recurring_window(cmd)?;
+    if target_interval_end.is_none() && target_interval_start.is_none() {
+        return Ok(recurring_tasks);
+    }
+    for recurring_task in recurring_tasks {
+        let cron_schedule = recurring_task.cron_schedule.as_ref().unwrap();
+        let next_occurrence = cron::get_next_occurrence(cron_schedule)?;
+        if target_interval_end.is_some_and(|et| next_occurrence >= et) {
+            continue;
+        }
+        if target_interval_start.is_some_and(|st| next_occurrence <= st) {
+            continue;
+        }
+        filtered_tasks.push(recurring_task);
+    }
+    Ok(filtered_tasks)
+}
+
+// One virtual row per occurrence within the window, in place of each recurring task.
+// The rows carry no id, so they get no index and cannot be completed from the list.
+fn expand_recurring_tasks(
+    recurring_tasks: Vec<Item>,
+    cmd: &ListTaskCommand,
+) -> Result<Vec<Item>, String> {
+    let (target_interval_start, target_interval_end) = recurring_window(cmd)?;
+    let Some(target_interval_end) = target_interval_end else {
+        return Err(
+            "--expand needs a window end: timestr, --days, --due-before, --due or --until".to_string(),
+        );
+    };
+    let mut occurrence_rows: Vec<Item> = Vec::new();
+    for recurring_task in recurring_tasks {
+        let cron_schedule = recurring_task.cron_schedule.as_ref().unwrap();
+        let occurrences = cron::get_next_occurrences(cron_schedule, cmd.limit)?;
+        for (i, occurrence) in occurrences.into_iter().enumerate() {
+            if occurrence >= target_interval_end {
+                break;
+            }
+            if target_interval_start.is_some_and(|st| occurrence <= st) {
+                continue;
             }
-            Ok(filtered_tasks)
+            let mut row = recurring_task.clone();
+            row.id = None;
+            row.target_time = Some(occurrence);
+            // A completion record only covers the upcoming occurrence
+            row.recurring_interval_complete = i == 0 && recurring_task.recurring_interval_complete;
+            occurrence_rows.push(row);
         }
-        None => Ok(recurring_tasks),
     }
+    Ok(occurrence_rows)
 }
 
-fn mark_recurring_task_by_completion(
+pub(crate) fn mark_recurring_task_by_completion(
     conn: &Connection,
     mut recurring_tasks: Vec<Item>,
 ) -> Result<Vec<Item>, String> {
@@ -191,16 +498,64 @@ fn mark_recurring_task_by_completion(
 }
 
 fn query_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec<Item>, String> {
+    query_tasks_with(conn, cmd, get_config())
+}
+
+// Tasks within the configured grace after their due time are not overdue yet.
+fn query_tasks_with(
+    conn: &Connection,
+    cmd: &ListTaskCommand,
+    config: &Config,
+) -> Result<Vec<Item>, String> {
     let mut task_query = ItemQuery::new().with_action(TASK);
-    if let Some(t) = &cmd.timestr {
+    let overdue_cutoff = config.overdue_cutoff(Local::now().timestamp());
+    if cmd.no_deadline {
+        task_query = task_query.with_no_target_time();
+    } else if cmd.overdue_only {
+        task_query = task_query.with_target_time_max(overdue_cutoff);
+    } else if let Some(t) = &cmd.timestr {
         let target_time_before = timestr::to_unix_epoch(t)?;
         task_query = task_query.with_target_time_max(target_time_before);
     } else if let Some(days) = cmd.days {
-        let cutoff_timestamp = timestr::days_after_to_unix_epoch(days);
+        let cutoff_timestamp = timestr::days_after_to_unix_epoch(days)?;
         task_query = task_query.with_target_time_max(cutoff_timestamp);
+    } else if let Some(t) = &cmd.due_before {
+        let target_time_before = timestr::to_unix_epoch(t)?;
+        task_query = task_query.with_target_time_max(target_time_before);
     }
-    if !cmd.overdue {
-        task_query = task_query.with_target_time_min(Local::now().timestamp());
+    if let Some(day) = &cmd.due {
+        // The whole day, the min bound is exclusive
+        let (start, end) = timestr::day_bounds_to_unix_epoch(day)?;
+        task_query = task_query.with_target_time_range(Some(start - 1), Some(end));
+    } else if !cmd.no_deadline {
+        let due_after = match &cmd.due_after {
+            Some(t) => Some(timestr::range_start_to_unix_epoch(t)?),
+            None => None,
+        };
+        // Without overdue, the window never starts before the overdue cutoff.
+        // Tasks planned for a day are listed even when already overdue.
+        let target_time_min = if cmd.overdue || cmd.overdue_only || cmd.do_date.is_some() {
+            due_after
+        } else {
+            Some(due_after.map_or(overdue_cutoff, |t| t.max(overdue_cutoff)))
+        };
+        if let Some(min) = target_time_min {
+            task_query = task_query.with_target_time_min(min);
+            // Tasks closed today stay listed even when due before the window
+            if cmd.include_done {
+                let (start_of_today, _) = timestr::date_bounds_to_unix_epoch(timestr::today())?;
+                task_query = task_query.with_closed_since(start_of_today - 1);
+            }
+        }
+    }
+    if let Some(t) = &cmd.until {
+        let until = timestr::to_unix_epoch(t)?;
+        let max = task_query.target_time_max.map_or(until, |max| max.min(until));
+        task_query = task_query.with_target_time_max(max);
+    }
+    if let Some(day) = &cmd.do_date {
+        let (start, end) = timestr::day_bounds_to_unix_epoch(day)?;
+        task_query = task_query.with_do_date_range(Some(start - 1), Some(end));
     }
     if let Some(cat) = &cmd.category {
         task_query = task_query.with_category(cat);
@@ -208,23 +563,29 @@ fn query_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec<Item>, St
     if let Some(search_term) = &cmd.search {
         task_query = task_query.with_content_like(search_term);
     }
+    if let Some(source) = &cmd.source {
+        task_query = task_query.with_source(source);
+    }
 
-    match cmd.status {
-        // 255 status means we query all task items regardless of status.
-        255 => {}
-        // 254 status indicates a combination of statuses that are open
-        254 => task_query = task_query.with_statuses(OPEN_STATUS_CODES.to_vec()),
-        // 253 status indicates a combination of statuses that are closed
-        253 => task_query = task_query.with_statuses(CLOSED_STATUS_CODES.to_vec()),
-        // Other statuses are individual statuses for query
-        _ => task_query = task_query.with_statuses(vec![cmd.status]),
+    let mut statuses = status_codes(cmd.status);
+    if cmd.include_done
+        && let Some(statuses) = statuses.as_mut()
+        && !statuses.contains(&1)
+    {
+        statuses.push(1);
+    }
+    if let Some(statuses) = statuses {
+        task_query = task_query.with_statuses(statuses);
+    }
+    if let Some(excluded) = excluded_status_codes(cmd)? {
+        task_query = task_query.with_status_not(excluded);
     }
 
     let mut offset = Offset::None;
     if cmd.next_page {
         offset = handle_next_page(conn);
         match offset {
-            Offset::TargetTime(..) => {}
+            Offset::PinnedTargetTime(..) => {}
             Offset::Id(_) => offset = Offset::None, // Transition from recurring to regular tasks
             Offset::None => return Err("No next page available".to_string()),
             _ => return Ok(Vec::new()), // Wrong offset type, skip regular tasks query
@@ -232,19 +593,78 @@ fn query_tasks(conn: &Connection, cmd: &ListTaskCommand) -> Result<Vec<Item>, St
     }
     task_query = task_query.with_offset(offset);
     task_query = task_query.with_limit(cmd.limit);
-    task_query = task_query.with_order_by(TARGET_TIME_COL);
+    task_query = task_query.with_order_by(TARGET_TIME_COL).with_pinned_first();
     query_items(conn, &task_query).map_err(|e| e.to_string())
 }
 
+fn status_codes(status: u8) -> Option<Vec<u8>> {
+    match status {
+        // 255 status means we query all task items regardless of status.
+        255 => None,
+        // 254 status indicates a combination of statuses that are open
+        254 => Some(OPEN_STATUS_CODES.to_vec()),
+        // 253 status indicates a combination of statuses that are closed
+        253 => Some(CLOSED_STATUS_CODES.to_vec()),
+        // Other statuses are individual statuses for query
+        _ => Some(vec![status]),
+    }
+}
+
+fn excluded_status_codes(cmd: &ListTaskCommand) -> Result<Option<Vec<u8>>, String> {
+    match cmd.not_status {
+        None => Ok(None),
+        Some(status) => status_codes(status)
+            .map(Some)
+            .ok_or_else(|| "--not-status all would leave out every task".to_string()),
+    }
+}
+
+// Regular tasks come pinned first from the query, this lifts pinned recurring tasks
+// as well. Stable, so pinned and unpinned tasks each keep their due order.
+fn order_pinned_first(mut tasks: Vec<Item>) -> Vec<Item> {
+    tasks.sort_by_key(|t| !t.pinned);
+    tasks
+}
+
+// Open overdue tasks lead with the oldest deadline first, pins are not lifted,
+// the rest keep their order after them.
+fn order_stalest_first(tasks: Vec<Item>, overdue_cutoff: i64) -> Vec<Item> {
+    let (mut overdue, rest): (Vec<Item>, Vec<Item>) = tasks.into_iter().partition(|t| {
+        t.action == TASK
+            && OPEN_STATUS_CODES.contains(&t.status)
+            && t.target_time.is_some_and(|tt| tt <= overdue_cutoff)
+    });
+    overdue.sort_by_key(|t| t.target_time);
+    overdue.extend(rest);
+    overdue
+}
+
+// Stable, so open and done tasks each keep their due order.
+fn order_done_last(mut tasks: Vec<Item>) -> Vec<Item> {
+    tasks.sort_by_key(|t| t.status == 1 || t.recurring_interval_complete);
+    tasks
+}
+
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
+
     use super::*;
-    use crate::tests::{
-        get_test_conn,
-        insert_recurring_record,
-        insert_recurring_task,
-        insert_task,
-        update_status,
+    use crate::{
+        actions::display::DisplayRow,
+        db::crud::{
+            get_item,
+            insert_item,
+            update_item,
+        },
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_recurring_record,
+            insert_recurring_task,
+            insert_task,
+            update_status,
+        },
     };
 
     impl ListTaskCommand {
@@ -253,11 +673,30 @@ mod tests {
                 timestr: None,
                 category: None,
                 days: None,
+                due_after: None,
+                due_before: None,
+                due: None,
+                do_date: None,
                 status: 0,
+                not_status: None,
                 overdue: false,
+                overdue_only: false,
+                no_deadline: false,
+                include_done: false,
                 limit: 100,
                 next_page: false,
                 search: None,
+                source: None,
+                no_cache: false,
+                budget: false,
+                expand: false,
+                until: None,
+                histogram: false,
+                agenda: false,
+                titles_only: false,
+                show_closed: false,
+                countdown: false,
+                sort: "due".to_string(),
             }
         }
 
@@ -286,10 +725,32 @@ mod tests {
             self
         }
 
+        fn with_due(mut self, day: &str) -> Self {
+            self.due = Some(day.to_string());
+            self
+        }
+
+        fn with_do(mut self, day: &str) -> Self {
+            self.do_date = Some(day.to_string());
+            self
+        }
+
         fn with_search(mut self, search: &str) -> Self {
             self.search = Some(search.to_string());
             self
         }
+
+        fn with_due_window(mut self, after: Option<&str>, before: Option<&str>) -> Self {
+            self.due_after = after.map(|s| s.to_string());
+            self.due_before = before.map(|s| s.to_string());
+            self
+        }
+
+        fn with_expand(mut self, days: usize) -> Self {
+            self.days = Some(days);
+            self.expand = true;
+            self
+        }
     }
 
     #[test]
@@ -311,6 +772,155 @@ mod tests {
         assert_eq!(results.first().unwrap().content, "first_due");
     }
 
+    #[test]
+    fn test_query_tasks_no_deadline() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "due today", "today");
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+        let someday = Item::with_target_time(
+            "task".to_string(),
+            "home".to_string(),
+            "clean garage".to_string(),
+            None,
+        );
+        insert_item(&conn, &someday).unwrap();
+
+        // Not listed by default, which starts at the overdue cutoff
+        let (tasks, _, _) = query_all_tasks(&conn, &ListTaskCommand::default_test()).unwrap();
+        assert!(tasks.iter().all(|t| t.content != "clean garage"));
+
+        let mut no_deadline = ListTaskCommand::default_test();
+        no_deadline.no_deadline = true;
+        let (tasks, _, _) = query_all_tasks(&conn, &no_deadline).unwrap();
+        let contents: Vec<&str> = tasks.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, vec!["clean garage"]);
+        assert_eq!(DisplayRow::from_task("1".to_string(), &tasks[0]).timestr, "No deadline");
+    }
+
+    #[test]
+    fn test_query_tasks_due_window() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "past", "2020/01/05");
+        insert_task(&conn, "work", "before window", "2099/03/01");
+        insert_task(&conn, "work", "window start", "2099/03/02 9AM");
+        insert_task(&conn, "work", "window end", "2099/03/06");
+        insert_task(&conn, "work", "after window", "2099/03/07 9AM");
+
+        // A date only lower bound includes its whole day
+        let window = ListTaskCommand::default_test()
+            .with_due_window(Some("2099/03/02"), Some("2099/03/06"));
+        let results = query_tasks(&conn, &window).unwrap();
+        let contents: Vec<&str> = results.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, vec!["window start", "window end"]);
+
+        // Only a lower bound
+        let after_only = ListTaskCommand::default_test().with_due_window(Some("2099/03/06"), None);
+        let results = query_tasks(&conn, &after_only).unwrap();
+        let contents: Vec<&str> = results.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, vec!["window end", "after window"]);
+        // A lower bound with a time stays exclusive
+        let after_time =
+            ListTaskCommand::default_test().with_due_window(Some("2099/03/02 9AM"), Some("2099/03/06"));
+        let results = query_tasks(&conn, &after_time).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "window end");
+
+        // A window in the past is clamped to now unless overdue is requested
+        let past_window = ListTaskCommand::default_test()
+            .with_due_window(Some("2020/01/01"), Some("2020/01/31"));
+        assert!(query_tasks(&conn, &past_window).unwrap().is_empty());
+        let past_window = past_window.with_overdue(true);
+        let results = query_tasks(&conn, &past_window).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "past");
+
+        // Status filtering still applies within the window
+        let closed = ListTaskCommand::default_test()
+            .with_due_window(Some("2099/03/02"), Some("2099/03/06"))
+            .with_status(253);
+        assert!(query_tasks(&conn, &closed).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_tasks_due_on_day() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "thursday", "2099/03/05");
+        insert_task(&conn, "work", "friday midnight", "2099/03/06 00:00");
+        insert_task(&conn, "work", "friday morning", "2099/03/06 9AM");
+        insert_task(&conn, "work", "friday end", "2099/03/06");
+        insert_task(&conn, "work", "saturday", "2099/03/07 00:00");
+
+        let friday = ListTaskCommand::default_test().with_due("2099/03/06");
+        let results = query_tasks(&conn, &friday).unwrap();
+        let contents: Vec<&str> = results.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, vec!["friday midnight", "friday morning", "friday end"]);
+
+        // The whole day is shown even when part of it has passed
+        insert_task(&conn, "work", "earlier today", "today 00:00");
+        let today = ListTaskCommand::default_test().with_due("today");
+        let results = query_tasks(&conn, &today).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "earlier today");
+    }
+
+    #[test]
+    fn test_query_tasks_by_do_date() {
+        let (conn, _temp_file) = get_test_conn();
+        let plan = |id: i64, day: &str| {
+            let mut task = get_item(&conn, id).unwrap();
+            task.do_date = Some(timestr::to_unix_epoch(day).unwrap());
+            update_item(&conn, &task).unwrap();
+        };
+        let later = insert_task(&conn, "work", "due later, do today", "2099/03/06");
+        plan(later, "today");
+        let overdue = insert_task(&conn, "work", "overdue, do today", "2020/01/01");
+        plan(overdue, "today 9AM");
+        let tomorrow = insert_task(&conn, "work", "do tomorrow", "2099/03/06");
+        plan(tomorrow, "tomorrow");
+        insert_task(&conn, "work", "due today, unplanned", "today");
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+
+        let cmd = ListTaskCommand::default_test().with_do("today");
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        let mut contents: Vec<&str> = tasks.iter().map(|t| t.content.as_str()).collect();
+        contents.sort();
+        assert_eq!(contents, vec!["due later, do today", "overdue, do today"]);
+
+        // The deadline is untouched by the do date
+        let tasks = query_tasks(&conn, &ListTaskCommand::default_test().with_due("today")).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].content, "due today, unplanned");
+    }
+
+    #[test]
+    fn test_sort_staleness() {
+        let (conn, _temp_file) = get_test_conn();
+        let recent = insert_task(&conn, "work", "recently overdue", "yesterday");
+        let mut pinned = get_item(&conn, recent).unwrap();
+        pinned.pinned = true;
+        update_item(&conn, &pinned).unwrap();
+        let upcoming = insert_task(&conn, "work", "upcoming", "tomorrow");
+        let oldest = insert_task(&conn, "work", "long overdue", "2025/01/01");
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+
+        let mut cmd = ListTaskCommand::default_test().with_overdue(true);
+        cmd.sort = "staleness".to_string();
+        handle_listtasks(&conn, cmd).unwrap();
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(oldest));
+        assert_eq!(cache::read(&conn, 2).unwrap(), Some(recent));
+        let rest = [cache::read(&conn, 3).unwrap(), cache::read(&conn, 4).unwrap()];
+        assert!(rest.contains(&Some(upcoming)));
+
+        // Pinned first is the default order
+        handle_listtasks(&conn, ListTaskCommand::default_test().with_overdue(true)).unwrap();
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(recent));
+
+        let mut cmd = ListTaskCommand::default_test();
+        cmd.sort = "staleness".to_string();
+        let err = handle_listtasks(&conn, cmd).unwrap_err();
+        assert!(err.contains("needs --overdue"), "{}", err);
+    }
+
     #[test]
     fn test_query_tasks_pagination() {
         let (conn, _temp_file) = get_test_conn();
@@ -359,6 +969,196 @@ mod tests {
         assert_eq!(results.unwrap_err(), "No next page available".to_string());
     }
 
+    #[test]
+    fn test_query_all_tasks_include_done() {
+        let (conn, _temp_file) = get_test_conn();
+        // Due before the window, done today
+        let done_id = insert_task(&conn, "work", "done today", "yesterday 9am");
+        update_status(&conn, done_id, 1);
+        // Done before today
+        let done_before_id = insert_task(&conn, "work", "done yesterday", "yesterday 9am");
+        update_status(&conn, done_before_id, 1);
+        conn.execute(
+            "UPDATE items SET closed_time = ?1 WHERE id = ?2",
+            rusqlite::params![timestr::to_unix_epoch("yesterday 10am").unwrap(), done_before_id],
+        )
+        .unwrap();
+        let cancelled_id = insert_task(&conn, "work", "cancelled", "yesterday 9am");
+        update_status(&conn, cancelled_id, 2);
+        insert_task(&conn, "work", "overdue", "yesterday 9am");
+        insert_task(&conn, "work", "open later", "tomorrow");
+
+        let cmd = ListTaskCommand::default_test().with_status(254);
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        assert_eq!(tasks.len(), 1);
+
+        let cmd = ListTaskCommand {
+            include_done: true,
+            ..ListTaskCommand::default_test().with_status(254)
+        };
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        let tasks = order_done_last(tasks);
+        let contents: Vec<&str> = tasks.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, vec!["open later", "done today"]);
+    }
+
+    #[test]
+    fn test_query_all_tasks_pinned_first() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "due today", "today");
+        let later_id = insert_task(&conn, "work", "pinned later", "tomorrow +1w");
+        let sooner_id = insert_task(&conn, "work", "pinned sooner", "tomorrow");
+        for id in [later_id, sooner_id] {
+            let mut task = get_item(&conn, id).unwrap();
+            task.pinned = true;
+            update_item(&conn, &task).unwrap();
+        }
+
+        let cmd = ListTaskCommand::default_test().with_status(254);
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        let contents: Vec<&str> = tasks.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, vec!["pinned sooner", "pinned later", "due today"]);
+
+        // Pinned tasks lead even when due past the limit, and pages continue after them
+        insert_task(&conn, "work", "due tomorrow", "tomorrow");
+        let contents_of = |tasks: &[Item]| -> Vec<String> {
+            tasks.iter().map(|t| t.content.clone()).collect()
+        };
+        let cmd = ListTaskCommand::default_test().with_status(254).with_limit(1);
+        let (mut tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        let mut pages = vec![contents_of(&tasks)];
+        let next = cmd.with_next_page();
+        for _ in 0..3 {
+            cache::store_with_next(&conn, &tasks).unwrap();
+            tasks = query_all_tasks(&conn, &next).unwrap().0;
+            pages.push(contents_of(&tasks));
+        }
+        assert_eq!(
+            pages,
+            vec![
+                vec!["pinned sooner"],
+                vec!["pinned later"],
+                vec!["due today"],
+                vec!["due tomorrow"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_page_after_no_deadline() {
+        let (conn, _temp_file) = get_test_conn();
+        let someday = Item::with_target_time(
+            "task".to_string(),
+            "home".to_string(),
+            "someday".to_string(),
+            None,
+        );
+        insert_item(&conn, &someday).unwrap();
+        insert_task(&conn, "work", "missed yesterday morning", "yesterday 9am");
+        insert_task(&conn, "work", "missed yesterday evening", "yesterday 6pm");
+
+        // The task without a deadline leads, the overdue ones follow a page at a time
+        let cmd = ListTaskCommand::default_test().with_overdue(true).with_limit(1);
+        let (mut tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        let mut pages = vec![tasks.iter().map(|t| t.content.clone()).collect::<Vec<_>>()];
+        let next = cmd.with_next_page();
+        for _ in 0..2 {
+            cache::store_with_next(&conn, &tasks).unwrap();
+            tasks = query_all_tasks(&conn, &next).unwrap().0;
+            pages.push(tasks.iter().map(|t| t.content.clone()).collect());
+        }
+        assert_eq!(
+            pages,
+            vec![
+                vec!["someday"],
+                vec!["missed yesterday morning"],
+                vec!["missed yesterday evening"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overdue_grace() {
+        let (conn, _temp_file) = get_test_conn();
+        let now = Local::now().timestamp();
+        let id = insert_task(&conn, "work", "just passed", "today");
+        let mut task = get_item(&conn, id).unwrap();
+        task.target_time = Some(now - 120);
+        update_item(&conn, &task).unwrap();
+        insert_task(&conn, "work", "long passed", "2025/01/01");
+
+        let grace = Config {
+            overdue_grace_minutes: 5,
+            ..Default::default()
+        };
+        let no_grace = Config::default();
+        let contents = |cmd: &ListTaskCommand, config: &Config| -> Vec<String> {
+            query_tasks_with(&conn, cmd, config)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.content)
+                .collect()
+        };
+
+        // Within the grace the task is still listed as upcoming, not overdue
+        let upcoming = ListTaskCommand::default_test();
+        assert_eq!(contents(&upcoming, &grace), vec!["just passed"]);
+        assert!(contents(&upcoming, &no_grace).is_empty());
+
+        let overdue_only = ListTaskCommand {
+            overdue_only: true,
+            ..ListTaskCommand::default_test()
+        };
+        assert_eq!(contents(&overdue_only, &grace), vec!["long passed"]);
+        assert_eq!(contents(&overdue_only, &no_grace), vec!["long passed", "just passed"]);
+
+        assert_eq!(count_overdue_tasks(&conn, None, grace.overdue_cutoff(now)).unwrap(), 1);
+        assert_eq!(count_overdue_tasks(&conn, None, no_grace.overdue_cutoff(now)).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_query_all_tasks_overdue_only() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "missed", "yesterday");
+        insert_task(&conn, "life", "missed too", "yesterday -1d");
+        let done_id = insert_task(&conn, "work", "missed but done", "yesterday");
+        update_status(&conn, done_id, 1);
+        insert_task(&conn, "work", "upcoming", "tomorrow");
+        insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+
+        let cmd = ListTaskCommand {
+            overdue_only: true,
+            ..ListTaskCommand::default_test().with_status(254)
+        };
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        let contents: Vec<&str> = tasks.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, vec!["missed too", "missed"]);
+
+        let now = Local::now().timestamp();
+        assert_eq!(count_overdue_tasks(&conn, None, now).unwrap(), 2);
+        assert_eq!(count_overdue_tasks(&conn, Some("work"), now).unwrap(), 1);
+        assert_eq!(count_overdue_tasks(&conn, Some("home"), now).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_count_due_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "missed", "yesterday");
+        insert_task(&conn, "work", "due today", "today");
+        let pending_id = insert_task(&conn, "life", "waiting today", "today");
+        update_status(&conn, pending_id, 6);
+        let done_id = insert_task(&conn, "work", "done today", "today");
+        update_status(&conn, done_id, 1);
+        insert_task(&conn, "work", "upcoming", "tomorrow");
+        insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        insert_record(&conn, "work", "logged", "today");
+
+        let end_of_today = timestr::to_unix_epoch("today").unwrap();
+        assert_eq!(count_due_tasks(&conn, end_of_today).unwrap(), 3);
+        let end_of_yesterday = timestr::to_unix_epoch("yesterday").unwrap();
+        assert_eq!(count_due_tasks(&conn, end_of_yesterday).unwrap(), 1);
+    }
+
     #[test]
     fn test_query_tasks_statuses() {
         let (conn, _temp_file) = get_test_conn();
@@ -375,15 +1175,21 @@ mod tests {
         for i in 1..=4 {
             insert_task(&conn, "ongoing", &format!("ongoing-task-{}", i), "today");
         }
+        let rowid = insert_task(&conn, "started", "in-progress-task", "today");
+        update_status(&conn, rowid, 7);
 
         let list_open = ListTaskCommand::default_test().with_status(254);
         let list_closed = ListTaskCommand::default_test().with_status(253);
+        let list_in_progress = ListTaskCommand::default_test().with_status(7);
 
         let results = query_tasks(&conn, &list_open).expect("Unable to query");
-        assert_eq!(results.len(), 6);
+        assert_eq!(results.len(), 7);
         assert!(results
             .iter()
-            .all(|t| t.category == "ongoing" || t.category == "pending"));
+            .all(|t| ["ongoing", "pending", "started"].contains(&t.category.as_str())));
+        let results = query_tasks(&conn, &list_in_progress).expect("Unable to query");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "in-progress-task");
         let results = query_tasks(&conn, &list_closed).expect("Unable to query");
         assert_eq!(results.len(), 4);
         assert!(results
@@ -599,7 +1405,7 @@ mod tests {
         assert_eq!(recurring_and_regular[0].content, "Recurring 3");
 
         // Should start getting regular tasks (didn't hit recurring limit)
-        assert!(regular_tasks.len() > 0);
+        assert!(!regular_tasks.is_empty());
 
         // Third page: should transition to regular tasks (not "No tasks found")
         let cmd_next = ListTaskCommand {
@@ -731,4 +1537,225 @@ mod tests {
             assert_eq!(task.category, "work");
         }
     }
+
+    #[test]
+    fn test_budget_sums_estimates() {
+        let (conn, _temp_file) = get_test_conn();
+        for (content, estimate) in [("draft", Some(45)), ("review", Some(90)), ("email", None)] {
+            let id = insert_task(&conn, "work", content, "today");
+            let mut task = get_item(&conn, id).unwrap();
+            task.estimate_minutes = estimate;
+            update_item(&conn, &task).unwrap();
+        }
+        insert_task(&conn, "home", "laundry", "today");
+
+        let cmd = ListTaskCommand::default_test().with_category("work");
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(format_budget(&tasks).unwrap(), "Budget: 2h15m (2/3 tasks estimated)");
+        assert_eq!(format_budget(&[]).unwrap(), "Budget: 0m (0/0 tasks estimated)");
+
+        let mut huge = tasks[..2].to_vec();
+        for task in &mut huge {
+            task.estimate_minutes = Some(i64::MAX / 2 + 1);
+        }
+        assert!(format_budget(&huge).unwrap_err().ends_with("too large to total"));
+    }
+
+    #[test]
+    fn test_status_summary() {
+        let (conn, _temp_file) = get_test_conn();
+        for (content, status) in [("a", 0), ("b", 6), ("c", 1), ("d", 0), ("e", 1), ("f", 0)] {
+            let id = insert_task(&conn, "work", content, "today");
+            update_status(&conn, id, status);
+        }
+
+        let mut cmd = ListTaskCommand::default_test();
+        cmd.status = 254;
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        assert_eq!(format_status_summary(&tasks), "3 ongoing, 1 pending");
+
+        // Only the queried statuses are counted
+        cmd.status = 6;
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        assert_eq!(format_status_summary(&tasks), "1 pending");
+        cmd.status = 254;
+
+        cmd.include_done = true;
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        assert_eq!(format_status_summary(&tasks), "3 ongoing, 2 completed, 1 pending");
+
+        // A recurring task done for its interval counts as its row shows it
+        let standup = insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        let future_time = Local::now().timestamp() + 86400;
+        insert_recurring_record(&conn, "work", "standup done", standup, future_time);
+        insert_recurring_task(&conn, "work", "review", "Daily 5PM");
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        assert_eq!(
+            format_status_summary(&tasks),
+            "4 ongoing, 1 fulfilled, 2 completed, 1 pending"
+        );
+    }
+
+    #[test]
+    fn test_expand_recurring_occurrences() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+        insert_task(&conn, "work", "report", "tomorrow");
+
+        let cmd = ListTaskCommand::default_test().with_expand(3);
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        let diary: Vec<&Item> = tasks.iter().filter(|t| t.content == "diary").collect();
+        assert_eq!(diary.len(), 3);
+        let mut days: Vec<String> = diary
+            .iter()
+            .map(|t| {
+                let dt = Local.timestamp_opt(t.target_time.unwrap(), 0).unwrap();
+                dt.date_naive().to_string()
+            })
+            .collect();
+        days.dedup();
+        assert_eq!(days.len(), 3);
+        assert!(diary.iter().all(|t| t.id.is_none()));
+
+        // Interleaved by date with the regular task
+        assert_eq!(tasks.len(), 4);
+        assert!(tasks.windows(2).all(|w| w[0].target_time <= w[1].target_time));
+
+        // Only regular tasks are cached for index lookups
+        handle_listtasks(&conn, ListTaskCommand::default_test().with_expand(3)).unwrap();
+        let report_id = tasks.iter().find(|t| t.content == "report").unwrap().id.unwrap();
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(report_id));
+        assert_eq!(cache::read(&conn, 2).unwrap(), None);
+    }
+
+    #[test]
+    fn test_expand_requires_window_end() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+        let mut cmd = ListTaskCommand::default_test();
+        cmd.expand = true;
+        let err = query_all_tasks(&conn, &cmd).unwrap_err();
+        assert!(err.contains("--expand needs a window end"), "{}", err);
+    }
+
+    #[test]
+    fn test_expand_until() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        let until = (Local::now() + chrono::Duration::days(5)).date_naive();
+
+        let mut cmd = ListTaskCommand::default_test();
+        cmd.expand = true;
+        cmd.until = Some(until.format("%Y-%m-%d").to_string());
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+
+        // One occurrence a day from the first upcoming one through the until date
+        let first = cron::get_next_occurrence("0 9 * * *").unwrap();
+        let first_day = Local.timestamp_opt(first, 0).unwrap().date_naive();
+        assert_eq!(tasks.len() as i64, (until - first_day).num_days() + 1);
+        assert_eq!(tasks[0].target_time, Some(first));
+
+        // The earlier of --days and --until ends the window, for regular tasks as well
+        insert_task(&conn, "work", "report", "tomorrow");
+        insert_task(&conn, "work", "far off", "2099/01/01");
+        cmd.days = Some(10);
+        cmd.until = Some("tomorrow".to_string());
+        let (tasks, _, _) = query_all_tasks(&conn, &cmd).unwrap();
+        let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
+        let mut expected = vec!["standup"; (tomorrow - first_day).num_days() as usize + 1];
+        expected.push("report");
+        let contents: Vec<&str> = tasks.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, expected);
+
+        // Agenda days stop at --until even for a listed task past it
+        let far_off = Item::with_target_time(
+            "task".to_string(),
+            "work".to_string(),
+            "far off".to_string(),
+            Some(timestr::to_unix_epoch("2099/01/01").unwrap()),
+        );
+        let (_, last_day) = agenda_days(&cmd, &[far_off]).unwrap();
+        assert_eq!(last_day, tomorrow);
+    }
+
+    #[test]
+    fn test_category_histogram() {
+        let counts = vec![
+            ("work".to_string(), 8),
+            ("home".to_string(), 4),
+            ("gym".to_string(), 2),
+            ("misc".to_string(), 0),
+        ];
+        // 51 columns less a 4 wide label, " | " and " (8)" leaves 40 for bars
+        let lines = format_histogram(&counts, 51);
+        let bars: Vec<usize> = lines.iter().map(|l| l.matches('#').count()).collect();
+        assert_eq!(bars, vec![40, 20, 10, 1]);
+        assert_eq!(lines[0], format!("work | {} (8)", "#".repeat(40)));
+        assert_eq!(lines[2], format!("gym  | {} (2)", "#".repeat(10)));
+        assert!(lines.iter().all(|l| l.chars().count() <= 51));
+
+        let (conn, _temp_file) = get_test_conn();
+        for i in 0..3 {
+            insert_task(&conn, "work", &format!("task {}", i), "tomorrow");
+        }
+        let done = insert_task(&conn, "work", "done", "tomorrow");
+        update_status(&conn, done, 1);
+        insert_task(&conn, "home", "laundry", "yesterday");
+        let cmd = ListTaskCommand {
+            histogram: true,
+            ..ListTaskCommand::default_test()
+        };
+        assert!(handle_listtasks(&conn, cmd).is_ok());
+    }
+
+    #[test]
+    fn test_not_status() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "ongoing", "tomorrow");
+        let removed = insert_task(&conn, "work", "removed", "tomorrow");
+        update_status(&conn, removed, 5);
+        let done = insert_task(&conn, "work", "done", "tomorrow");
+        update_status(&conn, done, 1);
+        let standup = insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        let future_time = Local::now().timestamp() + 86400;
+        insert_recurring_record(&conn, "work", "standup done", standup, future_time);
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+
+        let contents = |cmd: &ListTaskCommand| {
+            let (tasks, _, _) = query_all_tasks(&conn, cmd).unwrap();
+            let mut contents: Vec<String> = tasks.into_iter().map(|t| t.content).collect();
+            contents.sort();
+            contents
+        };
+        let cmd = ListTaskCommand {
+            status: 255,
+            not_status: Some(5),
+            ..ListTaskCommand::default_test()
+        };
+        assert_eq!(contents(&cmd), vec!["diary", "done", "ongoing", "standup"]);
+
+        // Aggregates exclude every status in them, completed recurring tasks count as done
+        let cmd = ListTaskCommand {
+            status: 255,
+            not_status: Some(253),
+            ..ListTaskCommand::default_test()
+        };
+        assert_eq!(contents(&cmd), vec!["diary", "ongoing"]);
+
+        // Composes with --status, removed is already closed
+        let cmd = ListTaskCommand {
+            status: 253,
+            not_status: Some(5),
+            ..ListTaskCommand::default_test()
+        };
+        assert_eq!(contents(&cmd), vec!["done", "standup"]);
+
+        let cmd = ListTaskCommand {
+            not_status: Some(255),
+            ..ListTaskCommand::default_test()
+        };
+        let err = query_all_tasks(&conn, &cmd).unwrap_err();
+        assert!(err.contains("leave out every task"), "{}", err);
+    }
 }
@@ -1,82 +1,379 @@
 use std::{io, io::Write};
 
+use chrono::Local;
 use rusqlite::Connection;
 
-use super::{get_rowid_from_cache, validate_cache};
+use super::{find_unique_match, get_rowid_from_cache, validate_cache};
 use crate::{
     actions::{
         display,
-        list::query_all_tasks,
-        ops::batch::prompt_y_n_q,
+        list::{
+            query_all_tasks,
+            CLOSED_STATUS_CODES,
+            OPEN_STATUS_CODES,
+        },
+        ops::{
+            backup::backup_path,
+            batch::prompt_y_n_q,
+        },
     },
     args::{
         cron,
-        parser::{DoneCommand, ListTaskCommand},
+        parser::{parse_indices, CancelCommand, DoneCommand, ListTaskCommand},
+        timestr,
     },
     db::{
-        crud::{insert_item, query_items, update_item},
-        item::{Item, ItemQuery, RECORD, RECURRING_TASK, RECURRING_TASK_RECORD},
+        crud::{get_item, insert_item, query_items, update_item},
+        item::{Item, ItemQuery, RECORD, RECURRING_TASK, RECURRING_TASK_RECORD, TASK},
     },
+    error::TascliError,
 };
 
 pub fn handle_donecmd(conn: &Connection, cmd: &DoneCommand) -> Result<(), String> {
-    if let Ok(index) = cmd.target.trim().parse::<usize>() {
-        return handle_done_by_index(conn, index, cmd.status, cmd.comment.as_deref());
+    if let Some(pattern) = &cmd.pattern {
+        let row_id = find_unique_match(conn, pattern)?;
+        let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {}", e))?;
+        return complete_with_followup(conn, item, cmd);
+    }
+    if cmd.first || cmd.last {
+        let item = find_due_edge(conn, cmd.last)?;
+        return complete_with_followup(conn, item, cmd);
+    }
+    let target = cmd.target.as_deref().map(str::trim);
+    if let Some(t) = target
+        && t.starts_with(|c: char| c.is_ascii_digit())
+    {
+        if cmd.category.is_some() || cmd.all {
+            return Err("--category and --all are not supported when completing by index".to_string());
+        }
+        let indices = parse_indices(t)?;
+        if cmd.followup.is_some() {
+            let [index] = indices[..] else {
+                return Err(format!("--followup needs a single task, found {} indices", indices.len()));
+            };
+            validate_cache(conn)?;
+            let row_id = get_rowid_from_cache(conn, index)?;
+            let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {}", e))?;
+            return complete_with_followup(conn, item, cmd);
+        }
+        return handle_done_by_indices(conn, &indices, cmd.status, cmd.comment.as_deref(), cmd.force);
     }
 
-    // Reject --comment flag for interactive modes
-    if matches!(cmd.target.trim(), "today" | "overdue") && cmd.comment.is_some() {
+    // Reject --comment and --followup flags for interactive modes
+    if cmd.comment.is_some() {
         return Err(
-            "--comment is not supported with 'today' or 'overdue'. Comments are added per task in the interactive flow.".to_string()
+            "--comment is not supported with 'today', 'overdue' or --category. Comments are added per task in the interactive flow.".to_string()
         );
     }
+    if cmd.followup.is_some() {
+        return Err("--followup is not supported with 'today', 'overdue' or --category".to_string());
+    }
+
+    let category = cmd.category.as_deref();
+    match target {
+        Some("today") => handle_done_today(conn, cmd.status, category, cmd.all),
+        Some("overdue") => handle_done_overdue(conn, cmd.status, category, cmd.all),
+        None if category.is_some() => handle_done_category(conn, cmd.status, category, cmd.all),
+        Some(other) => Err(format!("Unknown target '{}'. Expected an index, 'today', or 'overdue'", other)),
+        None => Err("Expected an index, 'today', 'overdue', or --category".to_string()),
+    }
+}
+
+pub fn handle_cancelcmd(conn: &Connection, cmd: &CancelCommand) -> Result<(), String> {
+    validate_cache(conn)?;
+    let row_id = get_rowid_from_cache(conn, cmd.index)?;
+    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {}", e))?;
+    cancel_item(conn, &mut item, cmd.reason.as_deref())
+}
 
-    match cmd.target.trim() {
-        "today" => handle_done_today(conn, cmd.status),
-        "overdue" => handle_done_overdue(conn, cmd.status),
-        other => Err(format!("Unknown target '{}'. Expected an index, 'today', or 'overdue'", other)),
+// Like complete_item for regular tasks, the reason goes to the record only.
+fn cancel_item(conn: &Connection, item: &mut Item, reason: Option<&str>) -> Result<(), String> {
+    if item.action != TASK {
+        return Err(format!("Only tasks can be cancelled, found {}", item.action));
     }
+
+    let record_content = match reason {
+        Some(r) => format!("Cancelled Task: {} ({})", item.content, r),
+        None => format!("Cancelled Task: {}", item.content),
+    };
+    let cancel_record = Item::new(RECORD.to_string(), item.category.clone(), record_content);
+    insert_item(conn, &cancel_record)
+        .map_err(|e| format!("Failed to create cancellation record: {}", e))?;
+
+    item.status = 2;
+    update_item(conn, item).map_err(|e| format!("Failed to update item: {}", e))?;
+    display::print_bold("Cancelled Task:");
+    display::print_items(std::slice::from_ref(item), false);
+    Ok(())
 }
 
-fn handle_done_by_index(
+// The follow-up is built before completing, so it keeps the content without the comment.
+fn complete_with_followup(conn: &Connection, mut item: Item, cmd: &DoneCommand) -> Result<(), String> {
+    let followup = match &cmd.followup {
+        Some(timestr) => {
+            if item.action != TASK {
+                return Err(format!("Only regular tasks can have a follow-up, found {}", item.action));
+            }
+            let target_time = timestr::to_unix_epoch(timestr)?;
+            Some(Item::with_target_time(
+                TASK.to_string(),
+                item.category.clone(),
+                item.content.clone(),
+                Some(target_time),
+            ))
+        }
+        None => None,
+    };
+    complete_item(conn, &mut item, cmd.status, cmd.comment.as_deref(), cmd.force)?;
+    if let Some(followup) = followup {
+        insert_item(conn, &followup).map_err(|e| format!("Failed to create follow-up task: {}", e))?;
+        display::print_bold("Inserted Follow-up Task:");
+        display::print_items(&[followup], false);
+    }
+    Ok(())
+}
+
+// The open task due earliest, or latest, recurring tasks have no due time.
+// Tasks without a deadline are neither first nor last.
+fn find_due_edge(conn: &Connection, latest: bool) -> Result<Item, String> {
+    let mut query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_any_target_time()
+        .with_order_by("target_time")
+        .with_limit(1);
+    if latest {
+        query = query.with_order_desc();
+    }
+    let tasks = query_items(conn, &query).map_err(|e| e.to_string())?;
+    tasks
+        .into_iter()
+        .next()
+        .ok_or_else(|| "No open tasks to complete".to_string())
+}
+
+// Every index is resolved before any task is completed.
+fn handle_done_by_indices(
     conn: &Connection,
-    index: usize,
+    indices: &[usize],
     status: u8,
     comment: Option<&str>,
+    force: bool,
 ) -> Result<(), String> {
     validate_cache(conn)?;
-    let row_id = get_rowid_from_cache(conn, index)?;
-    let mut item = crate::db::crud::get_item(conn, row_id)
-        .map_err(|e| format!("Failed to get item: {:?}", e))?;
-    complete_item(conn, &mut item, status, comment)
+    let row_ids = indices
+        .iter()
+        .map(|&index| get_rowid_from_cache(conn, index))
+        .collect::<Result<Vec<i64>, TascliError>>()?;
+    if let [row_id] = row_ids[..] {
+        let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {}", e))?;
+        return complete_item(conn, &mut item, status, comment, force);
+    }
+
+    let mut completed = 0;
+    let mut failed = 0;
+    for row_id in row_ids {
+        let result = get_item(conn, row_id)
+            .map_err(|e| format!("Failed to get item: {}", e))
+            .and_then(|mut item| complete_item(conn, &mut item, status, comment, force));
+        match result {
+            Ok(()) => completed += 1,
+            Err(e) => {
+                display::print_red(&format!("Error: {}", e));
+                failed += 1;
+            }
+        }
+    }
+
+    display::print_bold(&format!(
+        "✓ Completed {}, failed {}",
+        pluralize(completed, "task"),
+        failed
+    ));
+    if failed > 0 {
+        return Err(format!("Failed to complete {}", pluralize(failed, "task")));
+    }
+    Ok(())
 }
 
-fn handle_done_today(conn: &Connection, status: u8) -> Result<(), String> {
+fn handle_done_today(
+    conn: &Connection,
+    status: u8,
+    category: Option<&str>,
+    all: bool,
+) -> Result<(), String> {
     let list_cmd = ListTaskCommand {
         timestr: Some("today".to_string()),
-        category: None,
+        category: category.map(String::from),
         days: None,
+        due_after: None,
+        due_before: None,
+        due: None,
+        do_date: None,
         status: 254,
+        not_status: None,
         overdue: false,
+        overdue_only: false,
+        no_deadline: false,
+        include_done: false,
         limit: 100,
         next_page: false,
         search: None,
+        source: None,
+        no_cache: false,
+        budget: false,
+        expand: false,
+        until: None,
+        histogram: false,
+        agenda: false,
+        titles_only: false,
+        show_closed: false,
+        countdown: false,
+        sort: "due".to_string(),
     };
-    run_interactive_done(conn, &list_cmd, "No open tasks found for today", status)
+    run_done(conn, &list_cmd, "No open tasks found for today", status, all)
 }
 
-fn handle_done_overdue(conn: &Connection, status: u8) -> Result<(), String> {
+fn handle_done_overdue(
+    conn: &Connection,
+    status: u8,
+    category: Option<&str>,
+    all: bool,
+) -> Result<(), String> {
     let list_cmd = ListTaskCommand {
         timestr: Some("today".to_string()),
-        category: None,
+        category: category.map(String::from),
         days: None,
+        due_after: None,
+        due_before: None,
+        due: None,
+        do_date: None,
         status: 254,
+        not_status: None,
         overdue: true,
+        overdue_only: false,
+        no_deadline: false,
+        include_done: false,
         limit: 100,
         next_page: false,
         search: None,
+        source: None,
+        no_cache: false,
+        budget: false,
+        expand: false,
+        until: None,
+        histogram: false,
+        agenda: false,
+        titles_only: false,
+        show_closed: false,
+        countdown: false,
+        sort: "due".to_string(),
     };
-    run_interactive_done(conn, &list_cmd, "No open overdue tasks found", status)
+    run_done(conn, &list_cmd, "No open overdue tasks found", status, all)
+}
+
+// Every open task in the category, overdue ones included.
+fn handle_done_category(
+    conn: &Connection,
+    status: u8,
+    category: Option<&str>,
+    all: bool,
+) -> Result<(), String> {
+    let list_cmd = ListTaskCommand {
+        timestr: None,
+        category: category.map(String::from),
+        days: None,
+        due_after: None,
+        due_before: None,
+        due: None,
+        do_date: None,
+        status: 254,
+        not_status: None,
+        overdue: true,
+        overdue_only: false,
+        no_deadline: false,
+        include_done: false,
+        limit: 100,
+        next_page: false,
+        search: None,
+        source: None,
+        no_cache: false,
+        budget: false,
+        expand: false,
+        until: None,
+        histogram: false,
+        agenda: false,
+        titles_only: false,
+        show_closed: false,
+        countdown: false,
+        sort: "due".to_string(),
+    };
+    run_done(conn, &list_cmd, "No open tasks found in category", status, all)
+}
+
+fn run_done(
+    conn: &Connection,
+    list_cmd: &ListTaskCommand,
+    empty_msg: &str,
+    status: u8,
+    all: bool,
+) -> Result<(), String> {
+    if all {
+        run_bulk_done(conn, list_cmd, empty_msg, status)
+    } else {
+        run_interactive_done(conn, list_cmd, empty_msg, status)
+    }
+}
+
+fn run_bulk_done(
+    conn: &Connection,
+    list_cmd: &ListTaskCommand,
+    empty_msg: &str,
+    status: u8,
+) -> Result<(), String> {
+    let (tasks, _, _) = query_all_tasks(conn, list_cmd)?;
+
+    if tasks.is_empty() {
+        display::print_bold(empty_msg);
+        return Ok(());
+    }
+
+    display::print_bold(&format!("Found {}:", pluralize(tasks.len(), "task")));
+    display::print_items(&tasks, true);
+
+    print!("\nComplete all? (y/n): ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).map_err(|e| e.to_string())?;
+    if input.trim().to_lowercase() != "y" {
+        display::print_bold("Cancelled");
+        return Ok(());
+    }
+
+    display::print_bold("backing up database prior to bulk done");
+    if let Err(e) = backup_path(None) {
+        display::print_red(&e);
+    }
+
+    let mut completed = 0;
+    let mut failed = 0;
+    for item in &tasks {
+        let mut item = item.clone();
+        match complete_item(conn, &mut item, status, None, false) {
+            Ok(()) => completed += 1,
+            Err(e) => {
+                display::print_red(&format!("Error: {}", e));
+                failed += 1;
+            }
+        }
+    }
+
+    display::print_bold(&format!(
+        "✓ Completed {}, failed {}",
+        pluralize(completed, "task"),
+        failed
+    ));
+    Ok(())
 }
 
 fn run_interactive_done(
@@ -107,7 +404,7 @@ fn run_interactive_done(
             'y' => {
                 let mut item = item.clone();
                 let task_comment = prompt_optional_comment();
-                match complete_item(conn, &mut item, status, task_comment.as_deref()) {
+                match complete_item(conn, &mut item, status, task_comment.as_deref(), false) {
                     Ok(()) => completed += 1,
                     Err(e) => {
                         display::print_red(&format!("Error: {}", e));
@@ -138,15 +435,24 @@ fn run_interactive_done(
     Ok(())
 }
 
+// A closed task is only completed again with force, so it does not get a second record.
 fn complete_item(
     conn: &Connection,
     item: &mut Item,
     status: u8,
     comment: Option<&str>,
+    force: bool,
 ) -> Result<(), String> {
     if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
         return Err("Cannot complete a record".to_string());
     }
+    if item.action == TASK && CLOSED_STATUS_CODES.contains(&item.status) && !force {
+        return Err(format!(
+            "Task '{}' is already {}, pass --force to complete it again",
+            item.content,
+            display::translate_status(item.status)
+        ));
+    }
 
     if item.action == RECURRING_TASK {
         let cron_schedule = item
@@ -163,7 +469,7 @@ fn complete_item(
                 .with_recurring_task_id(item.id.unwrap())
                 .with_good_until_min(last_occurrence),
         )
-        .map_err(|e| format!("Failed to query existing records: {:?}", e))?;
+        .map_err(|e| format!("Failed to query existing records: {}", e))?;
 
         if !existing_records.is_empty() {
             return Err(
@@ -186,7 +492,7 @@ fn complete_item(
             next_occurrence,
         );
         insert_item(conn, &completion_record)
-            .map_err(|e| format!("Failed to create completion record: {:?}", e))?;
+            .map_err(|e| format!("Failed to create completion record: {}", e))?;
 
         display::print_bold("Completed Recurring Task:");
         display::print_items(std::slice::from_ref(item), false);
@@ -201,15 +507,31 @@ fn complete_item(
     let completion_content = format!("Completed Task: {}", item.content);
     let completion_record = Item::new(RECORD.to_string(), item.category.clone(), completion_content);
     insert_item(conn, &completion_record)
-        .map_err(|e| format!("Failed to create completion record: {:?}", e))?;
+        .map_err(|e| format!("Failed to create completion record: {}", e))?;
 
     item.status = status;
-    update_item(conn, item).map_err(|e| format!("Failed to update item: {:?}", e))?;
-    display::print_bold("Completed Task:");
+    update_item(conn, item).map_err(|e| format!("Failed to update item: {}", e))?;
+    match late_note(item, Local::now().timestamp()) {
+        Some(note) => display::print_bold(&format!("Completed Task {}:", note)),
+        None => display::print_bold("Completed Task:"),
+    }
     display::print_items(std::slice::from_ref(item), false);
     Ok(())
 }
 
+// Partial days count as a full day, so a task due yesterday is 1 day late
+fn late_note(item: &Item, now: i64) -> Option<String> {
+    if item.status != 1 {
+        return None;
+    }
+    let target_time = item.target_time?;
+    if now <= target_time {
+        return None;
+    }
+    let days_late = (now - target_time + 86399) / 86400;
+    Some(format!("(completed {} late)", pluralize(days_late as usize, "day")))
+}
+
 fn prompt_optional_comment() -> Option<String> {
     print!("Comment (optional): ");
     io::stdout().flush().unwrap();
@@ -232,7 +554,7 @@ mod tests {
             crud::{get_item, query_items},
             item::{ItemQuery, TASK},
         },
-        tests::{get_test_conn, insert_recurring_task, insert_task},
+        tests::{get_test_conn, insert_recurring_task, insert_task, update_status},
     };
 
     #[test]
@@ -242,7 +564,7 @@ mod tests {
         let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
         cache::store(&conn, &items).unwrap();
 
-        let done_cmd = DoneCommand { target: "1".to_string(), status: 1, comment: None };
+        let done_cmd = DoneCommand { target: Some("1".to_string()), status: 1, comment: None, category: None, all: false, pattern: None, first: false, last: false, followup: None, force: false };
         handle_donecmd(&conn, &done_cmd).unwrap();
         let item_id = cache::read(&conn, 1).unwrap().unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
@@ -253,7 +575,16 @@ mod tests {
         assert_eq!(records[0].content, "Completed Task: finish report");
         assert_eq!(records[0].category, "work");
 
-        let done_cmd = DoneCommand { target: "1".to_string(), status: 2, comment: None };
+        // Closing it again needs --force, without adding a record
+        let mut done_cmd = DoneCommand { target: Some("1".to_string()), status: 2, comment: None, category: None, all: false, pattern: None, first: false, last: false, followup: None, force: false };
+        assert_eq!(
+            handle_donecmd(&conn, &done_cmd).unwrap_err(),
+            "Task 'finish report' is already completed, pass --force to complete it again"
+        );
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap();
+        assert_eq!(records.len(), 1);
+
+        done_cmd.force = true;
         handle_donecmd(&conn, &done_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.status, 2);
@@ -262,6 +593,121 @@ mod tests {
         assert_eq!(records.len(), 2);
     }
 
+    #[test]
+    fn test_handle_cancelcmd() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "book venue", "tomorrow");
+        insert_task(&conn, "home", "fix fence", "tomorrow");
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+        let items = query_items(&conn, &ItemQuery::new().with_order_by("id")).unwrap();
+        cache::store(&conn, &items).unwrap();
+
+        let cancel_cmd = CancelCommand { index: 1, reason: Some("event moved online".to_string()) };
+        handle_cancelcmd(&conn, &cancel_cmd).unwrap();
+        let cancel_cmd = CancelCommand { index: 2, reason: None };
+        handle_cancelcmd(&conn, &cancel_cmd).unwrap();
+        for id in [items[0].id.unwrap(), items[1].id.unwrap()] {
+            assert_eq!(get_item(&conn, id).unwrap().status, 2);
+        }
+        // The task content stays as it was
+        assert_eq!(get_item(&conn, items[0].id.unwrap()).unwrap().content, "book venue");
+
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD).with_order_by("id")).unwrap();
+        let contents: Vec<&str> = records.iter().map(|r| r.content.as_str()).collect();
+        assert_eq!(contents, vec!["Cancelled Task: book venue (event moved online)", "Cancelled Task: fix fence"]);
+        assert_eq!(records[0].category, "work");
+
+        let cancel_cmd = CancelCommand { index: 3, reason: None };
+        assert_eq!(
+            handle_cancelcmd(&conn, &cancel_cmd).unwrap_err(),
+            "Only tasks can be cancelled, found recurring_task"
+        );
+    }
+
+    #[test]
+    fn test_handle_donecmd_multiple_indices() {
+        let (conn, _temp_file) = get_test_conn();
+        for i in 1..=6 {
+            insert_task(&conn, "work", &format!("task {}", i), "tomorrow");
+        }
+        let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        cache::store(&conn, &items).unwrap();
+        let open_contents = |conn: &Connection| {
+            query_items(conn, &ItemQuery::new().with_action(TASK).with_statuses(vec![0]))
+                .unwrap()
+                .into_iter()
+                .map(|t| t.content)
+                .collect::<Vec<String>>()
+        };
+
+        let done_cmd = DoneCommand { target: Some("1-3".to_string()), status: 1, comment: None, category: None, all: false, pattern: None, first: false, last: false, followup: None, force: false };
+        handle_donecmd(&conn, &done_cmd).unwrap();
+        assert_eq!(open_contents(&conn), vec!["task 4", "task 5", "task 6"]);
+
+        let done_cmd = DoneCommand { target: Some("4,6".to_string()), status: 1, comment: None, category: None, all: false, pattern: None, first: false, last: false, followup: None, force: false };
+        handle_donecmd(&conn, &done_cmd).unwrap();
+        assert_eq!(open_contents(&conn), vec!["task 5"]);
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap();
+        assert_eq!(records.len(), 5);
+
+        // An index missing from the cache fails before completing anything
+        let done_cmd = DoneCommand { target: Some("5,9".to_string()), status: 1, comment: None, category: None, all: false, pattern: None, first: false, last: false, followup: None, force: false };
+        assert_eq!(handle_donecmd(&conn, &done_cmd).unwrap_err(), "index 9 does not exist");
+        assert_eq!(open_contents(&conn), vec!["task 5"]);
+
+        // A task failing to complete fails the command after the others are done
+        let done_cmd = DoneCommand { target: Some("1,5".to_string()), status: 1, comment: None, category: None, all: false, pattern: None, first: false, last: false, followup: None, force: false };
+        assert_eq!(handle_donecmd(&conn, &done_cmd).unwrap_err(), "Failed to complete 1 task");
+        assert!(open_contents(&conn).is_empty());
+
+        let done_cmd = DoneCommand { target: Some("3-1".to_string()), status: 1, comment: None, category: None, all: false, pattern: None, first: false, last: false, followup: None, force: false };
+        assert!(handle_donecmd(&conn, &done_cmd).is_err());
+    }
+
+    #[test]
+    fn test_late_note() {
+        let (conn, _temp_file) = get_test_conn();
+        let overdue_id = insert_task(&conn, "work", "overdue report", "yesterday");
+        let ontime_id = insert_task(&conn, "work", "ontime report", "tomorrow");
+        let mut overdue = get_item(&conn, overdue_id).unwrap();
+        let mut ontime = get_item(&conn, ontime_id).unwrap();
+        let due = overdue.target_time.unwrap();
+
+        complete_item(&conn, &mut overdue, 1, None, false).unwrap();
+        complete_item(&conn, &mut ontime, 1, None, false).unwrap();
+        assert_eq!(late_note(&ontime, Local::now().timestamp()), None);
+        assert_eq!(late_note(&overdue, due), None);
+        assert_eq!(late_note(&overdue, due + 60).as_deref(), Some("(completed 1 day late)"));
+        assert_eq!(
+            late_note(&overdue, due + 3 * 86400).as_deref(),
+            Some("(completed 3 days late)")
+        );
+
+        // Only completions are annotated, not cancellations
+        overdue.status = 2;
+        assert_eq!(late_note(&overdue, due + 60), None);
+    }
+
+    #[test]
+    fn test_closed_time() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "work", "file taxes", "tomorrow");
+        assert_eq!(get_item(&conn, task_id).unwrap().closed_time, None);
+
+        let mut task = get_item(&conn, task_id).unwrap();
+        complete_item(&conn, &mut task, 1, None, false).unwrap();
+        let closed_time = get_item(&conn, task_id).unwrap().closed_time;
+        assert!(closed_time.is_some());
+
+        // Moving between closed statuses keeps the original time
+        update_status(&conn, task_id, 2);
+        assert_eq!(get_item(&conn, task_id).unwrap().closed_time, closed_time);
+
+        // Reopening clears it
+        update_status(&conn, task_id, 0);
+        assert_eq!(get_item(&conn, task_id).unwrap().closed_time, None);
+    }
+
     #[test]
     fn test_handle_donecmd_with_comment() {
         let (conn, _temp_file) = get_test_conn();
@@ -270,9 +716,16 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let done_cmd = DoneCommand {
-            target: "1".to_string(),
+            target: Some("1".to_string()),
             status: 1,
             comment: Some("Added extra analysis section".to_string()),
+            category: None,
+            all: false,
+            pattern: None,
+            first: false,
+            last: false,
+            followup: None,
+            force: false,
         };
         handle_donecmd(&conn, &done_cmd).unwrap();
         let item_id = cache::read(&conn, 1).unwrap().unwrap();
@@ -295,9 +748,16 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let done_cmd = DoneCommand {
-            target: "1".to_string(),
+            target: Some("1".to_string()),
             status: 1,
             comment: Some("Discussed sprint goals".to_string()),
+            category: None,
+            all: false,
+            pattern: None,
+            first: false,
+            last: false,
+            followup: None,
+            force: false,
         };
         let result = handle_donecmd(&conn, &done_cmd);
         assert!(result.is_ok());
@@ -310,7 +770,7 @@ mod tests {
         assert_eq!(records[0].recurring_task_id, Some(task_id));
         assert!(records[0].good_until.is_some());
 
-        let done_cmd2 = DoneCommand { target: "1".to_string(), status: 1, comment: None };
+        let done_cmd2 = DoneCommand { target: Some("1".to_string()), status: 1, comment: None, category: None, all: false, pattern: None, first: false, last: false, followup: None, force: false };
         let result = handle_donecmd(&conn, &done_cmd2);
         assert!(result.is_err());
         assert_eq!(
@@ -321,4 +781,139 @@ mod tests {
         let records = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK_RECORD)).unwrap();
         assert_eq!(records.len(), 1);
     }
+
+    #[test]
+    fn test_handle_donecmd_by_match() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "finish quarterly report", "tomorrow");
+        insert_task(&conn, "work", "review report draft", "tomorrow");
+        insert_task(&conn, "home", "water plants", "tomorrow");
+        let match_cmd = |pattern: &str| DoneCommand {
+            target: None,
+            status: 1,
+            comment: None,
+            category: None,
+            all: false,
+            pattern: Some(pattern.to_string()),
+            first: false,
+            last: false,
+            followup: None,
+            force: false,
+        };
+
+        let result = handle_donecmd(&conn, &match_cmd("report"));
+        assert_eq!(
+            result.unwrap_err(),
+            "2 open tasks match 'report', narrow it down or use an index"
+        );
+        let result = handle_donecmd(&conn, &match_cmd("laundry"));
+        assert_eq!(result.unwrap_err(), "No open task matches 'laundry'");
+
+        // No list command is needed first
+        handle_donecmd(&conn, &match_cmd("quarterly")).unwrap();
+        let open = query_items(&conn, &ItemQuery::new().with_action(TASK).with_statuses(vec![0])).unwrap();
+        let contents: Vec<&str> = open.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, vec!["review report draft", "water plants"]);
+
+        // Completed tasks and their records no longer count as matches
+        handle_donecmd(&conn, &match_cmd("report")).unwrap();
+        let records = query_items(&conn, &ItemQuery::new().with_action(RECORD)).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].content, "Completed Task: review report draft");
+    }
+
+    #[test]
+    fn test_handle_donecmd_first_last() {
+        let (conn, _temp_file) = get_test_conn();
+        let edge_cmd = |first: bool| DoneCommand {
+            target: None,
+            status: 1,
+            comment: None,
+            category: None,
+            all: false,
+            pattern: None,
+            first,
+            last: !first,
+            followup: None,
+            force: false,
+        };
+        let result = handle_donecmd(&conn, &edge_cmd(true));
+        assert_eq!(result.unwrap_err(), "No open tasks to complete");
+
+        let overdue = insert_task(&conn, "work", "overdue report", "yesterday");
+        let soon = insert_task(&conn, "work", "soon", "tomorrow");
+        let latest = insert_task(&conn, "home", "taxes", "2099/04/15");
+        let closed = insert_task(&conn, "home", "closed later", "2099/12/31");
+        update_status(&conn, closed, 2);
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+        let someday = Item::with_target_time(
+            "task".to_string(),
+            "home".to_string(),
+            "clean garage".to_string(),
+            None,
+        );
+        let someday = insert_item(&conn, &someday).unwrap();
+
+        handle_donecmd(&conn, &edge_cmd(true)).unwrap();
+        assert_eq!(get_item(&conn, overdue).unwrap().status, 1);
+        assert_eq!(get_item(&conn, soon).unwrap().status, 0);
+
+        handle_donecmd(&conn, &edge_cmd(false)).unwrap();
+        assert_eq!(get_item(&conn, latest).unwrap().status, 1);
+        assert_eq!(get_item(&conn, soon).unwrap().status, 0);
+
+        handle_donecmd(&conn, &edge_cmd(true)).unwrap();
+        assert_eq!(get_item(&conn, soon).unwrap().status, 1);
+        assert_eq!(get_item(&conn, closed).unwrap().status, 2);
+
+        let result = handle_donecmd(&conn, &edge_cmd(true));
+        assert_eq!(result.unwrap_err(), "No open tasks to complete");
+        assert_eq!(get_item(&conn, someday).unwrap().status, 0);
+    }
+
+    #[test]
+    fn test_done_with_followup() {
+        let (conn, _temp_file) = get_test_conn();
+        let report = insert_task(&conn, "work", "weekly report", "today");
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+        let report_item = get_item(&conn, report).unwrap();
+        cache::store(&conn, &[report_item]).unwrap();
+
+        let followup_cmd = |target: &str| DoneCommand {
+            target: Some(target.to_string()),
+            status: 1,
+            comment: Some("sent".to_string()),
+            category: None,
+            all: false,
+            pattern: None,
+            first: false,
+            last: false,
+            followup: Some("2099/01/08".to_string()),
+            force: false,
+        };
+        let err = handle_donecmd(&conn, &followup_cmd("1,2")).unwrap_err();
+        assert!(err.contains("single task"), "{}", err);
+
+        handle_donecmd(&conn, &followup_cmd("1")).unwrap();
+        let original = get_item(&conn, report).unwrap();
+        assert_eq!(original.status, 1);
+        assert_eq!(original.content, "weekly report\nsent");
+
+        let open = query_items(
+            &conn,
+            &ItemQuery::new().with_action(TASK).with_statuses(vec![0]),
+        )
+        .unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].content, "weekly report");
+        assert_eq!(open[0].category, "work");
+        assert_eq!(open[0].target_time, Some(timestr::to_unix_epoch("2099/01/08").unwrap()));
+
+        // Recurring tasks come back on their own schedule
+        let mut diary_cmd = followup_cmd("1");
+        diary_cmd.target = None;
+        diary_cmd.pattern = Some("diary".to_string());
+        let err = handle_donecmd(&conn, &diary_cmd).unwrap_err();
+        assert!(err.contains("Only regular tasks"), "{}", err);
+    }
 }
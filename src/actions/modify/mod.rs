@@ -1,25 +1,75 @@
 mod done;
 mod update;
 
-pub use done::handle_donecmd;
-pub use update::{handle_deletecmd, handle_updatecmd};
+pub use done::{handle_cancelcmd, handle_donecmd};
+pub use update::{handle_deletecmd, handle_pincmd, handle_startcmd, handle_updatecmd};
 
 use rusqlite::Connection;
 
-use crate::db::cache;
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+    },
+    db::{
+        cache,
+        crud::query_items,
+        item::{
+            ItemQuery,
+            RECURRING_TASK,
+            TASK,
+        },
+    },
+    error::TascliError,
+};
 
-pub(super) fn validate_cache(conn: &Connection) -> Result<(), String> {
-    match cache::validate_cache(conn) {
-        Ok(true) => Ok(()),
-        Ok(false) => Err("Cache is not valid, considering running list command first".to_string()),
-        Err(_) => Err("Cannot connect to cache".to_string()),
+pub(crate) fn validate_cache(conn: &Connection) -> Result<(), TascliError> {
+    if cache::validate_cache(conn)? {
+        Ok(())
+    } else {
+        Err(TascliError::CacheInvalid)
     }
 }
 
-pub(super) fn get_rowid_from_cache(conn: &Connection, index: usize) -> Result<i64, String> {
-    let index = index as i64;
-    match cache::read(conn, index).map_err(|e| format!("Failed to read cache table: {:?}", e))? {
-        Some(id) => Ok(id),
-        None => Err(format!("index {} does not exist", index)),
+pub(crate) fn get_rowid_from_cache(conn: &Connection, index: usize) -> Result<i64, TascliError> {
+    cache::read(conn, index as i64)?.ok_or(TascliError::IndexNotFound(index))
+}
+
+// Either the indices from the previous list command, or the one open task matching the pattern.
+pub(super) fn resolve_row_ids(
+    conn: &Connection,
+    indices: Option<&[usize]>,
+    pattern: Option<&str>,
+) -> Result<Vec<i64>, String> {
+    if let Some(pattern) = pattern {
+        return find_unique_match(conn, pattern).map(|row_id| vec![row_id]);
+    }
+    validate_cache(conn)?;
+    indices
+        .unwrap_or_default()
+        .iter()
+        .map(|&index| get_rowid_from_cache(conn, index))
+        .collect::<Result<_, TascliError>>()
+        .map_err(String::from)
+}
+
+// Several matches are shown so the pattern can be narrowed.
+pub(super) fn find_unique_match(conn: &Connection, pattern: &str) -> Result<i64, String> {
+    let query = ItemQuery::new()
+        .with_actions(vec![TASK, RECURRING_TASK])
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_content_like(pattern);
+    let matches = query_items(conn, &query).map_err(|e| e.to_string())?;
+    match &matches[..] {
+        [] => Err(format!("No open task matches '{}'", pattern)),
+        [item] => Ok(item.id.unwrap()),
+        _ => {
+            display::print_items(&matches, false);
+            Err(format!(
+                "{} open tasks match '{}', narrow it down or use an index",
+                matches.len(),
+                pattern
+            ))
+        }
     }
 }
@@ -1,25 +1,54 @@
 use std::{io, io::Write};
 
+use chrono::Local;
 use rusqlite::Connection;
 
-use super::{get_rowid_from_cache, validate_cache};
+use super::{get_rowid_from_cache, resolve_row_ids, validate_cache};
 use crate::{
-    actions::display,
+    actions::{addition::limit_content, display},
     args::{
-        parser::{DeleteCommand, UpdateCommand},
+        cron,
+        parser::{DeleteCommand, PinCommand, StartCommand, UpdateCommand},
         timestr,
     },
+    config::get_config,
     db::{
         crud::{delete_item, get_item, update_item},
-        item::{RECORD, RECURRING_TASK, RECURRING_TASK_RECORD},
+        item::{Item, CLOSED_STATUS_CODES, RECORD, RECURRING_TASK, RECURRING_TASK_RECORD, TASK},
     },
 };
 
+const PREVIEW_COUNT: usize = 3;
+
 pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), String> {
-    validate_cache(conn)?;
-    let row_id = get_rowid_from_cache(conn, cmd.index)?;
-    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    let row_ids = resolve_row_ids(conn, cmd.indices.as_deref(), cmd.pattern.as_deref())?;
+    let max_content_length = get_config().max_content_length();
+    for row_id in row_ids {
+        update_by_id(conn, cmd, row_id, max_content_length)?;
+    }
+    Ok(())
+}
+
+fn update_by_id(
+    conn: &Connection,
+    cmd: &UpdateCommand,
+    row_id: i64,
+    max_content_length: usize,
+) -> Result<(), String> {
+    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {}", e))?;
+    if cmd.preview && item.action != RECURRING_TASK {
+        return Err("--preview only applies to recurring task schedules".to_string());
+    }
+    if let Some(schedule_str) = &cmd.make_recurring {
+        make_recurring(&mut item, schedule_str)?;
+    }
 
+    if cmd.do_date.is_some() && item.action != TASK {
+        return Err(format!("Only tasks have a do date, found {}", item.action));
+    }
+    if cmd.remind.is_some() && item.action != TASK && item.action != RECURRING_TASK {
+        return Err(format!("Only tasks have a reminder lead, found {}", item.action));
+    }
     if item.action == RECURRING_TASK {
         if cmd.status.is_some() {
             return Err("Cannot update status for recurring tasks".to_string());
@@ -33,6 +62,16 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
         if let Some(schedule_str) = &cmd.target_time {
             match timestr::parse_recurring_timestr(schedule_str) {
                 Ok(cron_schedule) => {
+                    if cmd.preview {
+                        display::print_bold(&format!("Cron: {}", cron_schedule));
+                        display::print_bold("Next occurrences:");
+                        for line in schedule_preview(&cron_schedule)? {
+                            println!("  {}", line);
+                        }
+                        if !prompt_yes_no("Save this schedule?") {
+                            return Err("Not updating the schedule".to_string());
+                        }
+                    }
                     item.cron_schedule = Some(cron_schedule);
                     item.human_schedule = Some(schedule_str.clone());
                 }
@@ -46,15 +85,38 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
         if let Some(content) = &cmd.content {
             item.content = content.clone();
         }
+        if let Some(note) = &cmd.note {
+            item.note = Some(note.clone());
+        }
+        if let Some(reference) = &cmd.reference {
+            item.reference = Some(reference.clone());
+        }
+        if let Some(remind) = cmd.remind {
+            item.remind_before_minutes = Some(remind);
+        }
+        // Only checked when content changes, older oversized items stay editable.
+        if cmd.content.is_some() {
+            item.content = limit_content(item.content, max_content_length, cmd.truncate)?;
+        }
 
-        update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
+        update_item(conn, &item).map_err(|e| format!("Failed to update item: {}", e))?;
         display::print_bold("Updated Recurring Task:");
         display::print_items(&[item], false);
         return Ok(());
     }
 
     if let Some(target) = &cmd.target_time {
-        item.target_time = Some(timestr::to_unix_epoch(target)?);
+        let target_time = timestr::to_unix_epoch(target).map_err(|e| {
+            if item.action == TASK && timestr::parse_recurring_timestr(target).is_ok() {
+                format!("{}, use --make-recurring to turn the task into a recurring one", e)
+            } else {
+                e
+            }
+        })?;
+        item.target_time = Some(target_time);
+    }
+    if let Some(do_date) = &cmd.do_date {
+        item.do_date = Some(timestr::to_unix_epoch(do_date)?);
     }
     if let Some(category) = &cmd.category {
         item.category = category.clone();
@@ -62,8 +124,16 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
     if let Some(content) = &cmd.content {
         item.content = content.clone();
     }
+    if let Some(note) = &cmd.note {
+        item.note = Some(note.clone());
+    }
+    if let Some(reference) = &cmd.reference {
+        item.reference = Some(reference.clone());
+    }
+    if let Some(remind) = cmd.remind {
+        item.remind_before_minutes = Some(remind);
+    }
     if let Some(add) = &cmd.add_content {
-        use chrono::Local;
         let timestamp = Local::now().format("%Y-%m-%d %H:%M").to_string();
         item.content.push('\n');
         item.content.push_str(&format!("{} ({})", add, timestamp));
@@ -71,8 +141,11 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
     if let Some(status) = cmd.status {
         item.status = status;
     }
+    if cmd.content.is_some() || cmd.add_content.is_some() {
+        item.content = limit_content(item.content, max_content_length, cmd.truncate)?;
+    }
 
-    update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
+    update_item(conn, &item).map_err(|e| format!("Failed to update item: {}", e))?;
 
     let is_record = item.action == RECORD || item.action == RECURRING_TASK_RECORD;
     let action = if is_record { "Record" } else { "Task" };
@@ -81,21 +154,95 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
     Ok(())
 }
 
-pub fn handle_deletecmd(conn: &Connection, cmd: &DeleteCommand) -> Result<(), String> {
+// The due time gives way to the schedule, recurring tasks have no status.
+fn make_recurring(item: &mut Item, schedule_str: &str) -> Result<(), String> {
+    if item.action != TASK {
+        return Err(format!("Only tasks can be made recurring, found {}", item.action));
+    }
+    let cron_schedule = timestr::parse_recurring_timestr(schedule_str)?;
+    item.action = RECURRING_TASK.to_string();
+    item.cron_schedule = Some(cron_schedule);
+    item.human_schedule = Some(schedule_str.to_string());
+    item.target_time = None;
+    item.do_date = None;
+    item.status = 0;
+    Ok(())
+}
+
+pub fn handle_startcmd(conn: &Connection, cmd: &StartCommand) -> Result<(), String> {
+    validate_cache(conn)?;
+    let row_id = get_rowid_from_cache(conn, cmd.index)?;
+    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {}", e))?;
+    if item.action != TASK {
+        return Err(format!("Only tasks can be started, found {}", item.action));
+    }
+    // A closed task is only reopened with force, like completing it again
+    if CLOSED_STATUS_CODES.contains(&item.status) && !cmd.force {
+        return Err(format!(
+            "Task '{}' is already {}, pass --force to start it again",
+            item.content,
+            display::translate_status(item.status)
+        ));
+    }
+
+    item.status = 7;
+    update_item(conn, &item).map_err(|e| format!("Failed to update item: {}", e))?;
+    display::print_bold("Started Task:");
+    display::print_items(&[item], false);
+    Ok(())
+}
+
+pub fn handle_pincmd(conn: &Connection, cmd: &PinCommand, pinned: bool) -> Result<(), String> {
     validate_cache(conn)?;
     let row_id = get_rowid_from_cache(conn, cmd.index)?;
-    let item = get_item(conn, row_id).map_err(|e| format!("Failed to find item: {:?}", e))?;
-    let item_type = item.action.clone();
+    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {}", e))?;
+    if item.action != TASK && item.action != RECURRING_TASK {
+        return Err(format!("Only tasks can be pinned, found {}", item.action));
+    }
+
+    item.pinned = pinned;
+    update_item(conn, &item).map_err(|e| format!("Failed to update item: {}", e))?;
+    display::print_bold(if pinned { "Pinned Task:" } else { "Unpinned Task:" });
     display::print_items(&[item], false);
+    Ok(())
+}
+
+pub fn handle_deletecmd(conn: &Connection, cmd: &DeleteCommand) -> Result<(), String> {
+    let items = resolve_row_ids(conn, cmd.indices.as_deref(), cmd.pattern.as_deref())?
+        .into_iter()
+        .map(|row_id| {
+            get_item(conn, row_id).map_err(|e| format!("Failed to find item: {}", e))
+        })
+        .collect::<Result<Vec<Item>, String>>()?;
+    display::print_items(&items, false);
 
-    if !prompt_yes_no(&format!("Are you sure you want to delete this {}? ", &item_type)) {
+    let (this, item_type) = match &items[..] {
+        [item] => ("this", item.action.clone()),
+        _ => ("these", format!("{} items", items.len())),
+    };
+    if !prompt_yes_no(&format!("Are you sure you want to delete {} {}? ", this, &item_type)) {
         return Err(format!("Not deleting the {}", &item_type));
     }
-    delete_item(conn, row_id).map_err(|e| format!("Failed to delete item: {:?}", e))?;
+    for item in &items {
+        delete_item(conn, item.id.unwrap())
+            .map_err(|e| format!("Failed to delete item: {}", e))?;
+    }
     display::print_bold("Deletion success");
     Ok(())
 }
 
+// Upcoming occurrences of a schedule, formatted for display.
+fn schedule_preview(cron_schedule: &str) -> Result<Vec<String>, String> {
+    cron::get_next_occurrences(cron_schedule, PREVIEW_COUNT)?
+        .into_iter()
+        .map(|ts| {
+            timestr::timestamp_to_naive(ts)
+                .map(|dt| dt.format("%Y/%m/%d %a %H:%M").to_string())
+                .ok_or_else(|| format!("Invalid timestamp: {}", ts))
+        })
+        .collect()
+}
+
 fn prompt_yes_no(question: &str) -> bool {
     print!("{} (y/n): ", question);
     io::stdout().flush().unwrap();
@@ -113,9 +260,23 @@ mod tests {
             crud::{get_item, query_items},
             item::{ItemQuery, TASK},
         },
-        tests::{get_test_conn, insert_recurring_task, insert_task},
+        error::TascliError,
+        tests::{get_test_conn, insert_record, insert_recurring_task, insert_task, update_status},
     };
 
+    #[test]
+    fn test_cache_lookup_errors() {
+        let (conn, _temp_file) = get_test_conn();
+        assert!(matches!(validate_cache(&conn), Err(TascliError::CacheInvalid)));
+
+        insert_task(&conn, "home", "clean garage", "saturday");
+        let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        cache::store(&conn, &items).unwrap();
+        assert!(validate_cache(&conn).is_ok());
+        assert_eq!(get_rowid_from_cache(&conn, 1).unwrap(), items[0].id.unwrap());
+        assert!(matches!(get_rowid_from_cache(&conn, 2), Err(TascliError::IndexNotFound(2))));
+    }
+
     #[test]
     fn test_handle_updatecmd() {
         let (conn, _temp_file) = get_test_conn();
@@ -125,24 +286,40 @@ mod tests {
         let item_id = cache::read(&conn, 1).unwrap().unwrap();
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            indices: Some(vec![1]),
+            pattern: None,
             target_time: None,
             category: None,
             content: Some("reorganize garage thoroughly".to_string()),
             add_content: None,
+            note: None,
+            reference: None,
+            do_date: None,
+            remind: None,
             status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         handle_updatecmd(&conn, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.content, "reorganize garage thoroughly");
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            indices: Some(vec![1]),
+            pattern: None,
             target_time: None,
             category: None,
             content: None,
             add_content: Some("move stuff to basement".to_string()),
+            note: None,
+            reference: None,
+            do_date: None,
+            remind: None,
             status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         handle_updatecmd(&conn, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
@@ -150,30 +327,115 @@ mod tests {
         assert!(updated_item.content.ends_with(")"));
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            indices: Some(vec![1]),
+            pattern: None,
             target_time: None,
             category: None,
             content: None,
             add_content: None,
+            note: None,
+            reference: None,
+            do_date: None,
+            remind: None,
             status: Some(3),
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         handle_updatecmd(&conn, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.status, 3);
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            indices: Some(vec![1]),
+            pattern: None,
             target_time: Some("eow".to_string()),
             category: Some("chore".to_string()),
             content: None,
             add_content: None,
+            note: None,
+            reference: None,
+            do_date: None,
+            remind: None,
             status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         handle_updatecmd(&conn, &update_cmd).unwrap();
         let got_item = get_item(&conn, item_id).unwrap();
         assert_eq!(got_item.category, "chore");
     }
 
+    #[test]
+    fn test_update_content_length() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "home", "clean the whole garage", "saturday");
+        let recurring_id = insert_recurring_task(&conn, "life", "write the daily diary", "Daily 9PM");
+        let update_cmd = |content: Option<&str>, truncate: bool| UpdateCommand {
+            indices: None,
+            pattern: None,
+            target_time: None,
+            category: Some("chore".to_string()),
+            content: content.map(|c| c.to_string()),
+            add_content: None,
+            note: None,
+            reference: None,
+            do_date: None,
+            remind: None,
+            status: None,
+            truncate,
+            preview: false,
+            make_recurring: None,
+        };
+
+        // Content over the limit is left alone unless it changes
+        for id in [task_id, recurring_id] {
+            update_by_id(&conn, &update_cmd(None, false), id, 10).unwrap();
+            assert_eq!(get_item(&conn, id).unwrap().category, "chore");
+
+            let err = update_by_id(&conn, &update_cmd(Some("far too long content"), false), id, 10)
+                .unwrap_err();
+            assert!(err.starts_with("Content is 20 characters"), "{}", err);
+            update_by_id(&conn, &update_cmd(Some("far too long content"), true), id, 10).unwrap();
+            assert_eq!(get_item(&conn, id).unwrap().content, "far too lo");
+        }
+    }
+
+    #[test]
+    fn test_handle_updatecmd_multiple_indices() {
+        let (conn, _temp_file) = get_test_conn();
+        for i in 1..=4 {
+            insert_task(&conn, "home", &format!("chore {}", i), "saturday");
+        }
+        let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        cache::store(&conn, &items).unwrap();
+
+        let update_cmd = UpdateCommand {
+            indices: Some(vec![1, 2, 4]),
+            pattern: None,
+            target_time: None,
+            category: Some("chore".to_string()),
+            content: None,
+            add_content: None,
+            note: None,
+            reference: None,
+            do_date: None,
+            remind: None,
+            status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
+        };
+        handle_updatecmd(&conn, &update_cmd).unwrap();
+        let categories: Vec<String> = query_items(&conn, &ItemQuery::new().with_action(TASK))
+            .unwrap()
+            .into_iter()
+            .map(|t| t.category)
+            .collect();
+        assert_eq!(categories, vec!["chore", "chore", "home", "chore"]);
+    }
+
     #[test]
     fn test_handle_updatecmd_recurring_task() {
         let (conn, _temp_file) = get_test_conn();
@@ -182,12 +444,20 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            indices: Some(vec![1]),
+            pattern: None,
             target_time: None,
             category: Some("meetings".to_string()),
             content: Some("Daily team sync".to_string()),
             add_content: None,
+            note: None,
+            reference: None,
+            do_date: None,
+            remind: None,
             status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         assert!(handle_updatecmd(&conn, &update_cmd).is_ok());
 
@@ -196,12 +466,20 @@ mod tests {
         assert_eq!(updated_item.category, "meetings");
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            indices: Some(vec![1]),
+            pattern: None,
             target_time: Some("Daily 3PM".to_string()),
             category: None,
             content: None,
             add_content: None,
+            note: None,
+            reference: None,
+            do_date: None,
+            remind: None,
             status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         assert!(handle_updatecmd(&conn, &update_cmd).is_ok());
         let updated_item = get_item(&conn, task_id).unwrap();
@@ -209,16 +487,24 @@ mod tests {
         assert_eq!(updated_item.human_schedule, Some("Daily 3PM".to_string()));
 
         let update_cmd = UpdateCommand {
-            index: 1, target_time: None, category: None, content: None,
-            add_content: None, status: Some(1),
+            indices: Some(vec![1]),
+            pattern: None, target_time: None, category: None, content: None,
+            add_content: None, note: None, reference: None, do_date: None, remind: None, status: Some(1),
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Cannot update status for recurring tasks");
 
         let update_cmd = UpdateCommand {
-            index: 1, target_time: None, category: None, content: None,
-            add_content: Some("extra notes".to_string()), status: None,
+            indices: Some(vec![1]),
+            pattern: None, target_time: None, category: None, content: None,
+            add_content: Some("extra notes".to_string()), note: None, reference: None, do_date: None, remind: None, status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_err());
@@ -228,6 +514,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_handle_updatecmd_by_match() {
+        let (conn, _temp_file) = get_test_conn();
+        let report_id = insert_task(&conn, "work", "finish report", "tomorrow");
+        insert_task(&conn, "work", "review report", "tomorrow");
+        let update_cmd = |pattern: &str| UpdateCommand {
+            indices: None,
+            pattern: Some(pattern.to_string()),
+            target_time: None,
+            category: Some("urgent".to_string()),
+            content: None, add_content: None, note: None, reference: None, do_date: None, remind: None, status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
+        };
+
+        let result = handle_updatecmd(&conn, &update_cmd("report"));
+        assert!(result.unwrap_err().starts_with("2 open tasks match 'report'"));
+        handle_updatecmd(&conn, &update_cmd("finish")).unwrap();
+        assert_eq!(get_item(&conn, report_id).unwrap().category, "urgent");
+    }
+
+    #[test]
+    fn test_schedule_preview() {
+        let cron_schedule = timestr::parse_recurring_timestr("Weekly Monday 9AM").unwrap();
+        assert_eq!(cron_schedule, "0 9 * * 1");
+        let preview = schedule_preview(&cron_schedule).unwrap();
+        assert_eq!(preview.len(), 3);
+        assert!(preview.iter().all(|line| line.ends_with("Mon 09:00")), "{:?}", preview);
+        assert!(preview.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", preview);
+
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "finish report", "tomorrow");
+        let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        cache::store(&conn, &items).unwrap();
+        let update_cmd = UpdateCommand {
+            indices: Some(vec![1]),
+            pattern: None,
+            target_time: Some("friday".to_string()),
+            category: None, content: None, add_content: None, note: None, reference: None, do_date: None, remind: None, status: None,
+            truncate: false,
+            preview: true,
+            make_recurring: None,
+        };
+        let result = handle_updatecmd(&conn, &update_cmd);
+        assert!(result.unwrap_err().contains("only applies to recurring"));
+    }
+
     #[test]
     fn test_block_task_conversions() {
         let (conn, _temp_file) = get_test_conn();
@@ -237,13 +571,19 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            indices: Some(vec![1]),
+            pattern: None,
             target_time: Some("Daily 9AM".to_string()),
-            category: None, content: None, add_content: None, status: None,
+            category: None, content: None, add_content: None, note: None, reference: None, do_date: None, remind: None, status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         let result = handle_updatecmd(&conn, &update_cmd);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Couldn't parse"));
+        let err = result.unwrap_err();
+        assert!(err.contains("Couldn't parse"));
+        assert!(err.contains("--make-recurring"), "{}", err);
 
         cache::clear(&conn).unwrap();
         insert_recurring_task(&conn, "work", "Daily standup", "Daily 9AM");
@@ -251,10 +591,163 @@ mod tests {
         cache::store(&conn, &items).unwrap();
 
         let update_cmd = UpdateCommand {
-            index: 1,
+            indices: Some(vec![1]),
+            pattern: None,
             target_time: Some("tomorrow".to_string()),
-            category: None, content: None, add_content: None, status: None,
+            category: None, content: None, add_content: None, note: None, reference: None, do_date: None, remind: None, status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
         };
         assert!(handle_updatecmd(&conn, &update_cmd).is_err());
     }
+
+    #[test]
+    fn test_update_do_date() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "home", "clean garage", "2099/03/06");
+        let record_id = insert_record(&conn, "home", "swept porch", "today");
+        let mut items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        items.push(get_item(&conn, record_id).unwrap());
+        cache::store(&conn, &items).unwrap();
+
+        let mut update_cmd = UpdateCommand {
+            indices: Some(vec![1]),
+            pattern: None, target_time: None, category: None, content: None,
+            add_content: None, note: None, reference: None, do_date: Some("saturday".to_string()), remind: None, status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
+        };
+        handle_updatecmd(&conn, &update_cmd).unwrap();
+        let task = get_item(&conn, task_id).unwrap();
+        assert_eq!(task.do_date, Some(timestr::to_unix_epoch("saturday").unwrap()));
+        assert_eq!(task.target_time, Some(timestr::to_unix_epoch("2099/03/06").unwrap()));
+
+        update_cmd.indices = Some(vec![2]);
+        let err = handle_updatecmd(&conn, &update_cmd).unwrap_err();
+        assert!(err.contains("Only tasks have a do date"), "{}", err);
+    }
+
+    #[test]
+    fn test_update_remind() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "life", "renew passport", "2099/06/01");
+        let recurring_id = insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        let record_id = insert_record(&conn, "life", "booked photos", "today");
+        let items = vec![
+            get_item(&conn, task_id).unwrap(),
+            get_item(&conn, recurring_id).unwrap(),
+            get_item(&conn, record_id).unwrap(),
+        ];
+        cache::store(&conn, &items).unwrap();
+
+        let mut update_cmd = UpdateCommand {
+            indices: Some(vec![1, 2]),
+            pattern: None, target_time: None, category: None, content: None,
+            add_content: None, note: None, reference: None, do_date: None, remind: Some(24 * 60), status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: None,
+        };
+        handle_updatecmd(&conn, &update_cmd).unwrap();
+        assert_eq!(get_item(&conn, task_id).unwrap().remind_before_minutes, Some(24 * 60));
+        assert_eq!(get_item(&conn, recurring_id).unwrap().remind_before_minutes, Some(24 * 60));
+
+        update_cmd.indices = Some(vec![3]);
+        let err = handle_updatecmd(&conn, &update_cmd).unwrap_err();
+        assert!(err.contains("Only tasks have a reminder lead"), "{}", err);
+    }
+
+    #[test]
+    fn test_make_recurring() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "life", "water plants", "tomorrow");
+        update_status(&conn, task_id, 4);
+        let record_id = insert_record(&conn, "life", "watered plants", "today");
+        let items = vec![get_item(&conn, task_id).unwrap(), get_item(&conn, record_id).unwrap()];
+        cache::store(&conn, &items).unwrap();
+        let make_recurring = |index: usize| UpdateCommand {
+            indices: Some(vec![index]),
+            pattern: None,
+            target_time: None,
+            category: None, content: None, add_content: None, note: None, reference: None, do_date: None, remind: None, status: None,
+            truncate: false,
+            preview: false,
+            make_recurring: Some("Daily 9AM".to_string()),
+        };
+
+        handle_updatecmd(&conn, &make_recurring(1)).unwrap();
+        let converted = get_item(&conn, task_id).unwrap();
+        assert_eq!(converted.action, RECURRING_TASK);
+        assert_eq!(converted.cron_schedule, Some("0 9 * * *".to_string()));
+        assert_eq!(converted.human_schedule, Some("Daily 9AM".to_string()));
+        assert_eq!(converted.target_time, None);
+        assert_eq!(converted.status, 0);
+        let recurring = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK)).unwrap();
+        assert_eq!(recurring.len(), 1);
+        assert_eq!(recurring[0].content, "water plants");
+        assert!(query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap().is_empty());
+
+        let result = handle_updatecmd(&conn, &make_recurring(1));
+        assert_eq!(result.unwrap_err(), "Only tasks can be made recurring, found recurring_task");
+        let result = handle_updatecmd(&conn, &make_recurring(2));
+        assert_eq!(result.unwrap_err(), "Only tasks can be made recurring, found record");
+        assert_eq!(get_item(&conn, record_id).unwrap().action, RECORD);
+    }
+
+    #[test]
+    fn test_handle_startcmd() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "work", "write report", "tomorrow");
+        let recurring_id = insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        let items = vec![get_item(&conn, task_id).unwrap(), get_item(&conn, recurring_id).unwrap()];
+        cache::store(&conn, &items).unwrap();
+
+        handle_startcmd(&conn, &StartCommand { index: 1, force: false }).unwrap();
+        assert_eq!(get_item(&conn, task_id).unwrap().status, 7);
+
+        let start_cmd = StartCommand { index: 2, force: false };
+        assert!(handle_startcmd(&conn, &start_cmd).is_err());
+        assert_eq!(get_item(&conn, recurring_id).unwrap().status, 0);
+
+        // A closed task is only started again with force
+        update_status(&conn, task_id, 1);
+        let mut start_cmd = StartCommand { index: 1, force: false };
+        assert_eq!(
+            handle_startcmd(&conn, &start_cmd).unwrap_err(),
+            "Task 'write report' is already completed, pass --force to start it again"
+        );
+        assert_eq!(get_item(&conn, task_id).unwrap().status, 1);
+        start_cmd.force = true;
+        handle_startcmd(&conn, &start_cmd).unwrap();
+        assert_eq!(get_item(&conn, task_id).unwrap().status, 7);
+    }
+
+    #[test]
+    fn test_handle_pincmd() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "work", "quarterly goals", "eom");
+        let recurring_id = insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        let record_id = insert_record(&conn, "work", "met goals", "today");
+        let items = vec![
+            get_item(&conn, task_id).unwrap(),
+            get_item(&conn, recurring_id).unwrap(),
+            get_item(&conn, record_id).unwrap(),
+        ];
+        cache::store(&conn, &items).unwrap();
+
+        handle_pincmd(&conn, &PinCommand { index: 1 }, true).unwrap();
+        handle_pincmd(&conn, &PinCommand { index: 2 }, true).unwrap();
+        assert!(get_item(&conn, task_id).unwrap().pinned);
+        assert!(get_item(&conn, recurring_id).unwrap().pinned);
+
+        handle_pincmd(&conn, &PinCommand { index: 1 }, false).unwrap();
+        assert!(!get_item(&conn, task_id).unwrap().pinned);
+        assert!(get_item(&conn, recurring_id).unwrap().pinned);
+
+        let result = handle_pincmd(&conn, &PinCommand { index: 3 }, true);
+        assert_eq!(result.unwrap_err(), "Only tasks can be pinned, found record");
+        assert!(!get_item(&conn, record_id).unwrap().pinned);
+    }
 }
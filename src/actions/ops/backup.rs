@@ -1,6 +1,9 @@
 use std::{
     fs,
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
 
 use crate::{
@@ -10,28 +13,43 @@ use crate::{
         get_data_path,
         str_to_pathbuf,
     },
+    db::conn::checkpoint,
 };
 
 const BACKUP_FILENAME: &str = "tascli_bak.db";
 
 pub fn handle_backupcmd(cmd: &OpsBackupCommand) -> Result<(), String> {
-    backup_path(cmd.path.clone())
+    let dest_path = create_backup(cmd.path.clone())?;
+    if cmd.quiet {
+        // Bare path so scripts can capture it
+        println!("{}", dest_path.display());
+    } else {
+        display::print_bold(&format!("Backed up to: {}", dest_path.display()));
+    }
+    Ok(())
 }
 
 pub fn backup_path(path: Option<String>) -> Result<(), String> {
+    let dest_path = create_backup(path)?;
+    display::print_bold(&format!("Backed up to: {}", dest_path.display()));
+    Ok(())
+}
+
+// Copies the database and returns the absolute path of the backup.
+fn create_backup(path: Option<String>) -> Result<PathBuf, String> {
     let source_path = get_data_path()?;
     if !source_path.exists() {
         return Err("Source database does not exist".to_string());
     }
 
     let dest_path = resolve_dest_path(&source_path, path)?;
+    checkpoint(&source_path).map_err(|e| format!("Failed to checkpoint database: {}", e))?;
     fs::copy(&source_path, &dest_path).map_err(|e| format!("Failed to backup database: {}", e))?;
 
-    display::print_bold(&format!("Backed up to: {}", dest_path.display()));
-    Ok(())
+    fs::canonicalize(&dest_path).map_err(|e| format!("Failed to resolve backup path: {}", e))
 }
 
-fn resolve_dest_path(source_path: &PathBuf, path: Option<String>) -> Result<PathBuf, String> {
+fn resolve_dest_path(source_path: &Path, path: Option<String>) -> Result<PathBuf, String> {
     match path {
         None => {
             let parent = source_path
@@ -45,10 +63,11 @@ fn resolve_dest_path(source_path: &PathBuf, path: Option<String>) -> Result<Path
                 Ok(dest.join(BACKUP_FILENAME))
             } else {
                 // Ensure parent directory exists
-                if let Some(parent) = dest.parent() {
-                    if !parent.as_os_str().is_empty() && !parent.exists() {
-                        return Err(format!("Directory does not exist: {}", parent.display()));
-                    }
+                if let Some(parent) = dest.parent()
+                    && !parent.as_os_str().is_empty()
+                    && !parent.exists()
+                {
+                    return Err(format!("Directory does not exist: {}", parent.display()));
                 }
                 Ok(dest)
             }
@@ -1,5 +1,6 @@
 use std::io::{
     self,
+    BufRead,
     Write,
 };
 
@@ -18,9 +19,13 @@ use crate::{
         parser::OpsBatchCommand,
         timestr,
     },
+    config::get_config,
     db::{
         crud::query_items,
         item::{
+            add_tag,
+            remove_tag,
+            tag_list,
             Item,
             ItemQuery,
         },
@@ -32,6 +37,9 @@ use crate::{
     },
 };
 
+// Items shown in the before and after preview of a bulk update.
+const PREVIEW_LIMIT: usize = 10;
+
 pub fn handle_batchcmd(conn: &Connection, cmd: &OpsBatchCommand) -> Result<(), String> {
     let actions = parse_action_filter(&cmd.action)?;
 
@@ -46,7 +54,7 @@ pub fn handle_batchcmd(conn: &Connection, cmd: &OpsBatchCommand) -> Result<(), S
 
     if cmd.status_to.is_some_and(|s| s >= 240) {
         return Err(
-            "--status-to requires a concrete status: ongoing|done|cancelled|duplicate|suspended|removed|pending"
+            "--status-to requires a concrete status: ongoing|done|cancelled|duplicate|suspended|removed|pending|in_progress"
                 .to_string(),
         );
     }
@@ -55,9 +63,11 @@ pub fn handle_batchcmd(conn: &Connection, cmd: &OpsBatchCommand) -> Result<(), S
         && cmd.category_to.is_none()
         && cmd.status_to.is_none()
         && cmd.target_time_to.is_none()
+        && cmd.add_tag.is_none()
+        && cmd.remove_tag.is_none()
     {
         return Err(
-            "Must specify an operation: --delete, --category-to, --status-to, or --target-time-to"
+            "Must specify an operation: --delete, --category-to, --status-to, --target-time-to, --add-tag or --remove-tag"
                 .to_string(),
         );
     }
@@ -106,6 +116,8 @@ pub fn handle_batchcmd(conn: &Connection, cmd: &OpsBatchCommand) -> Result<(), S
             category: cmd.category_to.clone(),
             status: cmd.status_to,
             target_time,
+            add_tag: cmd.add_tag.clone(),
+            remove_tag: cmd.remove_tag.clone(),
         })
     };
 
@@ -128,15 +140,17 @@ fn execute_bulk(
     display::print_items(items, true);
     println!();
     print_operation_description(cmd);
+    if let Some(updates) = updates {
+        display::print_bold("Changes:");
+        for line in update_preview_lines(items, updates, PREVIEW_LIMIT) {
+            println!("{}", line);
+        }
+    }
 
-    print!("\nProceed? (y/n): ");
-    io::stdout().flush().unwrap();
-    let mut input = String::new();
-    io::stdin()
-        .read_line(&mut input)
-        .map_err(|e| e.to_string())?;
-
-    if input.trim().to_lowercase() != "y" {
+    let threshold = cmd
+        .confirm_threshold
+        .or_else(|| get_config().confirm_threshold());
+    if !confirm_proceed(&mut io::stdin().lock(), items.len(), threshold)? {
         display::print_bold("Cancelled");
         return Ok(());
     }
@@ -151,6 +165,28 @@ fn execute_bulk(
     Ok(())
 }
 
+// Above the threshold a single y is not enough, the exact count has to be typed.
+fn confirm_proceed(
+    input: &mut impl BufRead,
+    count: usize,
+    threshold: Option<usize>,
+) -> Result<bool, String> {
+    let expected = match threshold {
+        Some(t) if count > t => {
+            print!("\nType {} to proceed: ", count);
+            count.to_string()
+        }
+        _ => {
+            print!("\nProceed? (y/n): ");
+            "y".to_string()
+        }
+    };
+    io::stdout().flush().unwrap();
+    let mut answer = String::new();
+    input.read_line(&mut answer).map_err(|e| e.to_string())?;
+    Ok(answer.trim().to_lowercase() == expected)
+}
+
 fn execute_interactive(
     conn: &Connection,
     items: &[Item],
@@ -229,6 +265,73 @@ fn print_operation_description(cmd: &OpsBatchCommand) {
         if let Some(ref time) = cmd.target_time_to {
             println!("  • Change target_time to: {}", time);
         }
+        if let Some(ref tag) = cmd.add_tag {
+            println!("  • Add tag: {}", tag);
+        }
+        if let Some(ref tag) = cmd.remove_tag {
+            println!("  • Remove tag: {}", tag);
+        }
+    }
+}
+
+// One "field: old -> new" line per item, numbered like the listing above it.
+fn update_preview_lines(items: &[Item], updates: &ItemUpdates, limit: usize) -> Vec<String> {
+    let format_time = |t: Option<i64>| match t.and_then(timestr::timestamp_to_naive) {
+        Some(dt) => dt.format("%Y-%m-%d %H:%M").to_string(),
+        None => "none".to_string(),
+    };
+    let mut lines: Vec<String> = items
+        .iter()
+        .take(limit)
+        .enumerate()
+        .map(|(i, item)| {
+            let mut changes = Vec::new();
+            if let Some(category) = &updates.category {
+                changes.push(format!("category: {} -> {}", item.category, category));
+            }
+            if let Some(status) = updates.status {
+                changes.push(format!(
+                    "status: {} -> {}",
+                    status_to_string(item.status),
+                    status_to_string(status)
+                ));
+            }
+            if let Some(target_time) = updates.target_time {
+                changes.push(format!(
+                    "target_time: {} -> {}",
+                    format_time(item.target_time),
+                    format_time(Some(target_time))
+                ));
+            }
+            if updates.add_tag.is_some() || updates.remove_tag.is_some() {
+                let mut tags = item.tags.clone();
+                if let Some(tag) = &updates.add_tag {
+                    tags = add_tag(tags.as_deref(), tag);
+                }
+                if let Some(tag) = &updates.remove_tag {
+                    tags = remove_tag(tags.as_deref(), tag);
+                }
+                changes.push(format!(
+                    "tags: {} -> {}",
+                    format_tags(item.tags.as_deref()),
+                    format_tags(tags.as_deref())
+                ));
+            }
+            format!("  {}. {}", i + 1, changes.join(", "))
+        })
+        .collect();
+    if items.len() > limit {
+        lines.push(format!("  ... and {} more", items.len() - limit));
+    }
+    lines
+}
+
+fn format_tags(tags: Option<&str>) -> String {
+    let list = tag_list(tags);
+    if list.is_empty() {
+        "none".to_string()
+    } else {
+        list.join(",")
     }
 }
 
@@ -249,7 +352,7 @@ pub(crate) fn prompt_y_n_q(prompt: &str) -> Result<char, String> {
     }
 }
 
-fn pluralize(n: usize, word: &str) -> String {
+pub(super) fn pluralize(n: usize, word: &str) -> String {
     if n == 1 {
         format!("{} {}", n, word)
     } else {
@@ -257,7 +360,7 @@ fn pluralize(n: usize, word: &str) -> String {
     }
 }
 
-fn parse_action_filter(action: &str) -> Result<Option<Vec<String>>, String> {
+pub(super) fn parse_action_filter(action: &str) -> Result<Option<Vec<String>>, String> {
     match action {
         "all" => Ok(None),
         "task" | "record" | "recurring_task" | "recurring_task_record" => {
@@ -309,6 +412,7 @@ fn status_to_string(status: u8) -> &'static str {
         4 => "suspended",
         5 => "removed",
         6 => "pending",
+        7 => "in progress",
         _ => "unknown",
     }
 }
@@ -344,7 +448,10 @@ mod tests {
             category_to: None,
             status_to: None,
             target_time_to: None,
+            add_tag: None,
+            remove_tag: None,
             status: None,
+            confirm_threshold: None,
         };
 
         let failing = vec![
@@ -398,6 +505,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_confirm_proceed() {
+        let confirm = |answer: &str, count, threshold| {
+            confirm_proceed(&mut io::Cursor::new(answer), count, threshold).unwrap()
+        };
+        // At or below the threshold a y is enough
+        assert!(confirm("y\n", 3, None));
+        assert!(confirm("Y\n", 5, Some(5)));
+        assert!(!confirm("n\n", 5, Some(5)));
+        // Above it only the exact count proceeds
+        assert!(confirm("42\n", 42, Some(10)));
+        assert!(!confirm("y\n", 42, Some(10)));
+        assert!(!confirm("41\n", 42, Some(10)));
+        assert!(!confirm("", 42, Some(10)));
+    }
+
+    #[test]
+    fn test_update_preview_lines() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "task 1", "2099/03/06 9AM");
+        let id2 = insert_task(&conn, "home", "task 2", "2099/03/07 9AM");
+        update_status(&conn, id2, 6);
+        insert_task(&conn, "work", "task 3", "2099/03/08 9AM");
+        let items = query_items_for_batch(&conn, None, None, None, None, None).unwrap();
+
+        let updates = ItemUpdates {
+            category: Some("errands".to_string()),
+            status: Some(1),
+            target_time: Some(timestr::to_unix_epoch("2099/03/09 5PM").unwrap()),
+            add_tag: None,
+            remove_tag: None,
+        };
+        let lines = update_preview_lines(&items[..2], &updates, 10);
+        assert_eq!(
+            lines,
+            vec![
+                "  1. category: work -> errands, status: ongoing -> completed, target_time: 2099-03-06 09:00 -> 2099-03-09 17:00",
+                "  2. category: home -> errands, status: pending -> completed, target_time: 2099-03-07 09:00 -> 2099-03-09 17:00",
+            ]
+        );
+
+        // Only the changed fields, and larger sets are cut short
+        let updates = ItemUpdates {
+            category: Some("errands".to_string()),
+            status: None,
+            target_time: None,
+            add_tag: None,
+            remove_tag: None,
+        };
+        let lines = update_preview_lines(&items, &updates, 2);
+        assert_eq!(
+            lines,
+            vec![
+                "  1. category: work -> errands",
+                "  2. category: home -> errands",
+                "  ... and 1 more",
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_action_filter() {
         assert_eq!(parse_action_filter("all").unwrap(), None);
@@ -453,6 +620,8 @@ mod tests {
             category: Some("personal".to_string()),
             status: None,
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         assert_eq!(batch_update_items(&conn, &item_ids, &updates).unwrap(), 2);
 
@@ -467,6 +636,67 @@ mod tests {
         assert_eq!(query_items(&conn, &query).unwrap().len(), 1);
     }
 
+    #[test]
+    fn test_batch_add_and_remove_tag() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "task 1", "today");
+        insert_task(&conn, "work", "task 2", "today");
+        insert_record(&conn, "work", "record 1", "yesterday");
+        insert_task(&conn, "home", "task 3", "today");
+
+        let ids_of = |category| -> Vec<i64> {
+            query_items_for_batch(&conn, None, Some(category), None, None, None)
+                .unwrap()
+                .iter()
+                .map(|i| i.id.unwrap())
+                .collect()
+        };
+        let tags_of = |category| -> Vec<Option<String>> {
+            query_items_for_batch(&conn, None, Some(category), None, None, None)
+                .unwrap()
+                .into_iter()
+                .map(|i| i.tags)
+                .collect()
+        };
+        let tag_updates = |add_tag: Option<&str>, remove_tag: Option<&str>| ItemUpdates {
+            category: None,
+            status: None,
+            target_time: None,
+            add_tag: add_tag.map(String::from),
+            remove_tag: remove_tag.map(String::from),
+        };
+
+        // Adding twice keeps one copy, the category stays
+        let work_ids = ids_of("work");
+        for _ in 0..2 {
+            let updates = tag_updates(Some("urgent"), None);
+            assert_eq!(batch_update_items(&conn, &work_ids, &updates).unwrap(), 3);
+        }
+        assert_eq!(tags_of("work"), vec![Some(",urgent,".to_string()); 3]);
+        assert_eq!(tags_of("home"), vec![None]);
+
+        let updates = tag_updates(Some("q3"), None);
+        batch_update_items(&conn, &work_ids[..1], &updates).unwrap();
+        assert_eq!(tags_of("work")[0].as_deref(), Some(",urgent,q3,"));
+
+        // Removing leaves the other tags, and no tags at all is stored as NULL
+        let updates = tag_updates(None, Some("urgent"));
+        batch_update_items(&conn, &work_ids, &updates).unwrap();
+        assert_eq!(
+            tags_of("work"),
+            vec![Some(",q3,".to_string()), None, None]
+        );
+        batch_update_items(&conn, &work_ids, &updates).unwrap();
+        assert_eq!(tags_of("work")[0].as_deref(), Some(",q3,"));
+
+        let items = query_items_for_batch(&conn, None, Some("work"), None, None, None).unwrap();
+        let lines = update_preview_lines(&items[..2], &tag_updates(Some("urgent"), Some("q3")), 10);
+        assert_eq!(
+            lines,
+            vec!["  1. tags: q3 -> urgent", "  2. tags: none -> urgent"]
+        );
+    }
+
     #[test]
     fn test_batch_update_status() {
         let (conn, _temp_file) = get_test_conn();
@@ -479,6 +709,8 @@ mod tests {
             category: None,
             status: Some(1),
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         assert_eq!(batch_update_items(&conn, &[id1, id2], &updates).unwrap(), 2);
 
@@ -524,6 +756,8 @@ mod tests {
             category: Some("standup".to_string()),
             status: None,
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         assert_eq!(batch_update_items(&conn, &item_ids, &updates).unwrap(), 3);
 
@@ -575,6 +809,8 @@ mod tests {
             category: Some("person".to_string()),
             status: None,
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         assert_eq!(batch_update_items(&conn, &item_ids, &updates).unwrap(), 4);
 
@@ -593,7 +829,7 @@ mod tests {
         update_status(&conn, id2, 2); // cancelled
                                       // id3 remains ongoing (0)
 
-        // Filter by open (254 = ongoing|suspended|pending)
+        // Filter by open (254 = ongoing|suspended|pending|in_progress)
         let items =
             query_items_for_batch(&conn, None, Some("work"), Some(254), None, None).unwrap();
         assert_eq!(items.len(), 1);
@@ -625,6 +861,8 @@ mod tests {
             category: Some("done".to_string()),
             status: Some(1),
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         assert_eq!(
             apply_operation(&conn, &[id1], false, Some(&updates)).unwrap(),
@@ -0,0 +1,110 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        ops::{
+            backup::backup_path,
+            batch::pluralize,
+            reschedule::{
+                overdue_task_ids,
+                reschedule,
+            },
+        },
+    },
+    args::{
+        parser::OpsCarryoverCommand,
+        timestr,
+    },
+    db::crud::{
+        get_item,
+        update_item,
+    },
+};
+
+const CARRIED_OVER: &str = "(carried over)";
+
+pub fn handle_carryovercmd(conn: &Connection, cmd: &OpsCarryoverCommand) -> Result<(), String> {
+    // Tasks due earlier today are left alone, only previous days carry over.
+    let (_, end_of_yesterday) = timestr::day_bounds_to_unix_epoch("yesterday")?;
+    let task_ids = overdue_task_ids(conn, end_of_yesterday)?;
+    if task_ids.is_empty() {
+        display::print_bold("No open tasks left from previous days");
+        return Ok(());
+    }
+
+    display::print_bold("backing up database prior to carrying over");
+    if let Err(e) = backup_path(None) {
+        display::print_red(&e);
+    }
+    let today = timestr::to_unix_epoch("today")?;
+    let carried = if cmd.mark {
+        carry_over_marked(conn, &task_ids, today)?
+    } else {
+        reschedule(conn, &task_ids, today)?
+    };
+    display::print_bold(&format!("✓ Carried over {} to today", pluralize(carried, "task")));
+    Ok(())
+}
+
+// Tasks carried over before keep a single marker.
+fn carry_over_marked(
+    conn: &Connection,
+    task_ids: &[i64],
+    target_time: i64,
+) -> Result<usize, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for &id in task_ids {
+        let mut task = get_item(&tx, id).map_err(|e| e.to_string())?;
+        if !task.content.ends_with(CARRIED_OVER) {
+            task.content.push(' ');
+            task.content.push_str(CARRIED_OVER);
+        }
+        task.target_time = Some(target_time);
+        update_item(&tx, &task).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(task_ids.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_recurring_task,
+        insert_task,
+        update_status,
+    };
+
+    #[test]
+    fn test_carry_over() {
+        let (conn, _temp_file) = get_test_conn();
+        let yesterday = insert_task(&conn, "work", "unfinished slides", "yesterday");
+        let older = insert_task(&conn, "home", "call plumber", "yesterday -1d");
+        let done = insert_task(&conn, "work", "finished memo", "yesterday");
+        update_status(&conn, done, 1);
+        let today_task = insert_task(&conn, "work", "due today", "today");
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+
+        let (_, end_of_yesterday) = timestr::day_bounds_to_unix_epoch("yesterday").unwrap();
+        let mut task_ids = overdue_task_ids(&conn, end_of_yesterday).unwrap();
+        task_ids.sort();
+        assert_eq!(task_ids, vec![yesterday, older]);
+
+        let today = timestr::to_unix_epoch("today").unwrap();
+        assert_eq!(carry_over_marked(&conn, &task_ids, today).unwrap(), 2);
+        let moved = get_item(&conn, yesterday).unwrap();
+        assert_eq!(moved.target_time, Some(today));
+        assert_eq!(moved.content, "unfinished slides (carried over)");
+        assert_eq!(get_item(&conn, older).unwrap().target_time, Some(today));
+        assert_ne!(get_item(&conn, done).unwrap().target_time, Some(today));
+        assert_eq!(get_item(&conn, today_task).unwrap().content, "due today");
+        assert!(overdue_task_ids(&conn, end_of_yesterday).unwrap().is_empty());
+
+        // Carrying over again does not stack markers
+        carry_over_marked(&conn, &[yesterday], today).unwrap();
+        let moved = get_item(&conn, yesterday).unwrap();
+        assert_eq!(moved.content, "unfinished slides (carried over)");
+    }
+}
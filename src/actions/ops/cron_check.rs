@@ -0,0 +1,55 @@
+use crate::{
+    actions::display,
+    args::{
+        cron,
+        parser::OpsCronCheckCommand,
+        timestr,
+    },
+};
+
+const PREVIEW_COUNT: usize = 5;
+
+pub fn handle_croncheckcmd(cmd: &OpsCronCheckCommand) -> Result<(), String> {
+    let cron_schedule = resolve_cron(&cmd.schedule)?;
+    let occurrences = cron::get_next_occurrences(&cron_schedule, PREVIEW_COUNT)?;
+
+    display::print_bold(&format!("Cron: {}", cron_schedule));
+    display::print_bold(&format!("Next {} occurrences:", occurrences.len()));
+    for ts in occurrences {
+        let dt = timestr::timestamp_to_naive(ts).ok_or_else(|| format!("Invalid timestamp: {}", ts))?;
+        println!("  {}", dt.format("%Y/%m/%d %a %H:%M"));
+    }
+    Ok(())
+}
+
+// Human schedules are tried first, then the input is taken as a raw cron.
+fn resolve_cron(schedule: &str) -> Result<String, String> {
+    let schedule = schedule.trim();
+    if let Ok(cron_schedule) = timestr::parse_recurring_timestr(schedule) {
+        return Ok(cron_schedule);
+    }
+    match cron::get_next_occurrence(schedule) {
+        Ok(_) => Ok(schedule.split_whitespace().collect::<Vec<_>>().join(" ")),
+        Err(e) => Err(format!(
+            "'{}' is neither a recurring schedule like 'Daily 9AM' nor a supported cron: {}",
+            schedule, e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_cron() {
+        assert_eq!(resolve_cron("Daily 9AM").unwrap(), "0 9 * * *");
+        assert_eq!(resolve_cron("Weekly Monday-Friday 2PM").unwrap(), "0 14 * * 1-5");
+        assert_eq!(resolve_cron(" 30  14 * * 1 ").unwrap(), "30 14 * * 1");
+
+        let err = resolve_cron("Every so often").unwrap_err();
+        assert!(err.contains("neither a recurring schedule"), "{}", err);
+        assert!(resolve_cron("0 25 * * *").is_err());
+        assert!(resolve_cron("0 9 * 5 1").is_err());
+    }
+}
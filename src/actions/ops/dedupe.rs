@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        ops::{
+            backup::backup_path,
+            batch::pluralize,
+        },
+    },
+    args::parser::OpsDedupeCommand,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+            RECURRING_TASK_RECORD,
+            TASK,
+        },
+        ops::batch_delete_items,
+    },
+};
+
+// Times within the same minute count as equal.
+const TIME_ROUNDING_SECS: i64 = 60;
+
+type DuplicateKey = (String, String, String, i64);
+
+pub fn handle_dedupecmd(conn: &Connection, cmd: &OpsDedupeCommand) -> Result<(), String> {
+    let removals = find_duplicates(conn, cmd.category.as_deref())?;
+    if removals.is_empty() {
+        display::print_bold("No duplicate items found");
+        return Ok(());
+    }
+    if !cmd.apply {
+        display::print_bold(&format!(
+            "Found {}, keeping the oldest of each:",
+            pluralize(removals.len(), "duplicate")
+        ));
+        display::print_items(&removals, false);
+        display::print_bold("Run again with --apply to delete them");
+        return Ok(());
+    }
+
+    display::print_bold("backing up database prior to dedupe");
+    if let Err(e) = backup_path(None) {
+        display::print_red(&e);
+    }
+    let deleted = delete_duplicates(conn, &removals)?;
+    display::print_bold(&format!("✓ Deleted {}", pluralize(deleted, "duplicate")));
+    display::print_items(&removals, false);
+    Ok(())
+}
+
+// Every item but the oldest of each duplicate group. Records of a recurring task
+// refer to it by id, so recurring tasks are left alone.
+fn find_duplicates(conn: &Connection, category: Option<&str>) -> Result<Vec<Item>, String> {
+    let mut query = ItemQuery::new()
+        .with_actions(vec![TASK, RECORD, RECURRING_TASK_RECORD])
+        .with_order_by("id");
+    if let Some(cat) = category {
+        query = query.with_category(cat);
+    }
+    let items = query_items(conn, &query).map_err(|e| e.to_string())?;
+    Ok(duplicate_groups(items)
+        .into_iter()
+        .flat_map(|group| group.into_iter().skip(1))
+        .collect())
+}
+
+fn delete_duplicates(conn: &Connection, removals: &[Item]) -> Result<usize, String> {
+    let ids: Vec<i64> = removals.iter().filter_map(|i| i.id).collect();
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let deleted = batch_delete_items(&tx, &ids).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(deleted)
+}
+
+// Tasks are compared by deadline, records by when they happened.
+fn duplicate_key(item: &Item) -> DuplicateKey {
+    let time = match item.target_time {
+        Some(target_time) if item.action == TASK => target_time,
+        _ => item.create_time,
+    };
+    (
+        item.action.clone(),
+        item.category.clone(),
+        item.content.clone(),
+        time.div_euclid(TIME_ROUNDING_SECS),
+    )
+}
+
+// Groups of two or more matching items in the order the items came in,
+// each group in the same order, so the oldest leads when items are by id.
+fn duplicate_groups(items: Vec<Item>) -> Vec<Vec<Item>> {
+    let mut groups: Vec<Vec<Item>> = Vec::new();
+    let mut group_index: HashMap<DuplicateKey, usize> = HashMap::new();
+    for item in items {
+        let key = duplicate_key(&item);
+        match group_index.get(&key) {
+            Some(&index) => groups[index].push(item),
+            None => {
+                group_index.insert(key, groups.len());
+                groups.push(vec![item]);
+            }
+        }
+    }
+    groups.retain(|g| g.len() > 1);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::get_item,
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_dedupe() {
+        let (conn, _temp_file) = get_test_conn();
+        let kept = insert_record(&conn, "feeding", "100ML", "2025/03/01 10AM");
+        let copy = insert_record(&conn, "feeding", "100ML", "2025/03/01 10AM");
+        let other_time = insert_record(&conn, "feeding", "100ML", "2025/03/01 1PM");
+        let other_category = insert_record(&conn, "pumping", "100ML", "2025/03/01 10AM");
+        let task = insert_task(&conn, "work", "report", "2025/03/02");
+        let task_copy = insert_task(&conn, "work", "report", "2025/03/02");
+        let later_task = insert_task(&conn, "work", "report", "2025/03/03");
+
+        let items = query_items(&conn, &ItemQuery::new()).unwrap();
+        let groups = duplicate_groups(items);
+        let ids: Vec<Vec<i64>> = groups
+            .iter()
+            .map(|g| g.iter().filter_map(|i| i.id).collect())
+            .collect();
+        assert_eq!(ids, vec![vec![kept, copy], vec![task, task_copy]]);
+
+        // A dry run leaves everything in place
+        let dry_run = OpsDedupeCommand { category: None, apply: false };
+        handle_dedupecmd(&conn, &dry_run).unwrap();
+        assert!(get_item(&conn, copy).is_ok());
+
+        let feeding = find_duplicates(&conn, Some("feeding")).unwrap();
+        assert_eq!(feeding.iter().filter_map(|i| i.id).collect::<Vec<_>>(), vec![copy]);
+        assert_eq!(delete_duplicates(&conn, &feeding).unwrap(), 1);
+        assert!(get_item(&conn, copy).is_err());
+        assert!(get_item(&conn, task_copy).is_ok());
+
+        let removals = find_duplicates(&conn, None).unwrap();
+        assert_eq!(delete_duplicates(&conn, &removals).unwrap(), 1);
+        for id in [kept, other_time, other_category, task, later_task] {
+            assert!(get_item(&conn, id).is_ok(), "{} should survive", id);
+        }
+        assert!(get_item(&conn, task_copy).is_err());
+        assert!(duplicate_groups(query_items(&conn, &ItemQuery::new()).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_dedupe_keeps_lowest_id() {
+        let (conn, _temp_file) = get_test_conn();
+        let first = insert_record(&conn, "feeding", "100ML", "2025/03/01 10AM");
+        let kept = insert_record(&conn, "feeding", "100ML", "2025/03/01 10AM");
+        // The item inserted first ends up with the higher id, and a later time within the minute
+        let renumbered = kept + 100;
+        conn.execute(
+            "UPDATE items SET id = ?1, create_time = create_time + 30 WHERE id = ?2",
+            [renumbered, first],
+        )
+        .unwrap();
+
+        for category in [None, Some("feeding")] {
+            let removals = find_duplicates(&conn, category).unwrap();
+            assert_eq!(removals.iter().filter_map(|i| i.id).collect::<Vec<_>>(), vec![renumbered]);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::fs;
+
+use nanoserde::SerJson;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        ops::batch::pluralize,
+    },
+    args::parser::OpsDumpCommand,
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+        },
+    },
+};
+
+pub fn handle_dumpcmd(conn: &Connection, cmd: &OpsDumpCommand) -> Result<(), String> {
+    let items = query_items(conn, &ItemQuery::new()).map_err(|e| e.to_string())?;
+    let json = to_json(&items);
+
+    match &cmd.file {
+        Some(path) => {
+            fs::write(path, json).map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+            display::print_bold(&format!("Dumped {} to {}", pluralize(items.len(), "item"), path));
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+pub(crate) fn to_json(items: &[Item]) -> String {
+    items.serialize_json()
+}
@@ -0,0 +1,185 @@
+use std::{
+    fs::{
+        self,
+        File,
+    },
+    io::{
+        self,
+        BufWriter,
+        Write,
+    },
+    ops::ControlFlow,
+};
+
+use chrono::{
+    Local,
+    TimeZone,
+};
+use nanoserde::SerJson;
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    args::parser::OpsExportCommand,
+    db::{
+        crud::{
+            for_each_item,
+            query_items,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+            TASK,
+        },
+    },
+};
+
+pub(crate) const CSV_HEADER: &str = "id,action,category,content,create_time_iso,target_time_iso,status";
+pub(crate) const CSV_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+pub fn handle_exportcmd(conn: &Connection, cmd: &OpsExportCommand) -> Result<(), String> {
+    let mut query = ItemQuery::new()
+        .with_actions(vec![TASK, RECORD])
+        .with_order_by("create_time");
+    if let Some(cat) = &cmd.category {
+        query = query.with_category(cat);
+    }
+    if cmd.format == "jsonl" {
+        return export_jsonl(conn, &query, cmd.file.as_deref());
+    }
+    let items = query_items(conn, &query).map_err(|e| e.to_string())?;
+    let csv = to_csv(&items);
+
+    match &cmd.file {
+        Some(path) => {
+            fs::write(path, csv).map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+            display::print_bold(&format!("Exported {} items to {}", items.len(), path));
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+// One JSON object per line, streamed from the database so nothing is held in memory.
+fn export_jsonl(conn: &Connection, query: &ItemQuery, file: Option<&str>) -> Result<(), String> {
+    match file {
+        Some(path) => {
+            let out = File::create(path).map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+            let exported = write_jsonl(conn, query, BufWriter::new(out))
+                .map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+            display::print_bold(&format!("Exported {} items to {}", exported, path));
+        }
+        None => {
+            write_jsonl(conn, query, BufWriter::new(io::stdout().lock()))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_jsonl(conn: &Connection, query: &ItemQuery, mut out: impl Write) -> Result<usize, String> {
+    let mut write_error = None;
+    let exported = for_each_item(conn, query, |item| {
+        match writeln!(out, "{}", item.serialize_json()) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(e) => {
+                write_error = Some(e);
+                ControlFlow::Break(())
+            }
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    if let Some(e) = write_error {
+        return Err(e.to_string());
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(exported)
+}
+
+fn to_csv(items: &[Item]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for item in items {
+        let row = [
+            item.id.map(|id| id.to_string()).unwrap_or_default(),
+            item.action.clone(),
+            item.category.clone(),
+            item.content.clone(),
+            format_csv_time(item.create_time),
+            item.target_time.map(format_csv_time).unwrap_or_default(),
+            item.status.to_string(),
+        ];
+        let fields: Vec<String> = row.iter().map(|f| escape_field(f)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn format_csv_time(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.format(CSV_TIME_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+// Quote fields containing separators, quotes or line breaks, doubling inner quotes
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nanoserde::DeJson;
+
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_record,
+        insert_task,
+    };
+
+    #[test]
+    fn test_to_csv() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "plain task", "2025/06/12");
+        insert_record(&conn, "notes", "said \"hi\", then\nleft", "2025/06/11 9AM");
+        let items = query_items(&conn, &ItemQuery::new().with_order_by("create_time")).unwrap();
+
+        let csv = to_csv(&items);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "2,record,notes,\"said \"\"hi\"\", then");
+        assert_eq!(lines[2], "left\",2025-06-11T09:00:00,,0");
+        assert!(lines[3].starts_with("1,task,work,plain task,"));
+        assert!(lines[3].ends_with(",2025-06-12T23:59:59,0"));
+    }
+
+    #[test]
+    fn test_write_jsonl() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "plain task", "2025/06/12");
+        insert_record(&conn, "notes", "said \"hi\", then\nleft", "2025/06/11 9AM");
+        insert_record(&conn, "notes", "second", "2025/06/11 10AM");
+        let query = ItemQuery::new().with_order_by("create_time");
+
+        let mut out = Vec::new();
+        assert_eq!(write_jsonl(&conn, &query, &mut out).unwrap(), 3);
+        let jsonl = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let items: Vec<Item> = lines
+            .iter()
+            .map(|line| Item::deserialize_json(line).expect(line))
+            .collect();
+        assert_eq!(items[0].content, "said \"hi\", then\nleft");
+        assert_eq!(items[1].content, "second");
+        assert_eq!(items[2].content, "plain task");
+        assert_eq!(items[2].action, "task");
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{
+    Duration,
+    NaiveDate,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        ops::batch::pluralize,
+    },
+    args::{
+        parser::OpsGapsCommand,
+        timestr,
+    },
+    db::{
+        crud::query_items,
+        item::{
+            ItemQuery,
+            RECORD,
+            RECURRING_TASK_RECORD,
+        },
+    },
+};
+
+pub fn handle_gapscmd(conn: &Connection, cmd: &OpsGapsCommand) -> Result<(), String> {
+    let last_day = timestr::today();
+    let first_day = last_day - Duration::days(cmd.days as i64 - 1);
+    let missed = missed_days(conn, &cmd.category, first_day, last_day)?;
+
+    if missed.is_empty() {
+        display::print_bold(&format!(
+            "No missed days for '{}' in the last {}",
+            cmd.category,
+            pluralize(cmd.days, "day")
+        ));
+        return Ok(());
+    }
+    display::print_bold(&format!(
+        "Missed {} of the last {} for '{}':",
+        missed.len(),
+        pluralize(cmd.days, "day"),
+        cmd.category
+    ));
+    for day in missed {
+        println!("  {}", day.format("%Y-%m-%d %a"));
+    }
+    Ok(())
+}
+
+// Days from first_day to last_day without a record of the category, oldest first.
+// A single query covers the window, its records are then matched day by day.
+fn missed_days(
+    conn: &Connection,
+    category: &str,
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+) -> Result<Vec<NaiveDate>, String> {
+    let (window_start, _) = timestr::date_bounds_to_unix_epoch(first_day)?;
+    let (_, window_end) = timestr::date_bounds_to_unix_epoch(last_day)?;
+    // The min bound is exclusive
+    let query = ItemQuery::new()
+        .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+        .with_category(category)
+        .with_create_time_range(Some(window_start - 1), Some(window_end));
+    let create_times: Vec<i64> = query_items(conn, &query)
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|r| r.create_time)
+        .collect();
+
+    let mut missed = Vec::new();
+    for day in first_day.iter_days().take_while(|d| *d <= last_day) {
+        let (start, end) = timestr::date_bounds_to_unix_epoch(day)?;
+        if !create_times.iter().any(|t| (start..=end).contains(t)) {
+            missed.push(day);
+        }
+    }
+    Ok(missed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_record,
+    };
+
+    #[test]
+    fn test_missed_days() {
+        let (conn, _temp_file) = get_test_conn();
+        for timestr in ["2025/03/10 8AM", "2025/03/11 11PM", "2025/03/11 9AM", "2025/03/13 12AM", "2025/03/15 7PM"] {
+            insert_record(&conn, "water", "glass", timestr);
+        }
+        insert_record(&conn, "coffee", "cup", "2025/03/12 9AM");
+        insert_record(&conn, "water", "glass", "2025/03/16 9AM");
+
+        let date = |d: u32| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert_eq!(
+            missed_days(&conn, "water", date(10), date(15)).unwrap(),
+            vec![date(12), date(14)]
+        );
+        assert!(missed_days(&conn, "water", date(10), date(11)).unwrap().is_empty());
+        assert_eq!(missed_days(&conn, "coffee", date(12), date(13)).unwrap(), vec![date(13)]);
+    }
+}
@@ -0,0 +1,209 @@
+use std::fs;
+
+use chrono::{
+    DateTime,
+    Local,
+    NaiveDateTime,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        ops::{
+            batch::pluralize,
+            export::CSV_TIME_FORMAT,
+        },
+    },
+    args::parser::OpsImportCommand,
+    db::{
+        crud::insert_item,
+        item::{
+            Item,
+            RECORD,
+            SOURCE_IMPORT,
+            TASK,
+        },
+    },
+};
+
+const REQUIRED_COLUMNS: &[&str] = &["id", "category", "content", "create_time_iso", "status"];
+
+pub fn handle_importcmd(conn: &Connection, cmd: &OpsImportCommand) -> Result<(), String> {
+    let text =
+        fs::read_to_string(&cmd.file).map_err(|e| format!("Failed to read '{}': {}", cmd.file, e))?;
+    let rows = parse_csv(&text)?;
+    let (items, failures) = rows_to_items(&rows)?;
+
+    // Rows are inserted fresh, the exported ids are not reused
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for item in &items {
+        insert_item(&tx, item).map_err(|e| format!("Failed to insert item: {}", e))?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    for failure in &failures {
+        display::print_red(failure);
+    }
+    display::print_bold(&format!(
+        "✓ Imported {}, failed {}",
+        pluralize(items.len(), "item"),
+        failures.len()
+    ));
+    Ok(())
+}
+
+// Columns are looked up by header name; action defaults to record
+// and target_time_iso is only read for tasks.
+fn rows_to_items(rows: &[Vec<String>]) -> Result<(Vec<Item>, Vec<String>), String> {
+    let header = rows.first().ok_or("CSV file is empty")?;
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    for name in REQUIRED_COLUMNS {
+        if column(name).is_none() {
+            return Err(format!("CSV header is missing the '{}' column", name));
+        }
+    }
+    let category_col = column("category").unwrap();
+    let content_col = column("content").unwrap();
+    let create_col = column("create_time_iso").unwrap();
+    let status_col = column("status").unwrap();
+    let action_col = column("action");
+    let target_col = column("target_time_iso");
+
+    let mut items = Vec::new();
+    let mut failures = Vec::new();
+    for (i, row) in rows.iter().enumerate().skip(1) {
+        let field = |col: usize| row.get(col).map(String::as_str).unwrap_or("");
+        let parsed = (|| {
+            if row.len() != header.len() {
+                return Err(format!("expected {} fields, found {}", header.len(), row.len()));
+            }
+            let action = match action_col.map(field).unwrap_or(RECORD) {
+                "" | RECORD => RECORD,
+                TASK => TASK,
+                other => return Err(format!("unsupported action '{}'", other)),
+            };
+            let create_time = parse_csv_time(field(create_col))?;
+            let status = match field(status_col).parse::<u8>() {
+                Ok(s) if s <= 7 => s,
+                _ => return Err(format!("invalid status '{}'", field(status_col))),
+            };
+            let mut item = Item::with_create_time(
+                action.to_string(),
+                field(category_col).to_string(),
+                field(content_col).to_string(),
+                create_time,
+            );
+            item.status = status;
+            item.source = SOURCE_IMPORT.to_string();
+            if action == TASK && let Some(col) = target_col && !field(col).is_empty() {
+                item.target_time = Some(parse_csv_time(field(col))?);
+            }
+            Ok(item)
+        })();
+        match parsed {
+            Ok(item) => items.push(item),
+            Err(e) => failures.push(format!("row {}: {}", i + 1, e)),
+        }
+    }
+    Ok((items, failures))
+}
+
+// Accepts the local time written by export, or RFC 3339 with an offset
+fn parse_csv_time(s: &str) -> Result<i64, String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.timestamp());
+    }
+    NaiveDateTime::parse_from_str(s, CSV_TIME_FORMAT)
+        .ok()
+        .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+        .map(|dt| dt.timestamp())
+        .ok_or_else(|| format!("invalid ISO timestamp '{}'", s))
+}
+
+// Minimal RFC 4180 parsing: quoted fields may contain commas,
+// line breaks and doubled quotes.
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("CSV file ends inside a quoted field".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|r| !(r.len() == 1 && r[0].is_empty()));
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "a,b,c\r\n1,\"x, y\",\"say \"\"hi\"\"\nthen\"\n\n2,,last";
+        let rows = parse_csv(text).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], vec!["a", "b", "c"]);
+        assert_eq!(rows[1], vec!["1", "x, y", "say \"hi\"\nthen"]);
+        assert_eq!(rows[2], vec!["2", "", "last"]);
+
+        assert!(parse_csv("a,\"unterminated\n").is_err());
+    }
+
+    #[test]
+    fn test_rows_to_items() {
+        let text = "id,category,content,create_time_iso,status\n\
+                    7,feeding,100ML,2025-06-11T09:00:00,0\n\
+                    8,feeding,bad time,yesterday,0\n\
+                    9,feeding,bad status,2025-06-11T10:00:00,99\n\
+                    10,work,offset time,2025-06-11T10:00:00+00:00,1\n";
+        let rows = parse_csv(text).unwrap();
+        let (items, failures) = rows_to_items(&rows).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].action, RECORD);
+        assert_eq!(items[0].id, None);
+        assert_eq!(items[0].content, "100ML");
+        assert_eq!(items[0].source, SOURCE_IMPORT);
+        assert_eq!(items[0].create_time, parse_csv_time("2025-06-11T09:00:00").unwrap());
+        assert_eq!(items[1].create_time, 1749636000);
+        assert_eq!(items[1].status, 1);
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].starts_with("row 3: invalid ISO timestamp"));
+        assert!(failures[1].starts_with("row 4: invalid status"));
+
+        let rows = parse_csv("id,category,content\n1,a,b\n").unwrap();
+        assert!(rows_to_items(&rows).unwrap_err().contains("create_time_iso"));
+    }
+}
@@ -0,0 +1,142 @@
+use std::fs;
+
+use nanoserde::DeJson;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        ops::{
+            backup::backup_path,
+            batch::pluralize,
+        },
+    },
+    args::parser::OpsLoadCommand,
+    db::{
+        crud::{
+            count_items,
+            restore_item,
+        },
+        item::{
+            Item,
+            ItemQuery,
+        },
+    },
+};
+
+pub fn handle_loadcmd(conn: &Connection, cmd: &OpsLoadCommand) -> Result<(), String> {
+    let text =
+        fs::read_to_string(&cmd.file).map_err(|e| format!("Failed to read '{}': {}", cmd.file, e))?;
+    let items = from_json(&text)?;
+
+    let existing = count_items(conn, &ItemQuery::new()).map_err(|e| e.to_string())?;
+    if existing > 0 {
+        if !cmd.force {
+            return Err(format!(
+                "Database already has {}, use --force to load into it",
+                pluralize(existing, "item")
+            ));
+        }
+        display::print_bold("backing up database prior to load");
+        backup_path(None)?;
+    }
+
+    load_items(conn, &items)?;
+    display::print_bold(&format!("✓ Loaded {}", pluralize(items.len(), "item")));
+    Ok(())
+}
+
+pub(crate) fn from_json(text: &str) -> Result<Vec<Item>, String> {
+    let items: Vec<Item> =
+        DeJson::deserialize_json(text).map_err(|e| format!("Invalid dump file: {}", e))?;
+    if let Some(item) = items.iter().find(|item| item.id.is_none()) {
+        return Err(format!("Invalid dump file: item '{}' has no id", item.content));
+    }
+    Ok(items)
+}
+
+// Ids are kept so recurring records still point at their recurring task.
+fn load_items(conn: &Connection, items: &[Item]) -> Result<(), String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for item in items {
+        restore_item(&tx, item).map_err(|e| format!("Failed to load item: {}", e))?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use nanoserde::SerJson;
+
+    use super::*;
+    use crate::{
+        actions::ops::dump::to_json,
+        db::crud::{
+            get_item,
+            query_items,
+            update_item,
+        },
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_recurring_record,
+            insert_recurring_task,
+            insert_task,
+            update_status,
+        },
+    };
+
+    #[test]
+    fn test_dump_load_round_trip() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "work", "write \"report\"\nwith notes", "tomorrow");
+        let mut task = get_item(&conn, task_id).unwrap();
+        task.pinned = true;
+        task.note = Some("outline first".to_string());
+        update_item(&conn, &task).unwrap();
+        let done_id = insert_task(&conn, "work", "done task", "yesterday");
+        update_status(&conn, done_id, 1);
+        insert_record(&conn, "feeding", "100ML", "yesterday 2PM");
+        let recurring_id = insert_recurring_task(&conn, "life", "write diary", "Daily 9PM");
+        insert_recurring_record(&conn, "life", "diary done", recurring_id, 1750000000);
+
+        let items = query_items(&conn, &ItemQuery::new()).unwrap();
+        let dumped = to_json(&items);
+
+        let (target, _target_file) = get_test_conn();
+        load_items(&target, &from_json(&dumped).unwrap()).unwrap();
+        let loaded = query_items(&target, &ItemQuery::new()).unwrap();
+        assert_eq!(loaded.len(), 5);
+        assert_eq!(loaded.serialize_json(), items.serialize_json());
+        let record = loaded.last().unwrap();
+        assert_eq!(record.recurring_task_id, Some(recurring_id));
+        assert_eq!(record.good_until, Some(1750000000));
+    }
+
+    #[test]
+    fn test_load_refuses_non_empty() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "existing", "tomorrow");
+        let dump_file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(dump_file.path(), "[]").unwrap();
+
+        let cmd = OpsLoadCommand {
+            json: true,
+            file: dump_file.path().to_str().unwrap().to_string(),
+            force: false,
+        };
+        let err = handle_loadcmd(&conn, &cmd).unwrap_err();
+        assert_eq!(err, "Database already has 1 item, use --force to load into it");
+
+        assert!(from_json("[{\"action\": \"task\"}]").is_err());
+        assert!(from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_load_defaults_missing_source() {
+        // Dumps written before items tracked their source
+        let dumped = "[{\"id\":1,\"action\":\"record\",\"category\":\"feeding\",\"content\":\"100ML\",\"create_time\":1750000000,\"target_time\":null,\"modify_time\":null,\"status\":0,\"cron_schedule\":null,\"human_schedule\":null,\"recurring_task_id\":null,\"good_until\":null,\"pinned\":false,\"note\":null}]";
+        let items = from_json(dumped).unwrap();
+        assert_eq!(items[0].source, "cli");
+    }
+}
@@ -0,0 +1,45 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::display,
+    db::conn::{
+        init_table,
+        opened_schema_version,
+        schema_version,
+    },
+};
+
+// Migrations already run when the database is opened, so the version before
+// is the one connect found. Running init_table again is a no-op when current.
+pub fn handle_migratecmd(conn: &Connection) -> Result<(), String> {
+    let current = || schema_version(conn).map_err(|e| e.to_string());
+    let before = match opened_schema_version() {
+        Some(version) => version,
+        None => current()?,
+    };
+    init_table(conn).map_err(|e| e.to_string())?;
+    let after = current()?;
+
+    display::print_bold(&describe_migration(before, after));
+    Ok(())
+}
+
+fn describe_migration(before: i32, after: i32) -> String {
+    match before {
+        0 => format!("Created new database at schema version {}", after),
+        _ if before == after => format!("Schema version {}, already up to date", after),
+        _ => format!("Migrated schema version {} -> {}", before, after),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_migration() {
+        assert_eq!(describe_migration(0, 5), "Created new database at schema version 5");
+        assert_eq!(describe_migration(5, 5), "Schema version 5, already up to date");
+        assert_eq!(describe_migration(2, 5), "Migrated schema version 2 -> 5");
+    }
+}
@@ -1,7 +1,41 @@
-mod backup;
+pub(crate) mod backup;
 pub(crate) mod batch;
+mod carryover;
+mod cron_check;
+mod dedupe;
+mod dump;
+mod export;
+mod gaps;
+mod import;
+mod load;
+mod migrate;
+mod on;
+mod purge_cache;
+mod recat;
+mod remind;
+mod reschedule;
+mod review;
+mod sed;
 mod stat;
+mod vacuum;
 
 pub use backup::handle_backupcmd;
 pub use batch::handle_batchcmd;
+pub use carryover::handle_carryovercmd;
+pub use cron_check::handle_croncheckcmd;
+pub use dedupe::handle_dedupecmd;
+pub use dump::handle_dumpcmd;
+pub use export::handle_exportcmd;
+pub use gaps::handle_gapscmd;
+pub use import::handle_importcmd;
+pub use load::handle_loadcmd;
+pub use migrate::handle_migratecmd;
+pub use on::handle_oncmd;
+pub use purge_cache::handle_purgecachecmd;
+pub use recat::handle_recatcmd;
+pub use remind::handle_remindcmd;
+pub use reschedule::handle_rescheduleoverduecmd;
+pub use review::handle_reviewcmd;
+pub use sed::handle_sedcmd;
 pub use stat::handle_statcmd;
+pub use vacuum::handle_vacuumcmd;
@@ -0,0 +1,111 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::COMPLETED_PREFIXES,
+    },
+    args::{
+        parser::OpsOnCommand,
+        timestr,
+    },
+    db::{
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            RECORD,
+            RECURRING_TASK_RECORD,
+        },
+    },
+};
+
+pub fn handle_oncmd(conn: &Connection, cmd: &OpsOnCommand) -> Result<(), String> {
+    let (start, end) = timestr::day_bounds_to_unix_epoch(&cmd.date)?;
+    let (completed, records) = items_on_day(conn, cmd.category.as_deref(), start, end)?;
+
+    let day = timestr::timestamp_to_naive(start)
+        .map(|dt| dt.format("%Y-%m-%d %a").to_string())
+        .ok_or_else(|| format!("Invalid timestamp: {}", start))?;
+    display::print_bold(&format!("Completed on {}:", day));
+    if completed.is_empty() {
+        println!("  No tasks completed");
+    } else {
+        display::print_items(&completed, false);
+    }
+    display::print_bold(&format!("Recorded on {}:", day));
+    if records.is_empty() {
+        println!("  No records");
+    } else {
+        display::print_items(&records, false);
+    }
+    Ok(())
+}
+
+// Records created within the day, split into the completion records done leaves
+// for tasks and everything else, both in the order they happened.
+fn items_on_day(
+    conn: &Connection,
+    category: Option<&str>,
+    start: i64,
+    end: i64,
+) -> Result<(Vec<Item>, Vec<Item>), String> {
+    // The min bound is exclusive
+    let mut query = ItemQuery::new()
+        .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+        .with_create_time_range(Some(start - 1), Some(end))
+        .with_order_by("create_time");
+    if let Some(cat) = category {
+        query = query.with_category(cat);
+    }
+    let items = query_items(conn, &query).map_err(|e| e.to_string())?;
+    Ok(items
+        .into_iter()
+        .partition(|item| COMPLETED_PREFIXES.iter().any(|p| item.content.starts_with(p))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::insert_item,
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_recurring_task,
+        },
+    };
+
+    #[test]
+    fn test_items_on_day() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "journal", "day before", "2025/03/13 11PM");
+        insert_record(&conn, "journal", "wrote notes", "2025/03/14 9AM");
+        insert_record(&conn, "work", "Completed Task: report", "2025/03/14 5PM");
+        insert_record(&conn, "journal", "late walk", "2025/03/14 11:30PM");
+        insert_record(&conn, "work", "Completed Task: slides", "2025/03/15 9AM");
+        insert_record(&conn, "journal", "day after", "2025/03/15 12AM");
+        let diary = insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+        let mut diary_done = Item::create_recurring_record(
+            "life".to_string(),
+            "Completed Recurring Task: diary".to_string(),
+            diary,
+            timestr::to_unix_epoch("2025/03/15 9PM").unwrap(),
+        );
+        diary_done.create_time = timestr::to_unix_epoch("2025/03/14 9PM").unwrap();
+        insert_item(&conn, &diary_done).unwrap();
+
+        let (start, end) = timestr::day_bounds_to_unix_epoch("2025/03/14").unwrap();
+        let contents = |items: Vec<Item>| items.into_iter().map(|i| i.content).collect::<Vec<_>>();
+        let (completed, records) = items_on_day(&conn, None, start, end).unwrap();
+        assert_eq!(contents(records), vec!["wrote notes", "late walk"]);
+        assert_eq!(
+            contents(completed),
+            vec!["Completed Task: report", "Completed Recurring Task: diary"]
+        );
+
+        let (completed, records) = items_on_day(&conn, Some("journal"), start, end).unwrap();
+        assert!(completed.is_empty());
+        assert_eq!(records.len(), 2);
+    }
+}
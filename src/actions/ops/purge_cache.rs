@@ -0,0 +1,53 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        ops::batch::pluralize,
+    },
+    db::cache,
+};
+
+// Only the index cache used by done/update/delete is cleared, items are untouched.
+pub fn handle_purgecachecmd(conn: &Connection) -> Result<(), String> {
+    let entries = cache::count_entries(conn).map_err(|e| e.to_string())?;
+    cache::clear(conn).map_err(|e| e.to_string())?;
+    display::print_bold(&format!(
+        "✓ Purged {} from the list cache, list again before referring to an index",
+        pluralize(entries as usize, "row")
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            crud::query_items,
+            item::ItemQuery,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_purge_cache() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "first", "today");
+        insert_task(&conn, "work", "second", "tomorrow");
+        let items = query_items(&conn, &ItemQuery::new()).unwrap();
+        cache::store(&conn, &items).unwrap();
+        assert_eq!(cache::count_entries(&conn).unwrap(), 3);
+
+        handle_purgecachecmd(&conn).unwrap();
+        assert_eq!(cache::count_entries(&conn).unwrap(), 0);
+        assert!(!cache::validate_cache(&conn).unwrap());
+        assert_eq!(query_items(&conn, &ItemQuery::new()).unwrap().len(), 2);
+
+        // Purging an empty cache is fine too
+        assert!(handle_purgecachecmd(&conn).is_ok());
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::{
+    self,
+    Write,
+};
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        modify::validate_cache,
+        ops::{
+            backup::backup_path,
+            batch::pluralize,
+        },
+    },
+    args::parser::parse_category,
+    db::{
+        cache,
+        crud::{
+            get_item,
+            update_item,
+        },
+        item::Item,
+    },
+};
+
+pub fn handle_recatcmd(conn: &Connection) -> Result<(), String> {
+    validate_cache(conn)?;
+    let items = cache::read_all(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|id| get_item(conn, id).map_err(|e| format!("Failed to get item: {}", e)))
+        .collect::<Result<Vec<Item>, String>>()?;
+
+    let mut inputs = Vec::with_capacity(items.len());
+    for item in &items {
+        display::print_items(std::slice::from_ref(item), false);
+        match prompt_category(&item.category)? {
+            Some(input) => inputs.push(input),
+            // Input ended, the remaining items keep their category
+            None => break,
+        }
+    }
+
+    let changes = category_changes(&items, &inputs)?;
+    if changes.is_empty() {
+        display::print_bold("No categories changed");
+        return Ok(());
+    }
+    display::print_bold("backing up database prior to recategorizing");
+    if let Err(e) = backup_path(None) {
+        display::print_red(&e);
+    }
+    let changed = recategorize(conn, changes)?;
+    display::print_bold(&format!("✓ Recategorized {}", pluralize(changed, "item")));
+    Ok(())
+}
+
+// None once stdin is closed.
+fn prompt_category(current: &str) -> Result<Option<String>, String> {
+    print!("New category [{}] (enter to keep): ", current);
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    let mut input = String::new();
+    let read = io::stdin().read_line(&mut input).map_err(|e| e.to_string())?;
+    if read == 0 {
+        return Ok(None);
+    }
+    Ok(Some(input.trim().to_string()))
+}
+
+// Pairs each item with the input given for it in order, an empty input or the
+// current category keeps the item as it is.
+fn category_changes(items: &[Item], inputs: &[String]) -> Result<Vec<Item>, String> {
+    let mut changes = Vec::new();
+    for (item, input) in items.iter().zip(inputs) {
+        if input.is_empty() {
+            continue;
+        }
+        let category = parse_category(input)?;
+        if category != item.category {
+            let mut item = item.clone();
+            item.category = category;
+            changes.push(item);
+        }
+    }
+    Ok(changes)
+}
+
+fn recategorize(conn: &Connection, changes: Vec<Item>) -> Result<usize, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for item in &changes {
+        update_item(&tx, item).map_err(|e| format!("Failed to update item: {}", e))?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(changes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_record,
+        insert_task,
+    };
+
+    #[test]
+    fn test_recategorize() {
+        let (conn, _temp_file) = get_test_conn();
+        let ids = [
+            insert_task(&conn, "work", "book flights", "tomorrow"),
+            insert_task(&conn, "work", "review slides", "tomorrow"),
+            insert_record(&conn, "misc", "ran 5k", "today"),
+            insert_task(&conn, "home", "fix sink", "tomorrow"),
+        ];
+        let items: Vec<Item> = ids.iter().map(|id| get_item(&conn, *id).unwrap()).collect();
+
+        // Kept by enter, and by input ending before the last item
+        let inputs: Vec<String> = ["travel", "", "gym"].iter().map(|s| s.to_string()).collect();
+        let changes = category_changes(&items, &inputs).unwrap();
+        assert_eq!(recategorize(&conn, changes).unwrap(), 2);
+
+        let categories: Vec<String> =
+            ids.iter().map(|id| get_item(&conn, *id).unwrap().category).collect();
+        assert_eq!(categories, vec!["travel", "work", "gym", "home"]);
+
+        let items: Vec<Item> = ids.iter().map(|id| get_item(&conn, *id).unwrap()).collect();
+        let inputs = vec!["travel".to_string(), "work".to_string()];
+        assert!(category_changes(&items, &inputs).unwrap().is_empty());
+    }
+}
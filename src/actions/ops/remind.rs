@@ -0,0 +1,164 @@
+use chrono::Local;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::{
+            mark_recurring_task_by_completion,
+            TARGET_TIME_COL,
+        },
+    },
+    args::{
+        cron,
+        parser::OpsRemindCommand,
+        timestr,
+    },
+    db::{
+        cache,
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            OPEN_STATUS_CODES,
+            RECURRING_TASK,
+            TASK,
+        },
+    },
+};
+
+pub fn handle_remindcmd(conn: &Connection, cmd: &OpsRemindCommand) -> Result<(), String> {
+    let now = Local::now().timestamp();
+    let window_end = cmd
+        .within
+        .checked_mul(60)
+        .and_then(|secs| now.checked_add(secs))
+        .ok_or_else(|| format!("Window of {} minutes is too large", cmd.within))?;
+    let tasks = tasks_to_remind(conn, cmd.category.as_deref(), now, window_end)?;
+    if tasks.is_empty() {
+        display::print_bold("No tasks coming due");
+        return Ok(());
+    }
+
+    cache::clear(conn).map_err(|e| e.to_string())?;
+    cache::store(conn, &tasks).map_err(|e| e.to_string())?;
+
+    display::print_bold(&format!(
+        "Coming due within {}:",
+        timestr::format_duration_minutes(cmd.within)
+    ));
+    display::print_items(&tasks, true);
+    Ok(())
+}
+
+// Open tasks not yet due and recurring tasks not yet done for their next occurrence,
+// whose reminder, the due time less the task's lead, comes by the window end.
+// Soonest due first.
+fn tasks_to_remind(
+    conn: &Connection,
+    category: Option<&str>,
+    now: i64,
+    window_end: i64,
+) -> Result<Vec<Item>, String> {
+    // The min bound is exclusive, overdue tasks are left to list task
+    let mut task_query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_min(now)
+        .with_order_by(TARGET_TIME_COL);
+    let mut recurring_query = ItemQuery::new().with_action(RECURRING_TASK);
+    if let Some(cat) = category {
+        task_query = task_query.with_category(cat);
+        recurring_query = recurring_query.with_category(cat);
+    }
+
+    let mut due: Vec<(i64, Item)> = query_items(conn, &task_query)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|task| Some((task.target_time?, task)))
+        .collect();
+    let recurring_tasks = query_items(conn, &recurring_query).map_err(|e| e.to_string())?;
+    for recurring_task in mark_recurring_task_by_completion(conn, recurring_tasks)? {
+        if recurring_task.recurring_interval_complete {
+            continue;
+        }
+        let cron_schedule = recurring_task.cron_schedule.as_ref().unwrap();
+        due.push((cron::get_next_occurrence_after(cron_schedule, now)?, recurring_task));
+    }
+
+    due.retain(|(due_time, task)| remind_time(*due_time, task.remind_before_minutes) <= window_end);
+    due.sort_by_key(|(due_time, _)| *due_time);
+    Ok(due.into_iter().map(|(_, task)| task).collect())
+}
+
+// A lead too large to subtract reminds from the start.
+fn remind_time(due_time: i64, remind_before_minutes: Option<i64>) -> i64 {
+    let lead = remind_before_minutes.unwrap_or(0).saturating_mul(60);
+    due_time.saturating_sub(lead)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::insert_item,
+        tests::{
+            get_test_conn,
+            insert_recurring_record,
+            insert_recurring_task,
+            update_status,
+        },
+    };
+
+    const DAY: i64 = 86400;
+
+    fn insert_due_task(conn: &Connection, content: &str, due_time: i64, lead: Option<i64>) -> i64 {
+        let mut task = Item::with_target_time(
+            TASK.to_string(),
+            "work".to_string(),
+            content.to_string(),
+            Some(due_time),
+        );
+        task.remind_before_minutes = lead;
+        insert_item(conn, &task).unwrap()
+    }
+
+    fn contents(tasks: &[Item]) -> Vec<&str> {
+        tasks.iter().map(|t| t.content.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tasks_to_remind() {
+        let (conn, _temp_file) = get_test_conn();
+        let now = Local::now().timestamp();
+        let window_end = now + DAY;
+        insert_due_task(&conn, "renew passport", now + DAY + 6 * 3600, Some(24 * 60));
+        insert_due_task(&conn, "file taxes", now + DAY + 6 * 3600, None);
+        insert_due_task(&conn, "call back", now + 2 * 3600, None);
+        let closed = insert_due_task(&conn, "sent already", now + 3600, None);
+        update_status(&conn, closed, 1);
+        insert_due_task(&conn, "missed", now - 3600, Some(24 * 60));
+
+        // The day long lead brings the passport forward a day, taxes wait for a later check
+        let tasks = tasks_to_remind(&conn, None, now, window_end).unwrap();
+        assert_eq!(contents(&tasks), vec!["call back", "renew passport"]);
+        let tasks = tasks_to_remind(&conn, None, now + DAY, window_end + DAY).unwrap();
+        assert_eq!(contents(&tasks), vec!["renew passport", "file taxes"]);
+        assert!(tasks_to_remind(&conn, Some("life"), now, window_end).unwrap().is_empty());
+
+        // Recurring tasks come by their next occurrence until done for it
+        let recurring_id = insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        let tasks = tasks_to_remind(&conn, None, now, window_end).unwrap();
+        assert!(contents(&tasks).contains(&"standup"));
+        // The occurrence counts from the given time, not the wall clock
+        let earlier = now - 3 * DAY;
+        let tasks = tasks_to_remind(&conn, None, earlier, earlier + DAY).unwrap();
+        assert!(contents(&tasks).contains(&"standup"));
+        let next_standup = cron::get_next_occurrence_after("0 9 * * *", now).unwrap();
+        insert_recurring_record(&conn, "work", "standup", recurring_id, next_standup);
+        let tasks = tasks_to_remind(&conn, None, now, window_end).unwrap();
+        assert!(!contents(&tasks).contains(&"standup"));
+
+        assert!(remind_time(now, Some(i64::MAX)) < 0);
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::Local;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+        ops::{
+            backup::backup_path,
+            batch::pluralize,
+        },
+    },
+    args::{
+        parser::OpsRescheduleOverdueCommand,
+        timestr,
+    },
+    config::get_config,
+    db::{
+        crud::query_items,
+        item::{
+            ItemQuery,
+            TASK,
+        },
+        ops::{
+            batch_update_items,
+            ItemUpdates,
+        },
+    },
+};
+
+pub fn handle_rescheduleoverduecmd(
+    conn: &Connection,
+    cmd: &OpsRescheduleOverdueCommand,
+) -> Result<(), String> {
+    let now = Local::now().timestamp();
+    let target_time = timestr::to_unix_epoch(&cmd.to)?;
+    if target_time <= now {
+        return Err(format!("--to '{}' is not in the future, tasks would stay overdue", cmd.to));
+    }
+
+    let task_ids = overdue_task_ids(conn, get_config().overdue_cutoff(now))?;
+    if task_ids.is_empty() {
+        display::print_bold("No overdue tasks found");
+        return Ok(());
+    }
+
+    display::print_bold("backing up database prior to rescheduling");
+    if let Err(e) = backup_path(None) {
+        display::print_red(&e);
+    }
+    let rescheduled = reschedule(conn, &task_ids, target_time)?;
+    display::print_bold(&format!(
+        "✓ Rescheduled {} to {}",
+        pluralize(rescheduled, "overdue task"),
+        cmd.to
+    ));
+    Ok(())
+}
+
+// Open regular tasks due by the overdue cutoff, recurring tasks are never overdue.
+pub(super) fn overdue_task_ids(conn: &Connection, overdue_cutoff: i64) -> Result<Vec<i64>, String> {
+    let query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_max(overdue_cutoff);
+    let tasks = query_items(conn, &query).map_err(|e| e.to_string())?;
+    Ok(tasks.iter().filter_map(|t| t.id).collect())
+}
+
+pub(super) fn reschedule(conn: &Connection, task_ids: &[i64], target_time: i64) -> Result<usize, String> {
+    let updates = ItemUpdates {
+        category: None,
+        status: None,
+        target_time: Some(target_time),
+        add_tag: None,
+        remove_tag: None,
+    };
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let rescheduled = batch_update_items(&tx, task_ids, &updates).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(rescheduled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::get_item,
+        tests::{
+            get_test_conn,
+            insert_recurring_task,
+            insert_task,
+            update_status,
+        },
+    };
+
+    #[test]
+    fn test_reschedule_overdue() {
+        let (conn, _temp_file) = get_test_conn();
+        let late = insert_task(&conn, "work", "late report", "yesterday");
+        let later = insert_task(&conn, "home", "late bill", "2025/01/01");
+        let done = insert_task(&conn, "work", "done late", "yesterday");
+        update_status(&conn, done, 1);
+        let upcoming = insert_task(&conn, "work", "upcoming", "tomorrow");
+        insert_recurring_task(&conn, "life", "diary", "Daily 9PM");
+
+        let now = Local::now().timestamp();
+        let mut task_ids = overdue_task_ids(&conn, now).unwrap();
+        task_ids.sort();
+        assert_eq!(task_ids, vec![late, later]);
+
+        let today = timestr::to_unix_epoch("today").unwrap();
+        assert_eq!(reschedule(&conn, &task_ids, today).unwrap(), 2);
+        for id in [late, later] {
+            assert_eq!(get_item(&conn, id).unwrap().target_time, Some(today));
+        }
+        assert_ne!(get_item(&conn, done).unwrap().target_time, Some(today));
+        assert_ne!(get_item(&conn, upcoming).unwrap().target_time, Some(today));
+        assert!(overdue_task_ids(&conn, now).unwrap().is_empty());
+    }
+}
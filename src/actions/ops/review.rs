@@ -0,0 +1,284 @@
+use chrono::{
+    Datelike,
+    Duration,
+    NaiveDate,
+    Weekday,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::{
+            COMPLETED_PREFIXES,
+            OPEN_STATUS_CODES,
+        },
+    },
+    args::{
+        parser::OpsReviewCommand,
+        timestr,
+    },
+    config::get_config,
+    db::{
+        crud::{
+            count_items,
+            query_items,
+        },
+        item::{
+            ItemQuery,
+            RECORD,
+            TASK,
+        },
+        ops::get_stats,
+    },
+};
+
+#[derive(Debug, PartialEq)]
+struct ReviewReport {
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+    completed: usize,
+    created: usize,
+    records: usize,
+    open: usize,
+}
+
+pub fn handle_reviewcmd(conn: &Connection, cmd: &OpsReviewCommand) -> Result<(), String> {
+    let report = build_report(conn, &cmd.period, cmd.category.as_deref(), timestr::today())?;
+
+    display::print_bold(&format!(
+        "Review for {} {} - {}:",
+        cmd.period,
+        report.first_day.format("%Y/%m/%d"),
+        report.last_day.format("%Y/%m/%d")
+    ));
+    println!("  Tasks completed: {}", report.completed);
+    println!("  Tasks created:   {}", report.created);
+    println!("  Records logged:  {}", report.records);
+    println!("  Still open:      {}", report.open);
+    Ok(())
+}
+
+// First and last day of the period containing today, weeks start on the given day.
+fn period_days(
+    period: &str,
+    today: NaiveDate,
+    week_start: Weekday,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    match period {
+        "week" => {
+            let week = today.week(week_start);
+            Ok((week.first_day(), week.last_day()))
+        }
+        "month" => {
+            let first = today.with_day(1).unwrap();
+            let next_month = if first.month() == 12 {
+                NaiveDate::from_ymd_opt(first.year() + 1, 1, 1).unwrap()
+            } else {
+                NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1).unwrap()
+            };
+            Ok((first, next_month - Duration::days(1)))
+        }
+        other => Err(format!("Unknown period '{}', expected week or month", other)),
+    }
+}
+
+// Queries exclude the lower bound, so the window starts at the end of the day before.
+// Days are those of the configured timezone, the system one if unset.
+fn day_window(first_day: NaiveDate, last_day: NaiveDate) -> Result<(i64, i64), String> {
+    let end_of_day = |day: NaiveDate| timestr::date_bounds_to_unix_epoch(day).map(|(_, end)| end);
+    Ok((end_of_day(first_day - Duration::days(1))?, end_of_day(last_day)?))
+}
+
+// Covers the period containing today.
+fn build_report(
+    conn: &Connection,
+    period: &str,
+    category: Option<&str>,
+    today: NaiveDate,
+) -> Result<ReviewReport, String> {
+    let (first_day, last_day) = period_days(period, today, get_config().week_start())?;
+    let (start, end) = day_window(first_day, last_day)?;
+    let stats = get_stats(conn, category, Some(start), Some(end), None, None)
+        .map_err(|e| e.to_string())?;
+
+    let mut done_query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(vec![1])
+        .with_closed_time_range(Some(start), Some(end));
+    if let Some(cat) = category {
+        done_query = done_query.with_category(cat);
+    }
+    let completed_tasks = count_items(conn, &done_query).map_err(|e| e.to_string())?;
+
+    let mut open_query = ItemQuery::new()
+        .with_action(TASK)
+        .with_statuses(OPEN_STATUS_CODES.to_vec())
+        .with_target_time_range(Some(start), Some(end));
+    if let Some(cat) = category {
+        open_query = open_query.with_category(cat);
+    }
+    let open_tasks = query_items(conn, &open_query).map_err(|e| e.to_string())?;
+
+    // Records done writes on completion are counted as completions, not as records
+    let mut record_query = ItemQuery::new()
+        .with_action(RECORD)
+        .with_create_time_range(Some(start), Some(end));
+    if let Some(cat) = category {
+        record_query = record_query.with_category(cat);
+    }
+    let records = query_items(conn, &record_query)
+        .map_err(|e| e.to_string())?
+        .iter()
+        .filter(|r| !COMPLETED_PREFIXES.iter().any(|p| r.content.starts_with(p)))
+        .count();
+
+    Ok(ReviewReport {
+        first_day,
+        last_day,
+        // Each recurring task record marks one completed iteration
+        completed: completed_tasks + stats.totals.recurring_task_record,
+        created: stats.totals.task + stats.totals.recurring_task,
+        records,
+        open: open_tasks.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::params;
+
+    use super::*;
+    use crate::{
+        db::{
+            crud::insert_item,
+            item::Item,
+        },
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_recurring_record,
+            insert_recurring_task,
+            update_status,
+        },
+    };
+
+    fn at(timestr: &str) -> i64 {
+        timestr::to_unix_epoch(timestr).unwrap()
+    }
+
+    fn insert_task_at(conn: &Connection, category: &str, created: &str, due: &str) -> i64 {
+        let mut task = Item::with_create_time(
+            TASK.to_string(),
+            category.to_string(),
+            format!("task created {}", created),
+            at(created),
+        );
+        task.target_time = Some(at(due));
+        insert_item(conn, &task).unwrap()
+    }
+
+    fn complete_at(conn: &Connection, id: i64, timestr: &str) {
+        update_status(conn, id, 1);
+        conn.execute(
+            "UPDATE items SET closed_time = ?1 WHERE id = ?2",
+            params![at(timestr), id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_period_days() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        // Wednesday
+        let today = date(2, 26);
+
+        assert_eq!(period_days("week", today, Weekday::Mon).unwrap(), (date(2, 24), date(3, 2)));
+        assert_eq!(period_days("month", today, Weekday::Mon).unwrap(), (date(2, 1), date(2, 28)));
+
+        let monday = date(2, 24);
+        assert_eq!(period_days("week", monday, Weekday::Mon).unwrap(), (date(2, 24), date(3, 2)));
+        let december = date(12, 31);
+        assert_eq!(
+            period_days("month", december, Weekday::Mon).unwrap(),
+            (date(12, 1), date(12, 31))
+        );
+        assert!(period_days("year", today, Weekday::Mon).is_err());
+
+        // Weeks starting on Sunday
+        assert_eq!(period_days("week", today, Weekday::Sun).unwrap(), (date(2, 23), date(3, 1)));
+        let sunday = date(3, 2);
+        assert_eq!(period_days("week", sunday, Weekday::Sun).unwrap(), (date(3, 2), date(3, 8)));
+    }
+
+    #[test]
+    fn test_week_review() {
+        let (conn, _temp_file) = get_test_conn();
+        // Created last week, completed this week
+        let id = insert_task_at(&conn, "work", "2025/02/20 9AM", "2025/02/25 5PM");
+        complete_at(&conn, id, "2025/02/25 4PM");
+        // Created and completed this week
+        let id = insert_task_at(&conn, "work", "2025/02/24 9AM", "2025/02/26 5PM");
+        complete_at(&conn, id, "2025/02/26 10AM");
+        // Completed this week and edited after, counted by when it was completed
+        let id = insert_task_at(&conn, "home", "2025/02/24 9AM", "2025/02/27 5PM");
+        complete_at(&conn, id, "2025/02/27 4PM");
+        conn.execute(
+            "UPDATE items SET modify_time = ?1 WHERE id = ?2",
+            params![at("2025/03/05 9AM"), id],
+        )
+        .unwrap();
+        // Completed last week, not counted
+        let id = insert_task_at(&conn, "work", "2025/02/17 9AM", "2025/02/21 5PM");
+        complete_at(&conn, id, "2025/02/21 4PM");
+        // Created this week, still open and due within the week
+        insert_task_at(&conn, "work", "2025/02/25 9AM", "2025/02/28 5PM");
+        insert_task_at(&conn, "home", "2025/03/02 9AM", "2025/03/02 8PM");
+        // Open but due next week
+        insert_task_at(&conn, "work", "2025/02/26 9AM", "2025/03/05 5PM");
+
+        insert_record(&conn, "work", "standup notes", "2025/02/24 10AM");
+        insert_record(&conn, "home", "groceries", "2025/03/02 11PM");
+        insert_record(&conn, "home", "last week", "2025/02/23 11PM");
+        // Written by done for the completions above
+        insert_record(&conn, "work", "Completed Task: task created", "2025/02/25 4PM");
+
+        let recurring_id = insert_recurring_task(&conn, "work", "standup", "Daily 9AM");
+        let record_id = insert_recurring_record(&conn, "work", "standup done", recurring_id, 0);
+        conn.execute(
+            "UPDATE items SET create_time = ?1 WHERE id IN (?2, ?3)",
+            params![at("2025/02/25 9AM"), recurring_id, record_id],
+        )
+        .unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2025, 2, 26).unwrap();
+        let first_day = NaiveDate::from_ymd_opt(2025, 2, 24).unwrap();
+        let last_day = NaiveDate::from_ymd_opt(2025, 3, 2).unwrap();
+
+        let report = build_report(&conn, "week", None, today).unwrap();
+        assert_eq!(
+            report,
+            ReviewReport {
+                first_day,
+                last_day,
+                completed: 4,
+                created: 6,
+                records: 2,
+                open: 2,
+            }
+        );
+
+        let report = build_report(&conn, "week", Some("home"), today).unwrap();
+        assert_eq!(
+            report,
+            ReviewReport {
+                first_day,
+                last_day,
+                completed: 1,
+                created: 2,
+                records: 1,
+                open: 1,
+            }
+        );
+    }
+}
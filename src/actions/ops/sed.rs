@@ -0,0 +1,206 @@
+use regex::Regex;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        ops::{
+            backup::backup_path,
+            batch::{
+                parse_action_filter,
+                pluralize,
+            },
+        },
+    },
+    args::parser::OpsSedCommand,
+    db::{
+        crud::{
+            query_items,
+            update_item,
+        },
+        item::{
+            Item,
+            ItemQuery,
+        },
+    },
+};
+
+pub fn handle_sedcmd(conn: &Connection, cmd: &OpsSedCommand) -> Result<(), String> {
+    if cmd.from.is_empty() {
+        return Err("--from cannot be empty".to_string());
+    }
+    let actions = parse_action_filter(&cmd.action)?;
+    let pattern = if cmd.regex {
+        Some(Regex::new(&cmd.from).map_err(|e| format!("Invalid regex '{}': {}", cmd.from, e))?)
+    } else {
+        None
+    };
+
+    let items = query_items_for_sed(
+        conn,
+        actions.as_ref(),
+        cmd.category.as_deref(),
+        &cmd.from,
+        cmd.regex,
+    )?;
+    let changed = replace_content(items, &cmd.from, &cmd.to, pattern.as_ref());
+
+    if changed.is_empty() {
+        display::print_bold("No items found matching the filters");
+        return Ok(());
+    }
+
+    display::print_bold("backing up database prior to sed operation");
+    if let Err(e) = backup_path(None) {
+        display::print_red(&e);
+    }
+
+    let affected = apply_replacements(conn, &changed)?;
+    display::print_bold(&format!(
+        "✓ Successfully updated {}",
+        pluralize(affected, "item")
+    ));
+    display::print_items(&changed, false);
+    Ok(())
+}
+
+// LIKE is case insensitive in sqlite, so the query may return items
+// which the replacement does not touch; those are dropped later.
+fn query_items_for_sed(
+    conn: &Connection,
+    actions: Option<&Vec<String>>,
+    category: Option<&str>,
+    from: &str,
+    regex: bool,
+) -> Result<Vec<Item>, String> {
+    let mut query = ItemQuery::new();
+    if let Some(acts) = actions {
+        query = query.with_actions(acts.iter().map(|s| s.as_str()).collect());
+    }
+    if let Some(cat) = category {
+        query = query.with_category(cat);
+    }
+    query = if regex {
+        query.with_content_regex(from)
+    } else {
+        query.with_content_like(from)
+    };
+    query_items(conn, &query).map_err(|e| e.to_string())
+}
+
+// Returns only the items whose content is changed by the replacement.
+fn replace_content(items: Vec<Item>, from: &str, to: &str, pattern: Option<&Regex>) -> Vec<Item> {
+    items
+        .into_iter()
+        .filter_map(|mut item| {
+            let replaced = match pattern {
+                Some(re) => re.replace_all(&item.content, to).into_owned(),
+                None => item.content.replace(from, to),
+            };
+            if replaced == item.content {
+                return None;
+            }
+            item.content = replaced;
+            Some(item)
+        })
+        .collect()
+}
+
+fn apply_replacements(conn: &Connection, items: &[Item]) -> Result<usize, String> {
+    for item in items {
+        update_item(conn, item).map_err(|e| format!("Failed to update item: {}", e))?;
+    }
+    Ok(items.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::get_item,
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_task,
+        },
+    };
+
+    fn sed(conn: &Connection, from: &str, to: &str, category: Option<&str>, regex: bool) -> usize {
+        let pattern = if regex { Some(Regex::new(from).unwrap()) } else { None };
+        let items = query_items_for_sed(conn, None, category, from, regex).unwrap();
+        let changed = replace_content(items, from, to, pattern.as_ref());
+        apply_replacements(conn, &changed).unwrap()
+    }
+
+    #[test]
+    fn test_literal_replacement() {
+        let (conn, _temp_file) = get_test_conn();
+        let id1 = insert_task(&conn, "work", "ship apollo v2", "tomorrow");
+        let id2 = insert_task(&conn, "work", "apollo retro, then apollo demo", "tomorrow");
+        let id3 = insert_record(&conn, "work", "reviewed apollo docs", "yesterday");
+        let id4 = insert_task(&conn, "work", "unrelated chore", "tomorrow");
+
+        assert_eq!(sed(&conn, "apollo", "artemis", None, false), 3);
+
+        assert_eq!(get_item(&conn, id1).unwrap().content, "ship artemis v2");
+        assert_eq!(
+            get_item(&conn, id2).unwrap().content,
+            "artemis retro, then artemis demo"
+        );
+        assert_eq!(get_item(&conn, id3).unwrap().content, "reviewed artemis docs");
+        let untouched = get_item(&conn, id4).unwrap();
+        assert_eq!(untouched.content, "unrelated chore");
+        assert!(untouched.modify_time.is_none());
+    }
+
+    #[test]
+    fn test_non_matching_items_untouched() {
+        let (conn, _temp_file) = get_test_conn();
+        let id1 = insert_task(&conn, "work", "apollo launch", "tomorrow");
+        let id2 = insert_task(&conn, "home", "apollo poster", "tomorrow");
+        // LIKE matches case insensitively, but replacement is case sensitive
+        let id3 = insert_task(&conn, "work", "Apollo kickoff", "tomorrow");
+
+        assert_eq!(sed(&conn, "apollo", "artemis", Some("work"), false), 1);
+
+        assert_eq!(get_item(&conn, id1).unwrap().content, "artemis launch");
+        let other_category = get_item(&conn, id2).unwrap();
+        assert_eq!(other_category.content, "apollo poster");
+        assert!(other_category.modify_time.is_none());
+        let different_case = get_item(&conn, id3).unwrap();
+        assert_eq!(different_case.content, "Apollo kickoff");
+        assert!(different_case.modify_time.is_none());
+    }
+
+    #[test]
+    fn test_regex_replacement() {
+        let (conn, _temp_file) = get_test_conn();
+        let id1 = insert_task(&conn, "work", "ticket-123 fix login", "tomorrow");
+        let id2 = insert_task(&conn, "work", "ticket-abc not a number", "tomorrow");
+
+        assert_eq!(sed(&conn, r"ticket-(\d+)", "JIRA-$1", None, true), 1);
+
+        assert_eq!(get_item(&conn, id1).unwrap().content, "JIRA-123 fix login");
+        assert_eq!(get_item(&conn, id2).unwrap().content, "ticket-abc not a number");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let (conn, _temp_file) = get_test_conn();
+        let base = OpsSedCommand {
+            from: "(".to_string(),
+            to: "x".to_string(),
+            category: None,
+            action: "all".to_string(),
+            regex: true,
+        };
+        assert!(handle_sedcmd(&conn, &base).unwrap_err().contains("Invalid regex"));
+
+        let empty = OpsSedCommand {
+            from: String::new(),
+            regex: false,
+            ..base
+        };
+        assert!(handle_sedcmd(&conn, &empty).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use nanoserde::SerJson;
 use rusqlite::Connection;
 
 use crate::{
@@ -6,26 +7,39 @@ use crate::{
         parser::OpsStatCommand,
         timestr,
     },
-    db::ops::get_stats,
+    db::ops::{
+        get_stats,
+        StatTable,
+    },
 };
 
 pub fn handle_statcmd(conn: &Connection, cmd: &OpsStatCommand) -> Result<(), String> {
     let stats = query_stats(conn, cmd)?;
 
+    if cmd.total_only {
+        println!("{}", stats.totals.total);
+        return Ok(());
+    }
+
+    if cmd.json {
+        println!("{}", format_stats_json(&stats, cmd.limit));
+        return Ok(());
+    }
+
     if stats.rows.is_empty() {
         display::print_bold("No statistics found");
         return Ok(());
     }
 
     display::print_bold("Statistics:");
-    print_stats_table(&stats);
+    print_stats_table(&stats, cmd.limit);
     Ok(())
 }
 
 fn query_stats(
     conn: &Connection,
     cmd: &OpsStatCommand,
-) -> Result<crate::db::ops::StatTable, String> {
+) -> Result<StatTable, String> {
     // Parse time filters
     let create_time_min = if let Some(ref starting_time) = cmd.starting_time {
         Some(timestr::to_unix_epoch(starting_time)?)
@@ -50,20 +64,27 @@ fn query_stats(
     .map_err(|e| e.to_string())
 }
 
-fn print_stats_table(stats: &crate::db::ops::StatTable) {
+fn print_stats_table(stats: &StatTable, limit: Option<usize>) {
+    for line in format_stats_table(stats, limit) {
+        println!("{}", line);
+    }
+}
+
+// Rows are sorted by total, so a limit keeps the largest categories.
+// Totals always cover every category.
+fn format_stats_table(stats: &StatTable, limit: Option<usize>) -> Vec<String> {
     // Define column widths
     let category_width = 20;
     let number_width = 12;
     // Separator width: category + 5 number columns + delimiters (6 "| " + 1 final "|" = 13 chars)
     let separator_width = category_width + number_width * 5 + 13;
+    let separator = format!("{:-<width$}", "", width = separator_width);
+    let shown = limit.unwrap_or(stats.rows.len()).min(stats.rows.len());
+    let mut lines = Vec::with_capacity(shown + 7);
 
-    // Print header
-    println!(
-        "{:-<width$}",
-        "",
-        width = separator_width
-    );
-    println!(
+    // Header
+    lines.push(separator.clone());
+    lines.push(format!(
         "| {:<cat_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}|",
         "Category",
         "Task",
@@ -73,16 +94,12 @@ fn print_stats_table(stats: &crate::db::ops::StatTable) {
         "Total",
         cat_w = category_width,
         num_w = number_width
-    );
-    println!(
-        "{:-<width$}",
-        "",
-        width = separator_width
-    );
-
-    // Print data rows
-    for row in &stats.rows {
-        println!(
+    ));
+    lines.push(separator.clone());
+
+    // Data rows
+    for row in &stats.rows[..shown] {
+        lines.push(format!(
             "| {:<cat_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}|",
             truncate_string(&row.category, category_width),
             row.task,
@@ -92,18 +109,14 @@ fn print_stats_table(stats: &crate::db::ops::StatTable) {
             row.total,
             cat_w = category_width,
             num_w = number_width
-        );
+        ));
     }
 
-    // Print separator before totals
-    println!(
-        "{:-<width$}",
-        "",
-        width = separator_width
-    );
+    // Separator before totals
+    lines.push(separator.clone());
 
-    // Print totals row
-    println!(
+    // Totals row
+    lines.push(format!(
         "| {:<cat_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}| {:<num_w$}|",
         "TOTAL",
         stats.totals.task,
@@ -113,12 +126,28 @@ fn print_stats_table(stats: &crate::db::ops::StatTable) {
         stats.totals.total,
         cat_w = category_width,
         num_w = number_width
-    );
-    println!(
-        "{:-<width$}",
-        "",
-        width = separator_width
-    );
+    ));
+    lines.push(separator);
+
+    if shown < stats.rows.len() {
+        lines.push(format!(
+            "Showing top {} of {} categories, {} omitted",
+            shown,
+            stats.rows.len(),
+            stats.rows.len() - shown
+        ));
+    }
+    lines
+}
+
+// Same limit as the table, totals still cover every category.
+fn format_stats_json(stats: &StatTable, limit: Option<usize>) -> String {
+    let shown = limit.unwrap_or(stats.rows.len()).min(stats.rows.len());
+    StatTable {
+        rows: stats.rows[..shown].to_vec(),
+        totals: stats.totals.clone(),
+    }
+    .serialize_json()
 }
 
 fn truncate_string(s: &str, max_len: usize) -> String {
@@ -131,6 +160,8 @@ fn truncate_string(s: &str, max_len: usize) -> String {
 
 #[cfg(test)]
 mod tests {
+    use nanoserde::DeJson;
+
     use super::*;
     use crate::tests::{
         get_test_conn,
@@ -146,6 +177,9 @@ mod tests {
                 category: None,
                 starting_time: None,
                 ending_time: None,
+                limit: None,
+                json: false,
+                total_only: false,
             }
         }
 
@@ -241,4 +275,53 @@ mod tests {
         assert_eq!(stats.totals.record, 2);
         assert_eq!(stats.totals.total, 2);
     }
+
+    #[test]
+    fn test_stats_table_limit() {
+        let (conn, _temp_file) = get_test_conn();
+        for (category, count) in [("Work", 4), ("Personal", 3), ("Gym", 2), ("Chores", 1)] {
+            for i in 0..count {
+                insert_task(&conn, category, &format!("Task {}", i), "tomorrow");
+            }
+        }
+        let stats = query_stats(&conn, &OpsStatCommand::default_test()).unwrap();
+
+        let lines = format_stats_table(&stats, Some(2));
+        let rows: Vec<&String> = lines.iter().filter(|l| l.starts_with("| ")).collect();
+        // Header, top 2 categories and totals
+        assert_eq!(rows.len(), 4);
+        assert!(rows[1].starts_with("| Work "));
+        assert!(rows[2].starts_with("| Personal "));
+        assert!(rows[3].starts_with("| TOTAL "));
+        assert!(rows[3].contains("| 10 "));
+        assert_eq!(lines.last().unwrap(), "Showing top 2 of 4 categories, 2 omitted");
+
+        // A limit past the category count shows everything without a note
+        let lines = format_stats_table(&stats, Some(10));
+        assert_eq!(lines.iter().filter(|l| l.starts_with("| ")).count(), 6);
+        assert!(lines.last().unwrap().starts_with("---"));
+    }
+
+    #[test]
+    fn test_stats_json() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "Work", "Task 1", "tomorrow");
+        insert_task(&conn, "Work", "Task 2", "tomorrow");
+        insert_record(&conn, "Work", "Record 1", "yesterday");
+        let recurring_id = insert_recurring_task(&conn, "Gym", "Workout", "Daily 6PM");
+        insert_recurring_record(&conn, "Gym", "Workout done", recurring_id, 1000);
+        let stats = query_stats(&conn, &OpsStatCommand::default_test()).unwrap();
+
+        let parsed = StatTable::deserialize_json(&format_stats_json(&stats, None)).unwrap();
+        assert_eq!(parsed.rows, stats.rows);
+        assert_eq!(parsed.totals, stats.totals);
+        assert_eq!(parsed.totals.total, 5);
+        let row_sum: usize = parsed.rows.iter().map(|r| r.total).sum();
+        assert_eq!(row_sum, parsed.totals.total);
+
+        let parsed = StatTable::deserialize_json(&format_stats_json(&stats, Some(1))).unwrap();
+        assert_eq!(parsed.rows.len(), 1);
+        assert_eq!(parsed.rows[0].category, "Work");
+        assert_eq!(parsed.totals.total, 5);
+    }
 }
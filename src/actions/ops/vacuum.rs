@@ -0,0 +1,102 @@
+use std::fs;
+
+use rusqlite::Connection;
+
+use crate::actions::display;
+
+pub fn handle_vacuumcmd(conn: &Connection) -> Result<(), String> {
+    let (before, after) = vacuum(conn)?;
+    display::print_bold(&format!(
+        "✓ Vacuumed database from {} to {}",
+        format_size(before),
+        format_size(after)
+    ));
+    Ok(())
+}
+
+// Returns the size of the database and its WAL before and after,
+// the checkpoint moves the rebuilt pages back so the main file shrinks.
+pub(super) fn vacuum(conn: &Connection) -> Result<(u64, u64), String> {
+    if !conn.is_autocommit() {
+        return Err("Cannot vacuum while a transaction is open".to_string());
+    }
+    let db_path = conn
+        .path()
+        .filter(|p| !p.is_empty())
+        .ok_or("Database is not backed by a file, nothing to vacuum")?
+        .to_string();
+    let before = database_size(&db_path);
+    conn.execute_batch("VACUUM")
+        .map_err(|e| format!("Failed to vacuum database: {}", e))?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+        .map_err(|e| format!("Failed to checkpoint database: {}", e))?;
+    Ok((before, database_size(&db_path)))
+}
+
+fn database_size(db_path: &str) -> u64 {
+    [db_path.to_string(), format!("{}-wal", db_path)]
+        .iter()
+        .filter_map(|p| fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            crud::{
+                delete_item,
+                query_items,
+            },
+            item::ItemQuery,
+        },
+        tests::{
+            get_test_conn,
+            insert_record,
+        },
+    };
+
+    #[test]
+    fn test_vacuum() {
+        let (conn, _temp_file) = get_test_conn();
+        let long_content = "x".repeat(2000);
+        let ids: Vec<i64> = (0..200)
+            .map(|_| insert_record(&conn, "log", &long_content, "today"))
+            .collect();
+        for id in &ids[..190] {
+            delete_item(&conn, *id).unwrap();
+        }
+
+        let (before, after) = vacuum(&conn).unwrap();
+        assert!(after < before);
+        assert_eq!(query_items(&conn, &ItemQuery::new()).unwrap().len(), 10);
+
+        let tx = conn.unchecked_transaction().unwrap();
+        assert!(vacuum(&conn).unwrap_err().contains("transaction is open"));
+        tx.rollback().unwrap();
+        assert!(vacuum(&conn).is_ok());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 + 512 * 1024), "3.5 MiB");
+    }
+}
@@ -1,30 +1,87 @@
 use chrono::{
+    DateTime,
     Datelike,
     Duration,
     Local,
     TimeZone,
     Timelike,
+    Utc,
 };
 
+use crate::config::get_config;
+
 // Parse a cron string and return the next or last occurrence timestamp
 // The cron implementation is specific to this project
 // avoiding additional dependency while implementing specific
 // subset of cron functionalities.
+// Schedules follow the wall clock of the configured timezone, the system one if unset.
 pub fn get_next_occurrence(cron_str: &str) -> Result<i64, String> {
-    get_occurrence_from(cron_str, Local::now(), true)
+    get_next_occurrence_after(cron_str, Utc::now().timestamp())
 }
 
 pub fn get_last_occurrence(cron_str: &str) -> Result<i64, String> {
-    get_occurrence_from(cron_str, Local::now(), false)
+    let now = Utc::now().timestamp();
+    match get_config().timezone() {
+        Some(tz) => get_occurrence_from(cron_str, datetime_at(&tz, now)?, false),
+        None => get_occurrence_from(cron_str, datetime_at(&Local, now)?, false),
+    }
+}
+
+// Next occurrence after the given unix time rather than the wall clock.
+pub fn get_next_occurrence_after(cron_str: &str, now: i64) -> Result<i64, String> {
+    match get_config().timezone() {
+        Some(tz) => get_occurrence_from(cron_str, datetime_at(&tz, now)?, true),
+        None => get_occurrence_from(cron_str, datetime_at(&Local, now)?, true),
+    }
+}
+
+pub fn get_next_occurrences(cron_str: &str, count: usize) -> Result<Vec<i64>, String> {
+    let now = Utc::now().timestamp();
+    match get_config().timezone() {
+        Some(tz) => get_occurrences_from(cron_str, datetime_at(&tz, now)?, count),
+        None => get_occurrences_from(cron_str, datetime_at(&Local, now)?, count),
+    }
+}
+
+fn datetime_at<T: TimeZone>(tz: &T, timestamp: i64) -> Result<DateTime<T>, String> {
+    tz.timestamp_opt(timestamp, 0)
+        .single()
+        .ok_or_else(|| format!("Invalid timestamp: {}", timestamp))
+}
+
+// Each occurrence is strictly after the previous one.
+fn get_occurrences_from<T: TimeZone>(
+    cron_str: &str,
+    now: DateTime<T>,
+    count: usize,
+) -> Result<Vec<i64>, String> {
+    let mut occurrences = Vec::with_capacity(count);
+    let mut cursor = now;
+    for _ in 0..count {
+        let next = get_occurrence_from(cron_str, cursor.clone(), true)?;
+        occurrences.push(next);
+        cursor = datetime_at(&cursor.timezone(), next)
+            .map_err(|_| format!("Invalid occurrence for cron: {}", cron_str))?;
+    }
+    Ok(occurrences)
 }
 
 // Underlying implementation to allow for testing
-fn get_occurrence_from(cron_str: &str, now: chrono::DateTime<Local>, forward: bool) -> Result<i64, String> {
+fn get_occurrence_from<T: TimeZone>(
+    cron_str: &str,
+    now: DateTime<T>,
+    forward: bool,
+) -> Result<i64, String> {
     let parts: Vec<&str> = cron_str.split_whitespace().collect();
     if parts.len() != 5 {
         return Err(format!("Invalid cron format: {}", cron_str));
     }
 
+    // Interval: "*/N * * * *" or "minute */N * * *"
+    if parts[2..] == ["*", "*", "*"] && (parts[0].starts_with("*/") || parts[1].starts_with("*/")) {
+        return calculate_interval(now, parts[0], parts[1], forward);
+    }
+
     let minute: u32 = parts[0]
         .parse()
         .map_err(|_| format!("Invalid minute: {}", parts[0]))?;
@@ -64,7 +121,71 @@ fn get_occurrence_from(cron_str: &str, now: chrono::DateTime<Local>, forward: bo
     }
 }
 
-fn calculate_daily(now: chrono::DateTime<Local>, minute: u32, hour: u32, forward: bool) -> Result<i64, String> {
+enum Field {
+    Any,
+    Exact(u32),
+    Step(u32),
+}
+
+impl Field {
+    fn parse(s: &str, name: &str, max: u32) -> Result<Field, String> {
+        let invalid = || format!("Invalid {}: {}", name, s);
+        if s == "*" {
+            return Ok(Field::Any);
+        }
+        if let Some(step) = s.strip_prefix("*/") {
+            let step: u32 = step.parse().map_err(|_| invalid())?;
+            if step == 0 || step > max {
+                return Err(invalid());
+            }
+            return Ok(Field::Step(step));
+        }
+        let value: u32 = s.parse().map_err(|_| invalid())?;
+        if value > max {
+            return Err(invalid());
+        }
+        Ok(Field::Exact(value))
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        match self {
+            Field::Any => true,
+            Field::Exact(v) => value == *v,
+            Field::Step(step) => value.is_multiple_of(*step),
+        }
+    }
+}
+
+// Steps through minutes until both fields match, a match is always found within a day.
+fn calculate_interval<T: TimeZone>(
+    now: DateTime<T>,
+    minute_str: &str,
+    hour_str: &str,
+    forward: bool,
+) -> Result<i64, String> {
+    let minute = Field::parse(minute_str, "minute", 59)?;
+    let hour = Field::parse(hour_str, "hour", 23)?;
+    let step = if forward { Duration::minutes(1) } else { Duration::minutes(-1) };
+
+    let mut candidate = now.with_second(0).unwrap().with_nanosecond(0).unwrap();
+    if (forward && candidate <= now) || (!forward && candidate >= now) {
+        candidate += step;
+    }
+    for _ in 0..=24 * 60 {
+        if minute.matches(candidate.minute()) && hour.matches(candidate.hour()) {
+            return Ok(candidate.timestamp());
+        }
+        candidate += step;
+    }
+    Err(format!("No occurrence found for cron: {} {} * * *", minute_str, hour_str))
+}
+
+fn calculate_daily<T: TimeZone>(
+    now: DateTime<T>,
+    minute: u32,
+    hour: u32,
+    forward: bool,
+) -> Result<i64, String> {
     let mut candidate = now
         .with_hour(hour)
         .ok_or("Invalid hour")?
@@ -90,8 +211,8 @@ fn calculate_daily(now: chrono::DateTime<Local>, minute: u32, hour: u32, forward
     Ok(candidate.timestamp())
 }
 
-fn calculate_weekly(
-    now: chrono::DateTime<Local>,
+fn calculate_weekly<T: TimeZone>(
+    now: DateTime<T>,
     minute: u32,
     hour: u32,
     weekday_str: &str,
@@ -149,8 +270,8 @@ fn calculate_weekly(
     Err("Could not find valid weekday".to_string())
 }
 
-fn calculate_monthly(
-    now: chrono::DateTime<Local>,
+fn calculate_monthly<T: TimeZone>(
+    now: DateTime<T>,
     minute: u32,
     hour: u32,
     day: u32,
@@ -160,7 +281,8 @@ fn calculate_monthly(
     let mut month = now.month();
 
     // Try current month
-    if let Some(dt) = Local
+    if let Some(dt) = now
+        .timezone()
         .with_ymd_and_hms(year, month, day, hour, minute, 0)
         .earliest()
     {
@@ -177,7 +299,8 @@ fn calculate_monthly(
             month = 1;
             year += 1;
         }
-        let dt = Local
+        let dt = now
+            .timezone()
             .with_ymd_and_hms(year, month, day, hour, minute, 0)
             .earliest();
         match dt {
@@ -194,7 +317,8 @@ fn calculate_monthly(
                 month -= 1;
             }
 
-            if let Some(dt) = Local
+            if let Some(dt) = now
+                .timezone()
                 .with_ymd_and_hms(year, month, day, hour, minute, 0)
                 .earliest()
             {
@@ -206,8 +330,8 @@ fn calculate_monthly(
     }
 }
 
-fn calculate_yearly(
-    now: chrono::DateTime<Local>,
+fn calculate_yearly<T: TimeZone>(
+    now: DateTime<T>,
     minute: u32,
     hour: u32,
     day: u32,
@@ -217,7 +341,8 @@ fn calculate_yearly(
     let mut year = now.year();
 
     // Try this year
-    if let Some(dt) = Local
+    if let Some(dt) = now
+        .timezone()
         .with_ymd_and_hms(year, month, day, hour, minute, 0)
         .earliest()
     {
@@ -233,7 +358,8 @@ fn calculate_yearly(
     } else {
         year -= 1;
     }
-    let dt = Local
+    let dt = now
+        .timezone()
         .with_ymd_and_hms(year, month, day, hour, minute, 0)
         .earliest();
     match dt {
@@ -285,6 +411,14 @@ mod tests {
             ("2024-12-26 10:00", "0 9 25 12 *", "2025-12-25 09:00"), // Next year
             ("2024-12-25 08:00", "0 9 25 12 *", "2024-12-25 09:00"), // Same day before time
             ("2024-12-25 10:00", "0 9 25 12 *", "2025-12-25 09:00"), // Same day after time
+            // Interval tests
+            ("2024-03-15 10:00", "*/30 * * * *", "2024-03-15 10:30"), // On a step -> next step
+            ("2024-03-15 10:07", "*/30 * * * *", "2024-03-15 10:30"), // Between steps
+            ("2024-03-15 23:45", "*/30 * * * *", "2024-03-16 00:00"), // Across midnight
+            ("2024-03-15 10:00", "0 */2 * * *", "2024-03-15 12:00"),  // On a step -> next step
+            ("2024-03-15 11:30", "0 */2 * * *", "2024-03-15 12:00"),  // Between steps
+            ("2024-03-15 23:10", "0 */6 * * *", "2024-03-16 00:00"),  // Across midnight
+            ("2024-03-15 10:00", "15 */3 * * *", "2024-03-15 12:15"), // Fixed minute
         ];
 
         for (now_str, cron, expected_str) in test_cases {
@@ -320,6 +454,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_next_occurrences() {
+        let now_naive = NaiveDateTime::parse_from_str("2024-03-15 10:00", "%Y-%m-%d %H:%M").unwrap();
+        let now = Local.from_local_datetime(&now_naive).unwrap();
+        let format = |ts: i64| Local.timestamp_opt(ts, 0).unwrap().format("%Y-%m-%d %H:%M").to_string();
+
+        let weekdays: Vec<String> = get_occurrences_from("0 9 * * 1-5", now, 5)
+            .unwrap()
+            .into_iter()
+            .map(format)
+            .collect();
+        assert_eq!(
+            weekdays,
+            vec![
+                "2024-03-18 09:00",
+                "2024-03-19 09:00",
+                "2024-03-20 09:00",
+                "2024-03-21 09:00",
+                "2024-03-22 09:00",
+            ]
+        );
+
+        let monthly = get_occurrences_from("0 9 30 * *", now, 3).unwrap();
+        assert_eq!(format(monthly[0]), "2024-03-30 09:00");
+        assert_eq!(format(monthly[1]), "2024-04-30 09:00");
+        assert_eq!(format(monthly[2]), "2024-05-30 09:00");
+
+        let half_hourly: Vec<String> = get_occurrences_from("*/30 * * * *", now, 3)
+            .unwrap()
+            .into_iter()
+            .map(format)
+            .collect();
+        assert_eq!(half_hourly, vec!["2024-03-15 10:30", "2024-03-15 11:00", "2024-03-15 11:30"]);
+
+        // The wall clock is the one of the given timezone
+        let tokyo = chrono_tz::Asia::Tokyo.with_ymd_and_hms(2024, 3, 15, 10, 0, 0).unwrap();
+        let daily = get_occurrences_from("0 9 * * *", tokyo, 1).unwrap();
+        assert_eq!(daily[0], tokyo.with_day(16).unwrap().with_hour(9).unwrap().timestamp());
+        let monthly = get_occurrences_from("0 9 30 * *", tokyo, 1).unwrap();
+        let expected = chrono_tz::Asia::Tokyo.with_ymd_and_hms(2024, 3, 30, 9, 0, 0).unwrap();
+        assert_eq!(monthly[0], expected.timestamp());
+
+        assert!(get_occurrences_from("0 9 * *", now, 5).is_err());
+        assert!(get_occurrences_from("*/0 * * * *", now, 1).is_err());
+        assert!(get_occurrences_from("0 */25 * * *", now, 1).is_err());
+    }
+
     #[test]
     fn test_last_occurrence() {
         let test_cases = vec![
@@ -354,6 +535,13 @@ mod tests {
             ("2024-03-15 10:00", "0 9 25 12 *", "2023-12-25 09:00"), // Before date -> previous year
             ("2024-12-25 10:00", "0 9 25 12 *", "2024-12-25 09:00"), // Same day after time
             ("2024-12-25 08:00", "0 9 25 12 *", "2023-12-25 09:00"), // Same day before time
+            // Interval tests
+            ("2024-03-15 10:00", "*/30 * * * *", "2024-03-15 09:30"), // On a step -> previous step
+            ("2024-03-15 10:07", "*/30 * * * *", "2024-03-15 10:00"), // Between steps
+            ("2024-03-16 00:10", "*/30 * * * *", "2024-03-16 00:00"), // Just after midnight
+            ("2024-03-15 11:30", "0 */2 * * *", "2024-03-15 10:00"),  // Between steps
+            ("2024-03-16 01:00", "0 */6 * * *", "2024-03-16 00:00"),  // Just after midnight
+            ("2024-03-16 00:00", "0 */6 * * *", "2024-03-15 18:00"),  // On midnight -> previous day
         ];
 
         for (now_str, cron, expected_str) in test_cases {
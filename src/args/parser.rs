@@ -1,9 +1,23 @@
+use std::collections::HashSet;
+
 use clap::{
     Args,
     Parser,
     Subcommand,
 };
-use crate::args::timestr::{parse_flexible_timestr, parse_recurring_timestr};
+use crate::{
+    actions::display::MIN_TABLE_WIDTH,
+    args::timestr::{parse_duration_minutes, parse_flexible_timestr, parse_recurring_timestr},
+    config::{
+        get_config,
+        Config,
+    },
+    db::item::{
+        builtin_status,
+        normalize_category,
+        validate_limit,
+    },
+};
 
 /// a simple CLI tool for tracking tasks and records from terminal
 ///
@@ -14,6 +28,27 @@ use crate::args::timestr::{parse_flexible_timestr, parse_recurring_timestr};
 pub struct CliArgs {
     #[command(subcommand)]
     pub arguments: Action,
+    /// date display style: human, iso, or a strftime format like "%Y-%m-%d %H:%M"
+    #[arg(long, global = true)]
+    pub date_format: Option<String>,
+    /// include seconds in displayed times, for the human date format
+    #[arg(long, global = true, default_value_t = false)]
+    pub seconds: bool,
+    /// print executed sql queries to stderr, also enabled by TASCLI_DEBUG
+    #[arg(long, global = true, default_value_t = false)]
+    pub verbose: bool,
+    /// table width in columns instead of the detected terminal width,
+    /// COLUMNS is used when this is not given
+    #[arg(long, global = true, value_parser = validate_width)]
+    pub width: Option<usize>,
+    /// print table rows as tab separated fields, index, category, content and time,
+    /// without borders or wrapping, for awk and cut
+    #[arg(long, global = true, default_value_t = false)]
+    pub plain: bool,
+    /// color and bold output: auto colors a terminal unless NO_COLOR is set,
+    /// always keeps colors when piped, e.g. into less -R
+    #[arg(long, global = true, default_value = "auto", value_parser = ["auto", "always", "never"])]
+    pub color: String,
 }
 
 #[derive(Debug, Subcommand)]
@@ -24,13 +59,25 @@ pub enum Action {
     Record(RecordCommand),
     /// complete task and generates a corresponding record entry
     Done(DoneCommand),
+    /// cancel task and generates a record with the reason
+    Cancel(CancelCommand),
+    /// mark task as in progress
+    Start(StartCommand),
+    /// pin task to the top of task listings
+    Pin(PinCommand),
+    /// unpin a previously pinned task
+    Unpin(PinCommand),
     /// update task and record entries
     Update(UpdateCommand),
     /// delete task or record
     Delete(DeleteCommand),
-    /// list tasks or records
-    #[command(subcommand)]
-    List(ListCommand),
+    /// list tasks or records, list alone lists tasks
+    /// or the default_list set in config
+    List(ListArgs),
+    /// list the most recently created items of any type
+    Recent(RecentCommand),
+    /// print the number of open tasks due today or overdue, e.g. for shell prompts
+    CountDue,
     /// operations and statistics
     #[command(subcommand)]
     Ops(OpsCommand),
@@ -46,57 +93,183 @@ pub struct TaskCommand {
     #[arg(value_parser = validate_timestr)]
     pub timestr: Option<String>,
     /// category of the task
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_category)]
     pub category: Option<String>,
+    /// initial status of the task, default to ongoing,
+    /// accept ongoing|done|cancelled|duplicate|suspended|pending|in_progress
+    #[arg(short, long, value_parser = parse_status)]
+    pub status: Option<u8>,
+    /// longer note kept apart from the content,
+    /// shown by list show --full
+    #[arg(short, long)]
+    pub note: Option<String>,
+    /// link or ticket the item refers to, kept out of the table,
+    /// shown by list show --full
+    #[arg(long = "ref", value_parser = validate_reference)]
+    pub reference: Option<String>,
+    /// estimated time to complete, like 45m, 2h or 1h30m
+    #[arg(short, long, value_parser = parse_duration_minutes)]
+    pub estimate: Option<i64>,
+    /// day you plan to work on the task, apart from when it is due,
+    /// listed by list task --do
+    #[arg(long = "do", value_parser = validate_timestr)]
+    pub do_date: Option<String>,
+    /// how long before the deadline ops remind lists the task,
+    /// like 30m, 2h or 24h
+    #[arg(long, value_parser = parse_duration_minutes)]
+    pub remind: Option<i64>,
+    /// do not print the inserted task
+    #[arg(short, long, default_value_t = false)]
+    pub quiet: bool,
+    /// cut content over max_content_length instead of failing
+    #[arg(long, default_value_t = false)]
+    pub truncate: bool,
+    /// due at the next occurrence of the recurring task
+    /// at this index from the previous list command
+    #[arg(long, value_parser = validate_index, conflicts_with = "timestr")]
+    pub before_recurring: Option<usize>,
+    /// how long before the recurring occurrence the task is due,
+    /// like 45m, 2h or 1h30m
+    #[arg(long, value_parser = parse_duration_minutes, requires = "before_recurring")]
+    pub lead: Option<i64>,
 }
 
 #[derive(Debug, Args)]
 pub struct RecordCommand {
-    /// content of the record
-    #[arg(value_parser = |s: &str| syntax_helper("record", s))]
-    pub content: String,
+    /// content of the record, may be omitted when only setting --goal
+    #[arg(value_parser = |s: &str| syntax_helper("record", s), required_unless_present = "goal")]
+    pub content: Option<String>,
     /// category of the record
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_category)]
     pub category: Option<String>,
     /// time the record is made,
     /// default to current time
     #[arg(short = 't', long = "time", value_parser = validate_timestr)]
     pub timestr: Option<String>,
+    /// longer note kept apart from the content,
+    /// shown by list show --full
+    #[arg(short, long)]
+    pub note: Option<String>,
+    /// link or ticket the item refers to, kept out of the table,
+    /// shown by list show --full
+    #[arg(long = "ref", value_parser = validate_reference)]
+    pub reference: Option<String>,
+    /// do not print the inserted record
+    #[arg(short, long, default_value_t = false)]
+    pub quiet: bool,
+    /// cut content over max_content_length instead of failing
+    #[arg(long, default_value_t = false)]
+    pub truncate: bool,
+    /// set how many records a day the category aims for, 0 removes the goal,
+    /// progress is shown by list record --today-progress
+    #[arg(long, requires = "category")]
+    pub goal: Option<usize>,
+    /// record even when --time is more than a year ahead, which is refused as a likely typo
+    #[arg(long, default_value_t = false)]
+    pub force: bool,
 }
 
 #[derive(Debug, Args)]
 pub struct DoneCommand {
-    /// index from previous list command;
+    /// index from previous list command, or several like 1-5 or 1,3,5;
     /// or today for interactively completing all tasks due today;
     /// or overdue for interactively completing all tasks overdue;
-    pub target: String,
+    /// may be omitted when --category is given
+    pub target: Option<String>,
     /// optional status, default to done.
     #[arg(short, long, value_parser = parse_status, default_value_t = 1)]
     pub status: u8,
     /// add comment to task content and completion record
     #[arg(short, long)]
     pub comment: Option<String>,
+    /// interactively complete open tasks in this category,
+    /// can be narrowed further with today or overdue
+    #[arg(long, value_parser = parse_category)]
+    pub category: Option<String>,
+    /// complete all matched tasks after a single confirmation,
+    /// instead of prompting per task
+    #[arg(long, default_value_t = false)]
+    pub all: bool,
+    /// complete the single open task whose content contains this text,
+    /// instead of an index from a previous list command
+    #[arg(long = "match", conflicts_with_all = ["target", "category", "all"])]
+    pub pattern: Option<String>,
+    /// complete the open task due earliest, without listing first
+    #[arg(long, conflicts_with_all = ["target", "category", "all", "pattern", "last"])]
+    pub first: bool,
+    /// complete the open task due latest, without listing first
+    #[arg(long, conflicts_with_all = ["target", "category", "all", "pattern"])]
+    pub last: bool,
+    /// after completing, add a new task with the same content and category due at this time,
+    /// e.g. "next friday"
+    #[arg(long, value_parser = validate_timestr, conflicts_with_all = ["category", "all"])]
+    pub followup: Option<String>,
+    /// complete a task that is already closed again, adding another record
+    #[arg(long, default_value_t = false)]
+    pub force: bool,
 }
 
 #[derive(Debug, Args)]
-pub struct DeleteCommand {
+pub struct CancelCommand {
     /// index from previous list command
     #[arg(value_parser = validate_index)]
     pub index: usize,
+    /// why the task was cancelled, kept in the cancellation record
+    #[arg(short, long)]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Args)]
-pub struct UpdateCommand {
+pub struct StartCommand {
     /// index from previous list command
     #[arg(value_parser = validate_index)]
     pub index: usize,
+    /// start a task that is already closed, reopening it
+    #[arg(long, default_value_t = false)]
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PinCommand {
+    /// index from previous list command
+    #[arg(value_parser = validate_index)]
+    pub index: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct RecentCommand {
+    /// amount of items to list, newest first
+    #[arg(default_value_t = 10, value_parser = validate_limit)]
+    pub count: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct DeleteCommand {
+    /// index from previous list command,
+    /// or several like 1-5 or 1,3,5
+    #[arg(value_parser = parse_indices, required_unless_present = "pattern")]
+    pub indices: Option<::std::vec::Vec<usize>>,
+    /// delete the single open task whose content contains this text
+    #[arg(long = "match", conflicts_with = "indices")]
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct UpdateCommand {
+    /// index from previous list command,
+    /// or several like 1-5 or 1,3,5
+    #[arg(value_parser = parse_indices, required_unless_present = "pattern")]
+    pub indices: Option<::std::vec::Vec<usize>>,
+    /// update the single open task whose content contains this text
+    #[arg(long = "match", conflicts_with = "indices")]
+    pub pattern: Option<String>,
     /// update the target time of task,
     /// or event time of record,
     /// or schedule of a recurring task
     #[arg(short, long, value_parser = validate_timestr)]
     pub target_time: Option<String>,
     /// update category of the task/record
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_category)]
     pub category: Option<String>,
     /// replace the content of the task/record
     #[arg(short='w', long)]
@@ -104,10 +277,53 @@ pub struct UpdateCommand {
     /// add to entry content in a newline 
     #[arg(short, long)]
     pub add_content: Option<String>,
+    /// replace the note of the task/record
+    #[arg(short, long)]
+    pub note: Option<String>,
+    /// replace the reference of the task/record
+    #[arg(long = "ref", value_parser = validate_reference)]
+    pub reference: Option<String>,
+    /// change the day you plan to work on the task, the deadline stays
+    #[arg(long = "do", value_parser = validate_timestr)]
+    pub do_date: Option<String>,
+    /// change how long before the deadline ops remind lists the task,
+    /// like 30m, 2h or 24h
+    #[arg(long, value_parser = parse_duration_minutes)]
+    pub remind: Option<i64>,
     /// update status of the tasks,
-    /// accept ongoing|done|cancelled|duplicate|suspended|pending
+    /// accept ongoing|done|cancelled|duplicate|suspended|pending|in_progress
     #[arg(short, long, value_parser = parse_status)]
-    pub status: Option<u8>
+    pub status: Option<u8>,
+    /// cut content over max_content_length instead of failing
+    #[arg(long, default_value_t = false)]
+    pub truncate: bool,
+    /// show the cron and next occurrences of a new recurring schedule,
+    /// and ask for confirmation before saving
+    #[arg(long, default_value_t = false, requires = "target_time")]
+    pub preview: bool,
+    /// turn a task into a recurring task with this schedule, e.g. "Daily 9AM"
+    #[arg(long, value_parser = validate_recurring_timestr, conflicts_with_all = ["target_time", "status", "add_content", "do_date"])]
+    pub make_recurring: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    #[command(subcommand)]
+    pub command: Option<ListCommand>,
+}
+
+// Lets a bare list run a subcommand with the same defaults as typing it out.
+#[derive(Debug, Parser)]
+#[command(name = "list")]
+struct BareList {
+    #[command(subcommand)]
+    command: ListCommand,
+}
+
+pub fn default_list_command(name: &str) -> Result<ListCommand, String> {
+    BareList::try_parse_from(["list", name])
+        .map(|bare| bare.command)
+        .map_err(|e| e.to_string())
 }
 
 #[derive(Debug, Subcommand)]
@@ -116,6 +332,8 @@ pub enum ListCommand {
     Task(ListTaskCommand),
     /// list records
     Record(ListRecordCommand),
+    /// list recently completed tasks
+    Done(ListDoneCommand),
     /// show specific listed item content directly for ease to copy
     Show(ShowContentCommand),
 }
@@ -128,6 +346,40 @@ pub enum OpsCommand {
     Batch(OpsBatchCommand),
     /// backup database to specified path or default location
     Backup(OpsBackupCommand),
+    /// search and replace text across item content
+    Sed(OpsSedCommand),
+    /// summarize tasks and records of the current week or month
+    Review(OpsReviewCommand),
+    /// check a recurring schedule and preview its next occurrences
+    CronCheck(OpsCronCheckCommand),
+    /// export tasks and records to a file
+    Export(OpsExportCommand),
+    /// import tasks and records from a file made by export
+    Import(OpsImportCommand),
+    /// dump every item with all of its fields
+    Dump(OpsDumpCommand),
+    /// load items from a file made by dump
+    Load(OpsLoadCommand),
+    /// apply pending schema migrations and report the schema version
+    Migrate,
+    /// move the deadline of every open overdue task to a new time
+    RescheduleOverdue(OpsRescheduleOverdueCommand),
+    /// move open tasks due yesterday or earlier to the end of today
+    Carryover(OpsCarryoverCommand),
+    /// list open tasks coming due soon, earlier for tasks added with --remind
+    Remind(OpsRemindCommand),
+    /// clear the cached indices of the last listing
+    PurgeCache,
+    /// find items with the same type, category, content and time, and delete extra copies
+    Dedupe(OpsDedupeCommand),
+    /// list tasks completed and records made on a day
+    On(OpsOnCommand),
+    /// rebuild the database file to reclaim space left by deleted items
+    Vacuum,
+    /// list the days without a record of a category, e.g. missed habit days
+    Gaps(OpsGapsCommand),
+    /// walk the items of the last listing and prompt a new category for each
+    Recat,
 }
 
 #[derive(Debug, Args)]
@@ -137,22 +389,59 @@ pub struct ListTaskCommand {
     /// that are marked for completion prior to this time
     pub timestr: Option<String>,
     /// category of the task
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_category)]
     pub category: Option<String>,
     /// days in the future for tasks to list - mutually exclusive with timestr
     #[arg(short, long, conflicts_with = "timestr")]
     pub days: Option<usize>,
+    /// only list tasks due after this time,
+    /// if this is date only, then the whole day is included
+    #[arg(long, value_parser = validate_timestr, conflicts_with_all = ["timestr", "days"])]
+    pub due_after: Option<String>,
+    /// only list tasks due before this time,
+    /// if this is date only, then it is inclusive
+    #[arg(long, value_parser = validate_timestr, conflicts_with_all = ["timestr", "days"])]
+    pub due_before: Option<String>,
+    /// only list tasks due on this day, e.g. friday
+    #[arg(long, value_parser = validate_timestr, conflicts_with_all = ["timestr", "days", "due_after", "due_before", "overdue_only"])]
+    pub due: Option<String>,
+    /// only list tasks planned for this day with --do, e.g. today,
+    /// whatever their deadline
+    #[arg(long = "do", value_parser = validate_timestr, conflicts_with = "overdue_only")]
+    pub do_date: Option<String>,
     /// status to list, default to "open",
-    /// you can filter individually to ongoing|done|cancelled|duplicate|suspended|pending,
+    /// you can filter individually to ongoing|done|cancelled|duplicate|suspended|pending|in_progress,
     /// or aggregate status like open|closed|all
     #[arg(short, long, value_parser = parse_status, default_value_t = 254)]
     pub status: u8,
+    /// leave out tasks with this status, e.g. removed, also aggregates like closed,
+    /// applies on top of --status
+    #[arg(long, value_parser = parse_status)]
+    pub not_status: Option<u8>,
     /// hhow overdue tasks - tasks that are scheduled to be completed in the past,
     /// but were not closed, these tasks are not returned by default
     #[arg(short, long, default_value_t = false)]
     pub overdue: bool,
-    /// limit the amount of tasks returned
-    #[arg(short, long, default_value_t = 100, value_parser = validate_limit)]
+    /// only show overdue tasks, those due before now
+    #[arg(long, default_value_t = false, conflicts_with_all = ["timestr", "days", "due_before"])]
+    pub overdue_only: bool,
+    /// only list tasks without a deadline, recurring tasks are left out
+    #[arg(long, default_value_t = false, conflicts_with_all = ["timestr", "days", "due_after", "due_before", "due", "overdue_only", "next_page", "agenda", "until"])]
+    pub no_deadline: bool,
+    /// also show completed tasks, listed after open ones,
+    /// including those due earlier but completed today
+    #[arg(long, default_value_t = false)]
+    pub include_done: bool,
+    /// show when each closed task was closed, next to its status,
+    /// for tasks listed with --include-done or a closed --status
+    #[arg(long, default_value_t = false, conflicts_with = "agenda")]
+    pub show_closed: bool,
+    /// add a Left column with the time until each open task is due,
+    /// e.g. +3h0m, negative like -2d1h once overdue
+    #[arg(long, default_value_t = false, conflicts_with = "agenda")]
+    pub countdown: bool,
+    /// limit the amount of tasks returned, default from config or 100
+    #[arg(short, long, default_value_t = default_list_limit(), value_parser = validate_limit)]
     pub limit: usize,
     /// next page if the previous list command reached limit
     #[arg(short, long, default_value_t = false)]
@@ -160,23 +449,59 @@ pub struct ListTaskCommand {
     /// search for tasks containing this text in their content
     #[arg(long)]
     pub search: Option<String>,
+    /// only list tasks created this way, cli or import
+    #[arg(long, value_parser = ["cli", "import"])]
+    pub source: Option<String>,
+    /// leave the index cache of the previous list untouched,
+    /// indices shown then do not refer to this listing
+    #[arg(long, default_value_t = false)]
+    pub no_cache: bool,
+    /// total the estimated time of the listed tasks below the list
+    #[arg(long, default_value_t = false)]
+    pub budget: bool,
+    /// show each occurrence of recurring tasks within the window as its own row,
+    /// these rows have no index
+    #[arg(long, default_value_t = false, conflicts_with = "next_page")]
+    pub expand: bool,
+    /// stop listing tasks and projecting recurring tasks after this time, e.g. eom,
+    /// also serves as the window end of --expand and --agenda
+    #[arg(long, value_parser = validate_timestr)]
+    pub until: Option<String>,
+    /// chart the number of open tasks per category instead of listing them
+    #[arg(long, default_value_t = false)]
+    pub histogram: bool,
+    /// list the window day by day under a header per day, recurring tasks
+    /// are expanded like --expand
+    #[arg(long, default_value_t = false, conflicts_with_all = ["next_page", "histogram"])]
+    pub agenda: bool,
+    /// show only the first line of multi-line content, `list show` has the rest
+    #[arg(long, default_value_t = false)]
+    pub titles_only: bool,
+    /// order of the listed tasks: due, or staleness to put overdue tasks first,
+    /// the oldest deadline leading, needs --overdue or --overdue-only
+    #[arg(long, default_value = "due", value_parser = ["due", "staleness"], conflicts_with = "agenda")]
+    pub sort: String,
 }
 
 #[derive(Debug, Args)]
 pub struct ListRecordCommand {
     /// category of the record
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_category)]
     pub category: Option<String>,
     /// days of records to retrieve,
     /// e.g. 1 shows record made in the last 24 hours,
     /// value of 7 would show record made in the past week
     #[arg(short, long, conflicts_with_all = ["starting_time", "ending_time"])]
     pub days: Option<usize>,
-    /// limit the amount of records returned
-    #[arg(short, long, default_value_t = 100, value_parser = validate_limit)]
+    /// count --days from the start of the day instead of exact 24 hour spans,
+    /// e.g. -d 1 includes all of yesterday
+    #[arg(long, default_value_t = false, requires = "days")]
+    pub calendar_days: bool,
+    /// limit the amount of records returned, default from config or 100
+    #[arg(short, long, default_value_t = default_list_limit(), value_parser = validate_limit)]
     pub limit: usize,
-    /// list the record starting from this time,
-    /// if this is date only, then it is non-inclusive
+    /// list the record starting after this time,
+    /// if this is date only, then the whole day is included
     #[arg(short, long, value_parser = validate_timestr, conflicts_with = "days")]
     pub starting_time: Option<String>,
     /// list the record ending at this time,
@@ -189,6 +514,52 @@ pub struct ListRecordCommand {
     /// search for records containing this text in their content
     #[arg(long)]
     pub search: Option<String>,
+    /// only match records whose content is exactly the --search text
+    #[arg(long, default_value_t = false, requires = "search")]
+    pub exact: bool,
+    /// show the time since the previous record in the same category
+    #[arg(short, long, default_value_t = false)]
+    pub gaps: bool,
+    /// count today's records against the daily goal of the category
+    #[arg(long, default_value_t = false, requires = "category", conflicts_with_all = ["days", "starting_time", "ending_time", "next_page", "gaps", "by_day"])]
+    pub today_progress: bool,
+    /// only list records created this way, cli or import
+    #[arg(long, value_parser = ["cli", "import"])]
+    pub source: Option<String>,
+    /// leave the index cache of the previous list untouched,
+    /// indices shown then do not refer to this listing
+    #[arg(long, default_value_t = false)]
+    pub no_cache: bool,
+    /// group records into a section per day, headed by the date and count
+    #[arg(long, default_value_t = false, conflicts_with = "gaps")]
+    pub by_day: bool,
+    /// show only the first line of multi-line content, `list show` has the rest
+    #[arg(long, default_value_t = false)]
+    pub titles_only: bool,
+    /// only list the completions of the recurring task at this index
+    /// from the previous list command
+    #[arg(long, value_parser = validate_index, conflicts_with = "today_progress")]
+    pub recurring: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct ListDoneCommand {
+    /// days of completions to retrieve
+    #[arg(short, long, default_value_t = 7)]
+    pub days: usize,
+    /// count --days from the start of the day instead of exact 24 hour spans
+    #[arg(long, default_value_t = false)]
+    pub calendar_days: bool,
+    /// category of the completed task
+    #[arg(short, long, value_parser = parse_category)]
+    pub category: Option<String>,
+    /// limit the amount of completions returned
+    #[arg(short, long, default_value_t = 100, value_parser = validate_limit)]
+    pub limit: usize,
+    /// leave the index cache of the previous list untouched,
+    /// indices shown then do not refer to this listing
+    #[arg(long, default_value_t = false)]
+    pub no_cache: bool,
 }
 
 #[derive(Debug, Args)]
@@ -196,12 +567,18 @@ pub struct ShowContentCommand {
     /// index from previous list command
     #[arg(value_parser = validate_index)]
     pub index: usize,
+    /// also show id, category, status and times of the item
+    #[arg(short, long, default_value_t = false)]
+    pub full: bool,
+    /// print the whole item as a single JSON object, for piping
+    #[arg(long, default_value_t = false, conflicts_with = "full")]
+    pub json: bool,
 }
 
 #[derive(Debug, Args)]
 pub struct OpsStatCommand {
     /// specify a particular category to stat
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_category)]
     pub category: Option<String>,
     /// Starting time of the statistic operation
     /// if this is date only, then it is non-inclusive
@@ -211,6 +588,15 @@ pub struct OpsStatCommand {
     /// if this is date only, then it is inclusive
     #[arg(short, long, value_parser = validate_timestr)]
     pub ending_time: Option<String>,
+    /// only show the top N categories by total
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+    /// print statistics as JSON instead of a table
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+    /// print only the total number of items matching the filters
+    #[arg(long, default_value_t = false, conflicts_with_all = ["json", "limit"])]
+    pub total_only: bool,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -221,10 +607,10 @@ pub struct OpsBatchCommand {
     #[arg(short, long, default_value_t = String::from("all"))]
     pub action: String,
     /// category of the selection at current time
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_category)]
     pub category: Option<String>,
     /// status of the selection at current time
-    /// accept ongoing|done|cancelled|duplicate|suspended|pending|open|closed|all
+    /// accept ongoing|done|cancelled|duplicate|suspended|pending|in_progress|open|closed|all
     #[arg(long, value_parser = parse_status)]
     pub status: Option<u8>,
     /// Starting time of the current selection
@@ -242,17 +628,43 @@ pub struct OpsBatchCommand {
     #[arg(short, long, default_value_t = false)]
     pub interactive: bool,
     /// modify the current selected items to this category
-    #[arg(short = 't', long)]
+    #[arg(short = 't', long, value_parser = parse_category)]
     pub category_to: Option<String>,
     /// modify the tasks selected to this status
     /// only work with tasks, require concrete status
-    /// accept ongoing|done|cancelled|duplicate|suspended|pending
+    /// accept ongoing|done|cancelled|duplicate|suspended|pending|in_progress
     #[arg(long, value_parser = parse_status)]
     pub status_to: Option<u8>,
     /// bulk update the due date of tasks
     /// only work with action:task
     #[arg(long, value_parser = validate_timestr)]
     pub target_time_to: Option<String>,
+    /// add this tag to the selected items, an item already tagged keeps it once
+    #[arg(long, value_parser = parse_tag)]
+    pub add_tag: Option<String>,
+    /// remove this tag from the selected items
+    #[arg(long, value_parser = parse_tag)]
+    pub remove_tag: Option<String>,
+    /// when more items than this are selected, require typing the count
+    /// instead of y to proceed; overrides confirm_threshold in config
+    #[arg(long)]
+    pub confirm_threshold: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsReviewCommand {
+    /// period to review, week starts on monday
+    #[arg(short, long, default_value = "week", value_parser = ["week", "month"])]
+    pub period: String,
+    /// only review this category
+    #[arg(short, long, value_parser = parse_category)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsCronCheckCommand {
+    /// schedule like "Weekly Monday-Friday 9AM", or a raw cron like "0 9 * * 1-5"
+    pub schedule: String,
 }
 
 #[derive(Debug, Args)]
@@ -263,6 +675,131 @@ pub struct OpsBackupCommand {
     /// if omitted, creates tascli_bak.db in same directory as original.
     #[arg(short, long)]
     pub path: Option<String>,
+    /// print only the absolute path of the backup file
+    #[arg(short, long, default_value_t = false)]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsSedCommand {
+    /// text to search for in item content
+    #[arg(long)]
+    pub from: String,
+    /// text to replace matches with
+    #[arg(long)]
+    pub to: String,
+    /// only replace within items of this category
+    #[arg(short, long, value_parser = parse_category)]
+    pub category: Option<String>,
+    /// type of items to replace content in - all or
+    /// task|record|recurring_task|recurring_task_record
+    #[arg(short, long, default_value_t = String::from("all"))]
+    pub action: String,
+    /// treat --from as a regular expression,
+    /// --to may then reference capture groups like $1
+    #[arg(short, long, default_value_t = false)]
+    pub regex: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsExportCommand {
+    /// output format, jsonl writes one JSON object with every field per line
+    #[arg(long, default_value = "csv", value_parser = ["csv", "jsonl"])]
+    pub format: String,
+    /// file to write to, prints to stdout if omitted
+    #[arg(short, long)]
+    pub file: Option<String>,
+    /// only export items of this category
+    #[arg(short, long, value_parser = parse_category)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsImportCommand {
+    /// input format
+    #[arg(long, default_value = "csv", value_parser = ["csv"])]
+    pub format: String,
+    /// file to read from
+    #[arg(short, long)]
+    pub file: String,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsRescheduleOverdueCommand {
+    /// new deadline for the overdue tasks, default to end of today
+    #[arg(long, default_value = "today", value_parser = validate_timestr)]
+    pub to: String,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsCarryoverCommand {
+    /// append "(carried over)" to the content of moved tasks
+    #[arg(short, long, default_value_t = false)]
+    pub mark: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsRemindCommand {
+    /// how far ahead to look, like 30m, 2h or 24h,
+    /// a task with a --remind lead is listed that much earlier
+    #[arg(short, long, default_value = "24h", value_parser = parse_duration_minutes)]
+    pub within: i64,
+    /// only tasks of this category
+    #[arg(short, long, value_parser = parse_category)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsDedupeCommand {
+    /// only look for duplicates within this category
+    #[arg(short, long, value_parser = parse_category)]
+    pub category: Option<String>,
+    /// delete the duplicates after a backup instead of only listing them
+    #[arg(long, default_value_t = false)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsOnCommand {
+    /// the day to look back on, e.g. yesterday or 2025/03/14
+    #[arg(value_parser = validate_timestr)]
+    pub date: String,
+    /// only items of this category
+    #[arg(short, long, value_parser = parse_category)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsGapsCommand {
+    /// category of the records to look for
+    #[arg(short, long, value_parser = parse_category)]
+    pub category: String,
+    /// calendar days to check, ending with today
+    #[arg(short, long, default_value_t = 30, value_parser = validate_gap_days)]
+    pub days: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsDumpCommand {
+    /// dump as JSON, currently the only format
+    #[arg(long, required = true)]
+    pub json: bool,
+    /// file to write to, prints to stdout if omitted
+    #[arg(short, long)]
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct OpsLoadCommand {
+    /// load from JSON, currently the only format
+    #[arg(long, required = true)]
+    pub json: bool,
+    /// file made by dump
+    pub file: String,
+    /// load into a database that already has items,
+    /// items with the same id are replaced
+    #[arg(long, default_value_t = false)]
+    pub force: bool,
 }
 
 fn syntax_helper(cmd: &str, s: &str) -> Result<String, String> {
@@ -275,15 +812,19 @@ fn syntax_helper(cmd: &str, s: &str) -> Result<String, String> {
     Ok(s.to_string())
 }
 
-fn validate_limit(s: &str) -> Result<usize, String> {
-    let limit: usize = s.parse().map_err(|_| "Must be a number".to_string())?;
-    if limit < 1 {
-        return Err("Limit cannot be less than 1".to_string());
+fn validate_gap_days(s: &str) -> Result<usize, String> {
+    let days: usize = s.parse().map_err(|_| "Days must be a number".to_string())?;
+    if days == 0 {
+        return Err("Days must be greater than 0".to_string());
     }
-    if limit > 65536 {
-        return Err("Limit cannot exceed 65536".to_string());
+    if days > 3660 {
+        return Err("Days cannot exceed 3660".to_string());
     }
-    Ok(limit)
+    Ok(days)
+}
+
+fn default_list_limit() -> usize {
+    get_config().default_list_limit()
 }
 
 fn validate_index(s: &str) -> Result<usize, String> {
@@ -297,6 +838,33 @@ fn validate_index(s: &str) -> Result<usize, String> {
     Ok(index)
 }
 
+// Accepts a single index, a range like 1-5, or a comma list like 1,3,5
+// mixing both. Duplicates are dropped, keeping the first occurrence.
+pub fn parse_indices(s: &str) -> Result<Vec<usize>, String> {
+    let mut indices = Vec::new();
+    let mut seen = HashSet::new();
+    for part in s.split(',') {
+        let part = part.trim();
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (validate_index(start.trim())?, validate_index(end.trim())?),
+            None => {
+                let index = validate_index(part)?;
+                (index, index)
+            }
+        };
+        if start > end {
+            return Err(format!("Invalid range '{}', start is after end", part));
+        }
+        // Kept in the order given, repeats only once
+        for index in start..=end {
+            if seen.insert(index) {
+                indices.push(index);
+            }
+        }
+    }
+    Ok(indices)
+}
+
 fn validate_timestr(s: &str) -> Result<String, String> {
     match parse_flexible_timestr(s) {
         Ok(_) => Ok(s.to_string()),
@@ -309,22 +877,143 @@ fn validate_timestr(s: &str) -> Result<String, String> {
     }
 }
 
+fn validate_recurring_timestr(s: &str) -> Result<String, String> {
+    parse_recurring_timestr(s).map(|_| s.to_string())
+}
+
+fn validate_width(s: &str) -> Result<usize, String> {
+    let width: usize = s.parse().map_err(|_| "Width must be a number".to_string())?;
+    if width < MIN_TABLE_WIDTH {
+        return Err(format!(
+            "Width cannot be less than {}, the table needs room for content",
+            MIN_TABLE_WIDTH
+        ));
+    }
+    Ok(width)
+}
+
+// Only references that look like web links are checked, ticket ids pass as is.
+fn validate_reference(s: &str) -> Result<String, String> {
+    if !s.to_lowercase().starts_with("http") {
+        return Ok(s.to_string());
+    }
+    let host = s
+        .split_once("://")
+        .filter(|(scheme, _)| {
+            scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+        })
+        .map(|(_, rest)| rest.split(['/', '?', '#']).next().unwrap_or(""))
+        .ok_or_else(|| format!("'{}' is not a valid URL, expected http:// or https://", s))?;
+    if host.is_empty() || s.chars().any(char::is_whitespace) {
+        return Err(format!("'{}' is not a valid URL", s));
+    }
+    Ok(s.to_string())
+}
+
+// Normalization is opt in, applied to every category argument.
+pub(crate) fn parse_category(s: &str) -> Result<String, String> {
+    if get_config().normalize_categories {
+        Ok(normalize_category(s))
+    } else {
+        Ok(s.to_string())
+    }
+}
+
+// A tag is one word, commas separate tags in storage.
+fn parse_tag(s: &str) -> Result<String, String> {
+    let tag = s.trim();
+    if tag.is_empty() || tag.contains(',') || tag.contains(char::is_whitespace) {
+        return Err(format!("Invalid tag: '{}', expected one word without commas", s));
+    }
+    Ok(tag.to_string())
+}
+
 fn parse_status(s: &str) -> Result<u8, String> {
-    match s.to_lowercase().as_str() {
-        "ongoing" => Ok(0),
-        "done" | "complete" | "completed" => Ok(1),
-        "cancelled" | "canceled" | "cancel" => Ok(2),
-        "duplicate" => Ok(3),
-        "deferred" | "suspended" | "shelved" => Ok(4),
-        "removed" | "remove" | "unneeded" | "unnecessary" => Ok(5),
-        "pending" => Ok(6),
-        "closed" => Ok(253), // combination of done | cancelled | duplicate | removed
-        "open" => Ok(254), // combination of ongoing | pending | suspended
-        "all" => Ok(255), // all status
-        _ => {
-            s.parse::<u8>().map_err(|_| 
-                format!("Invalid closing code: '{}'. Expected 'completed', 'cancelled', 'duplicate' or a number from 0-255", s)
-            )
-        }
+    parse_status_with(s, get_config())
+}
+
+// Built-in keywords win, aliases from config are only consulted for unknown words.
+fn parse_status_with(s: &str, config: &Config) -> Result<u8, String> {
+    builtin_status(s)
+        .or_else(|| config.status_alias(s))
+        .ok_or_else(|| {
+            format!("Invalid closing code: '{}'. Expected 'completed', 'cancelled', 'duplicate' or a number from 0-255", s)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(parse_status("ongoing"), Ok(0));
+        assert_eq!(parse_status("Done"), Ok(1));
+        assert_eq!(parse_status("pending"), Ok(6));
+        assert_eq!(parse_status("in_progress"), Ok(7));
+        assert_eq!(parse_status("in-progress"), Ok(7));
+        assert_eq!(parse_status("started"), Ok(7));
+        assert_eq!(parse_status("open"), Ok(254));
+        assert_eq!(parse_status("7"), Ok(7));
+        assert!(parse_status("progressing").is_err());
+    }
+
+    #[test]
+    fn test_parse_status_aliases() {
+        let config = Config {
+            status_aliases: [("wontfix", "cancelled"), ("Blocked", "suspended"), ("done", "removed")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(parse_status_with("wontfix", &config), Ok(2));
+        assert_eq!(parse_status_with("BLOCKED", &config), Ok(4));
+        // Built-in keywords cannot be redefined
+        assert_eq!(parse_status_with("done", &config), Ok(1));
+        assert!(parse_status_with("someday", &config).is_err());
+        assert!(parse_status_with("wontfix", &Config::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_indices() {
+        assert_eq!(parse_indices("3"), Ok(vec![3]));
+        assert_eq!(parse_indices("1-5"), Ok(vec![1, 2, 3, 4, 5]));
+        assert_eq!(parse_indices("1,3,5"), Ok(vec![1, 3, 5]));
+        assert_eq!(parse_indices("2-3, 7,3"), Ok(vec![2, 3, 7]));
+        assert_eq!(parse_indices("5,1-3,2"), Ok(vec![5, 1, 2, 3]));
+        assert!(parse_indices("5-1").is_err());
+        assert!(parse_indices("0-2").is_err());
+        assert!(parse_indices("1,,2").is_err());
+        assert!(parse_indices("1-").is_err());
+        assert!(parse_indices("today").is_err());
+    }
+
+    #[test]
+    fn test_normalize_category() {
+        assert_eq!(normalize_category("Home  Chores"), "home chores");
+        assert_eq!(normalize_category("  home\tchores "), "home chores");
+        assert_eq!(normalize_category("HOME CHORES"), "home chores");
+        assert_eq!(normalize_category("work"), "work");
+        assert_eq!(normalize_category("   "), "");
+    }
+
+    #[test]
+    fn test_validate_width() {
+        assert_eq!(validate_width("200"), Ok(200));
+        assert_eq!(validate_width("83"), Ok(83));
+        assert!(validate_width("82").unwrap_err().contains("cannot be less than 83"));
+        assert!(validate_width("wide").is_err());
+    }
+
+    #[test]
+    fn test_validate_reference() {
+        assert_eq!(validate_reference("JIRA-123"), Ok("JIRA-123".to_string()));
+        assert!(validate_reference("https://example.com/issues/42").is_ok());
+        assert!(validate_reference("HTTP://example.com").is_ok());
+        assert!(validate_reference("https://").is_err());
+        assert!(validate_reference("http:/example.com").is_err());
+        assert!(validate_reference("httpx://example.com").is_err());
+        assert!(validate_reference("https://example.com/a b").is_err());
     }
 }
@@ -7,39 +7,133 @@ use chrono::{
     NaiveTime,
     TimeZone,
     Timelike,
+    Utc,
     Weekday,
 };
 
-pub fn days_before_to_unix_epoch(d: usize) -> i64 {
-    let now = Local::now();
-    let past_date = now - Duration::days(d as i64);
-    past_date.timestamp()
+use crate::config::get_config;
+
+// The same wall clock time d days ago in the configured timezone, the system one if unset.
+pub fn days_before_to_unix_epoch(d: usize) -> Result<i64, String> {
+    local_to_unix_epoch(&(now_naive() - Duration::days(d as i64)))
+}
+
+// Start of the day d calendar days ago instead of an exact d * 24h before now.
+pub fn calendar_days_before_to_unix_epoch(d: usize) -> Result<i64, String> {
+    local_to_unix_epoch(&calendar_days_before(now_naive(), d))
+}
+
+fn calendar_days_before(now: NaiveDateTime, d: usize) -> NaiveDateTime {
+    (now.date() - Duration::days(d as i64))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
 }
 
-pub fn days_after_to_unix_epoch(d: usize) -> i64 {
-    let now = Local::now();
-    let future_date = now + Duration::days(d as i64);
-    future_date.timestamp()
+// Same as above, d days ahead.
+pub fn days_after_to_unix_epoch(d: usize) -> Result<i64, String> {
+    local_to_unix_epoch(&(now_naive() + Duration::days(d as i64)))
 }
 
 pub fn to_unix_epoch(s: &str) -> Result<i64, String> {
-    let dt = parse_flexible_timestr(s)?;
-    Local
-        .from_local_datetime(&dt)
+    local_to_unix_epoch(&parse_flexible_timestr(s)?)
+}
+
+// First and last second of the day the timestr falls on.
+pub fn day_bounds_to_unix_epoch(s: &str) -> Result<(i64, i64), String> {
+    date_bounds_to_unix_epoch(parse_flexible_timestr(s)?.date())
+}
+
+pub fn date_bounds_to_unix_epoch(date: NaiveDate) -> Result<(i64, i64), String> {
+    let (start, end) = day_bounds(date);
+    Ok((local_to_unix_epoch(&start)?, local_to_unix_epoch(&end)?))
+}
+
+// Today in the configured timezone, the system one if unset.
+pub fn today() -> NaiveDate {
+    now_naive().date()
+}
+
+// Wall clock time of a timestamp in the configured timezone, the system one if unset.
+pub fn timestamp_to_naive(timestamp: i64) -> Option<NaiveDateTime> {
+    match get_config().timezone() {
+        Some(tz) => tz.timestamp_opt(timestamp, 0).single().map(|dt| dt.naive_local()),
+        None => Local.timestamp_opt(timestamp, 0).single().map(|dt| dt.naive_local()),
+    }
+}
+
+fn day_bounds(date: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+    (
+        date.and_hms_opt(0, 0, 0).unwrap(),
+        date.and_hms_opt(23, 59, 59).unwrap(),
+    )
+}
+
+// Interprets dt in the configured timezone, the system one if unset.
+fn local_to_unix_epoch(dt: &NaiveDateTime) -> Result<i64, String> {
+    match get_config().timezone() {
+        Some(tz) => naive_to_unix_epoch(&tz, dt),
+        None => naive_to_unix_epoch(&Local, dt),
+    }
+}
+
+fn naive_to_unix_epoch<T: TimeZone>(tz: &T, dt: &NaiveDateTime) -> Result<i64, String> {
+    tz.from_local_datetime(dt)
         .earliest()
         .ok_or_else(|| String::from("cannot parse timestr into unix epoch"))
         .map(|dt| dt.timestamp())
 }
 
+// Wall clock time in the configured timezone, relative keywords resolve from it.
+fn now_naive() -> NaiveDateTime {
+    match get_config().timezone() {
+        Some(tz) => Utc::now().with_timezone(&tz).naive_local(),
+        None => Local::now().naive_local(),
+    }
+}
+
+// Lower bound for the exclusive comparison of range filters. A date without
+// a time starts the range at its midnight, so the whole day is included.
+pub fn range_start_to_unix_epoch(s: &str) -> Result<i64, String> {
+    let (dt, date_only) = parse_timestr_with_precision(s)?;
+    if date_only {
+        let (start, _) = day_bounds(dt.date());
+        return Ok(local_to_unix_epoch(&start)? - 1);
+    }
+    local_to_unix_epoch(&dt)
+}
+
 pub fn parse_flexible_timestr(s: &str) -> Result<NaiveDateTime, String> {
+    parse_timestr_with_precision(s).map(|(dt, _)| dt)
+}
+
+// Also tells whether the timestr was only a date, which then defaults to end of day.
+fn parse_timestr_with_precision(s: &str) -> Result<(NaiveDateTime, bool), String> {
     let s = s.trim();
-    let now = Local::now().naive_local();
+
+    // A trailing modifier like "+2h" or "-1d" shifts the computed date/time,
+    // only whole days keep a date a date.
+    if let Some((base, last)) = s.rsplit_once(char::is_whitespace)
+        && let Some(offset) = parse_offset(last)?
+    {
+        let (dt, date_only) = parse_base_timestr(base.trim())?;
+        let dt = dt
+            .checked_add_signed(offset)
+            .ok_or_else(|| format!("Offset '{}' is out of range", last))?;
+        return Ok((dt, date_only && offset.num_seconds() % 86400 == 0));
+    }
+
+    parse_base_timestr(s)
+}
+
+fn parse_base_timestr(s: &str) -> Result<(NaiveDateTime, bool), String> {
+    let now = now_naive();
 
     // Default time when only date is specified (end of day)
     let default_time = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
 
     // Default date when only time is specified (today)
     let default_date = now.date();
+    let week_start = get_config().week_start();
 
     let parts: Vec<&str> = s.split_whitespace().collect();
     if parts.len() > 2 {
@@ -50,27 +144,56 @@ pub fn parse_flexible_timestr(s: &str) -> Result<NaiveDateTime, String> {
     if parts.len() > 1 {
         let potential_date = parts[0];
         let potential_time = parts[1..].join(" ");
-        let date_result = parse_date_portion(potential_date, default_date);
+        let date_result = parse_date_portion(potential_date, default_date, week_start);
         let time_result = parse_time_portion(&potential_time);
         if let (Ok(date), Ok(time)) = (date_result, time_result) {
-            return Ok(date.and_time(time));
+            return Ok((date.and_time(time), false));
         }
     }
 
     // Try to parse the whole string as just a date
-    if let Ok(date) = parse_date_portion(s, default_date) {
-        return Ok(date.and_time(default_time));
+    if let Ok(date) = parse_date_portion(s, default_date, week_start) {
+        return Ok((date.and_time(default_time), true));
     }
 
     // Try to parse the whole string as just a time
     if let Ok(time) = parse_time_portion(s) {
-        return Ok(default_date.and_time(time));
+        return Ok((default_date.and_time(time), false));
     }
 
     Err(format!("Couldn't parse '{}' as a valid date/time", s))
 }
 
-fn parse_date_portion(s: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+// Parse a signed duration such as "+3h", "-1d", "+30m" or "+2w",
+// None when s is not an offset at all.
+fn parse_offset(s: &str) -> Result<Option<Duration>, String> {
+    let sign = match s.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Ok(None),
+    };
+    let Some((unit_idx, unit)) = s.char_indices().last() else {
+        return Ok(None);
+    };
+    if unit_idx < 2 {
+        return Ok(None);
+    }
+    let Ok(amount) = s[1..unit_idx].parse::<i64>() else {
+        return Ok(None);
+    };
+    let duration = match unit.to_ascii_lowercase() {
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => return Ok(None),
+    };
+    duration
+        .map(|d| Some(d * sign))
+        .ok_or_else(|| format!("Offset '{}' is out of range", s))
+}
+
+fn parse_date_portion(s: &str, today: NaiveDate, week_start: Weekday) -> Result<NaiveDate, String> {
     match s.to_lowercase().as_str() {
         "today" | "eod" => return Ok(today),
         "yesterday" => return Ok(today - Duration::days(1)),
@@ -81,12 +204,19 @@ fn parse_date_portion(s: &str, today: NaiveDate) -> Result<NaiveDate, String> {
         "thursday" => return Ok(next_weekday(today, Weekday::Thu)),
         "friday" => return Ok(next_weekday(today, Weekday::Fri)),
         "saturday" => return Ok(next_weekday(today, Weekday::Sat)),
-        "sunday" | "eow" | "week" => return Ok(next_weekday(today, Weekday::Sun)),
+        "sunday" => return Ok(next_weekday(today, Weekday::Sun)),
+        "eow" | "week" => return Ok(next_weekday(today, week_start.pred())),
         "year" | "eoy" => return Ok(today.with_month(12).unwrap().with_day(31).unwrap()),
         "month" | "eom" => return Ok(last_day_of_month(today)),
+        "quarter" | "eoq" => return Ok(last_day_of_quarter(today)),
+        "boq" => return Ok(last_day_of_quarter(today) + Duration::days(1)),
         _ => {}
     }
 
+    if let Some(date) = parse_iso_week(s)? {
+        return Ok(date);
+    }
+
     let full_date_formats = [
         "%Y/%m/%d", // 2025/06/12
         "%Y-%m-%d", // 2025-06-12
@@ -108,18 +238,45 @@ fn parse_date_portion(s: &str, today: NaiveDate) -> Result<NaiveDate, String> {
     // Also accept month/date shorthand like 3/24
     if s.contains('/') {
         let parts: Vec<&str> = s.split('/').collect();
-        if parts.len() == 2 {
-            if let (Ok(month), Ok(day)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-                if let Some(date) = NaiveDate::from_ymd_opt(today.year(), month, day) {
-                    return Ok(date);
-                }
-            }
+        if parts.len() == 2
+            && let (Ok(month), Ok(day)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>())
+            && let Some(date) = NaiveDate::from_ymd_opt(today.year(), month, day)
+        {
+            return Ok(date);
         }
     }
 
     Err(format!("Couldn't parse '{}' as a date", s))
 }
 
+// ISO week dates, 2025-W23 is the Monday of week 23 and 2025-W23-3 its Wednesday.
+// Ok(None) when s is not in that form.
+fn parse_iso_week(s: &str) -> Result<Option<NaiveDate>, String> {
+    let Some((year, rest)) = s.split_once("-W").or_else(|| s.split_once("-w")) else {
+        return Ok(None);
+    };
+    let (week, day) = match rest.split_once('-') {
+        Some((week, day)) => (week, Some(day)),
+        None => (rest, None),
+    };
+    let (Ok(year), Ok(week)) = (year.parse::<i32>(), week.parse::<u32>()) else {
+        return Ok(None);
+    };
+    let weekday = match day.map(str::parse::<u8>) {
+        None => Weekday::Mon,
+        Some(Ok(n @ 1..=7)) => Weekday::try_from(n - 1).unwrap(),
+        Some(_) => return Err(format!("Invalid ISO weekday in '{}', expected 1-7", s)),
+    };
+    match NaiveDate::from_isoywd_opt(year, week, weekday) {
+        Some(date) => Ok(Some(date)),
+        None => {
+            // Dec 28th always falls in the last ISO week of its year
+            let weeks = NaiveDate::from_ymd_opt(year, 12, 28).map_or(52, |d| d.iso_week().week());
+            Err(format!("Invalid ISO week in '{}', {} has weeks 1-{}", s, year, weeks))
+        }
+    }
+}
+
 fn parse_time_portion(s: &str) -> Result<NaiveTime, String> {
     // Try common time formats
     let time_formats = [
@@ -163,6 +320,12 @@ fn last_day_of_month(date: NaiveDate) -> NaiveDate {
     first_of_next - Duration::days(1)
 }
 
+// Quarters follow the calendar: Jan-Mar, Apr-Jun, Jul-Sep, Oct-Dec
+fn last_day_of_quarter(date: NaiveDate) -> NaiveDate {
+    let quarter_end_month = date.month0() / 3 * 3 + 3;
+    last_day_of_month(NaiveDate::from_ymd_opt(date.year(), quarter_end_month, 1).unwrap())
+}
+
 fn next_weekday(from_date: NaiveDate, weekday: Weekday) -> NaiveDate {
     let days_from_today =
         weekday.num_days_from_monday() as i64 - from_date.weekday().num_days_from_monday() as i64;
@@ -240,6 +403,11 @@ pub fn parse_recurring_timestr(s: &str) -> Result<String, String> {
                 return Err(String::from("'Every' requires additional specification"));
             }
 
+            // Check if it's an interval (e.g., "Every 30 minutes", "Every 2 hours")
+            if let (3, Ok(count)) = (parts.len(), parts[1].parse::<u32>()) {
+                return parse_interval(count, parts[2]);
+            }
+
             // Check if it's a time pattern (e.g., "Every 9PM")
             if parse_time_portion(parts[1]).is_ok() {
                 let time = get_time_or_default(&parts, 1)?;
@@ -296,6 +464,37 @@ pub fn parse_recurring_timestr(s: &str) -> Result<String, String> {
     }
 }
 
+// Intervals must divide the hour or the day evenly, so occurrences stay equally spaced.
+fn parse_interval(count: u32, unit: &str) -> Result<String, String> {
+    let (period, cron) = match unit.to_lowercase().as_str() {
+        "minute" | "minutes" | "min" | "mins" => (60, format!("*/{} * * * *", count)),
+        "hour" | "hours" | "hr" | "hrs" => (24, format!("0 */{} * * *", count)),
+        _ => return Err(format!("Invalid interval unit: '{}', expected minutes or hours", unit)),
+    };
+    if count == 0 || count >= period {
+        return Err(format!(
+            "Interval of {} {} must be between 1 and {}",
+            count,
+            unit,
+            period - 1
+        ));
+    }
+    if !period.is_multiple_of(count) {
+        let divisors: Vec<String> = (1..period)
+            .filter(|d| period.is_multiple_of(*d))
+            .map(|d| d.to_string())
+            .collect();
+        return Err(format!(
+            "Interval of {} {} does not divide evenly into {}, expected one of: {}",
+            count,
+            unit,
+            if period == 60 { "an hour" } else { "a day" },
+            divisors.join(", ")
+        ));
+    }
+    Ok(cron)
+}
+
 // Parse weekday names to cron weekday numbers (0=Sunday, 1=Monday, etc.)
 fn parse_weekday(s: &str) -> Result<u8, String> {
     match s.to_lowercase().as_str() {
@@ -347,7 +546,7 @@ fn parse_ordinal_day(s: &str) -> Option<u8> {
             &s
         };
 
-    day_str.parse::<u8>().ok().filter(|&d| d >= 1 && d <= 31)
+    day_str.parse::<u8>().ok().filter(|d| (1..=31).contains(d))
 }
 
 // Parse month/day patterns like "2/14"
@@ -374,9 +573,53 @@ fn parse_month_day(s: &str) -> Result<(u32, u32), String> {
     Ok((month, day))
 }
 
+// Parse durations like "45m", "2h" or "1h30m" into minutes.
+pub fn parse_duration_minutes(s: &str) -> Result<i64, String> {
+    let invalid = || format!("Invalid duration '{}', expected forms like 45m, 2h or 1h30m", s);
+    let lower = s.trim().to_lowercase();
+    let (hours, rest) = match lower.split_once('h') {
+        Some((h, rest)) => (Some(h), rest),
+        None => (None, lower.as_str()),
+    };
+    let minutes = match rest {
+        "" => None,
+        m => Some(m.strip_suffix('m').ok_or_else(invalid)?),
+    };
+    if hours.is_none() && minutes.is_none() {
+        return Err(invalid());
+    }
+    let parse_part = |part: Option<&str>| -> Result<i64, String> {
+        match part {
+            None => Ok(0),
+            Some(p) if !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()) => {
+                p.parse().map_err(|_| format!("Duration '{}' is too large", s))
+            }
+            Some(_) => Err(invalid()),
+        }
+    };
+    let (hours, minutes) = (parse_part(hours)?, parse_part(minutes)?);
+    let total = hours
+        .checked_mul(60)
+        .and_then(|m| m.checked_add(minutes))
+        .ok_or_else(|| format!("Duration '{}' is too large", s))?;
+    if total == 0 {
+        return Err(format!("Duration '{}' must be longer than zero", s));
+    }
+    Ok(total)
+}
+
+// Inverse of parse_duration_minutes, 90 becomes "1h30m".
+pub fn format_duration_minutes(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use chrono::Utc;
+    use chrono_tz::Tz;
 
     use super::*;
 
@@ -397,6 +640,9 @@ mod tests {
             "friday",
             "friday 3PM",
             "3/24",
+            "eoq",
+            "quarter 5PM",
+            "boq",
         ];
 
         for input in valid_inputs {
@@ -417,6 +663,156 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_calendar_days_before() {
+        let early_morning = NaiveDate::from_ymd_opt(2025, 3, 15)
+            .unwrap()
+            .and_hms_opt(1, 0, 0)
+            .unwrap();
+        let yesterday_early = NaiveDate::from_ymd_opt(2025, 3, 14)
+            .unwrap()
+            .and_hms_opt(0, 30, 0)
+            .unwrap();
+        let rolling = early_morning - Duration::days(1);
+        let calendar = calendar_days_before(early_morning, 1);
+        assert_eq!(rolling.to_string(), "2025-03-14 01:00:00");
+        assert_eq!(calendar.to_string(), "2025-03-14 00:00:00");
+        // Early on yesterday is only within the calendar day cutoff
+        assert!(yesterday_early < rolling);
+        assert!(yesterday_early >= calendar);
+
+        assert_eq!(calendar_days_before(early_morning, 0).to_string(), "2025-03-15 00:00:00");
+        assert_eq!(calendar_days_before(early_morning, 7).to_string(), "2025-03-08 00:00:00");
+
+        let cutoff = calendar_days_before_to_unix_epoch(1).unwrap();
+        assert!(cutoff <= days_before_to_unix_epoch(1).unwrap());
+    }
+
+    #[test]
+    fn test_day_bounds() {
+        let date = NaiveDate::from_ymd_opt(2025, 6, 13).unwrap();
+        let (start, end) = day_bounds(date);
+        assert_eq!(start, date.and_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(end, date.and_hms_opt(23, 59, 59).unwrap());
+
+        let (start, end) = day_bounds_to_unix_epoch("2025-06-13 3PM").unwrap();
+        assert_eq!(start, to_unix_epoch("2025-06-13 00:00").unwrap());
+        assert_eq!(end, to_unix_epoch("2025-06-13").unwrap());
+    }
+
+    #[test]
+    fn test_range_start() {
+        // Date only starts just before midnight, so an exclusive bound keeps the whole day
+        let midnight = to_unix_epoch("2025-06-13 00:00").unwrap();
+        assert_eq!(range_start_to_unix_epoch("2025-06-13").unwrap(), midnight - 1);
+        assert_eq!(range_start_to_unix_epoch("2025-06-13 -1d").unwrap(), midnight - 86401);
+        // With a time it is the exact time, as before
+        assert_eq!(
+            range_start_to_unix_epoch("2025-06-13 3PM").unwrap(),
+            to_unix_epoch("2025-06-13 3PM").unwrap()
+        );
+        assert_eq!(
+            range_start_to_unix_epoch("2025-06-13 +2h").unwrap(),
+            to_unix_epoch("2025-06-13").unwrap() + 7200
+        );
+    }
+
+    #[test]
+    fn test_quarter_boundaries() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let cases = [
+            (date(2025, 1, 1), date(2025, 3, 31), date(2025, 4, 1)),
+            (date(2025, 2, 14), date(2025, 3, 31), date(2025, 4, 1)),
+            (date(2025, 3, 31), date(2025, 3, 31), date(2025, 4, 1)),
+            (date(2025, 4, 1), date(2025, 6, 30), date(2025, 7, 1)),
+            (date(2025, 8, 20), date(2025, 9, 30), date(2025, 10, 1)),
+            (date(2025, 10, 1), date(2025, 12, 31), date(2026, 1, 1)),
+            (date(2025, 12, 31), date(2025, 12, 31), date(2026, 1, 1)),
+        ];
+
+        for (today, eoq, boq) in cases {
+            assert_eq!(parse_date_portion("eoq", today, Weekday::Mon).unwrap(), eoq, "eoq from {}", today);
+            assert_eq!(parse_date_portion("quarter", today, Weekday::Mon).unwrap(), eoq);
+            assert_eq!(parse_date_portion("boq", today, Weekday::Mon).unwrap(), boq, "boq from {}", today);
+        }
+    }
+
+    #[test]
+    fn test_iso_week_dates() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 11).unwrap();
+        let parse = |s| parse_date_portion(s, today, Weekday::Mon);
+        // Week 1 of 2025 starts in the previous calendar year
+        assert_eq!(parse("2025-W01").unwrap(), NaiveDate::from_ymd_opt(2024, 12, 30).unwrap());
+        assert_eq!(parse("2025-W23").unwrap(), NaiveDate::from_ymd_opt(2025, 6, 2).unwrap());
+        assert_eq!(parse("2025-W23-3").unwrap(), NaiveDate::from_ymd_opt(2025, 6, 4).unwrap());
+        assert_eq!(parse("2025-w23-7").unwrap(), NaiveDate::from_ymd_opt(2025, 6, 8).unwrap());
+        assert_eq!(parse("2026-W53").unwrap(), NaiveDate::from_ymd_opt(2026, 12, 28).unwrap());
+
+        assert!(parse("2025-W60").unwrap_err().contains("weeks 1-52"));
+        assert!(parse("2025-W53").is_err());
+        assert!(parse("2025-W00").is_err());
+        assert!(parse("2025-W23-8").unwrap_err().contains("weekday"));
+
+        let with_time = parse_flexible_timestr("2025-W23-3 9AM").unwrap();
+        assert_eq!(with_time, NaiveDate::from_ymd_opt(2025, 6, 4).unwrap().and_hms_opt(9, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_eow_week_start() {
+        let date = |d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+        // (today, eow for monday start, eow for sunday start), 2025-06-08 is a sunday
+        let cases = [
+            (date(4), date(8), date(7)),
+            (date(7), date(8), date(14)),
+            (date(8), date(15), date(14)),
+            (date(9), date(15), date(14)),
+        ];
+        for (today, mon_eow, sun_eow) in cases {
+            assert_eq!(parse_date_portion("eow", today, Weekday::Mon).unwrap(), mon_eow, "from {}", today);
+            assert_eq!(parse_date_portion("week", today, Weekday::Mon).unwrap(), mon_eow);
+            assert_eq!(parse_date_portion("eow", today, Weekday::Sun).unwrap(), sun_eow, "from {}", today);
+            assert_eq!(parse_date_portion("week", today, Weekday::Sun).unwrap(), sun_eow);
+            // Named weekdays do not depend on the week start
+            assert_eq!(
+                parse_date_portion("sunday", today, Weekday::Sun).unwrap(),
+                parse_date_portion("sunday", today, Weekday::Mon).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_relative_offsets() {
+        let today = Local::now().date_naive();
+        let eod = today.and_hms_opt(23, 59, 59).unwrap();
+        let friday = next_weekday(today, Weekday::Fri);
+        let at = |date: NaiveDate, h, m| date.and_hms_opt(h, m, 0).unwrap();
+        let cases = [
+            ("today +3h", eod + Duration::hours(3)),
+            ("eod +2h", eod + Duration::hours(2)),
+            ("friday -1h", friday.and_hms_opt(22, 59, 59).unwrap()),
+            ("friday 3PM +30m", at(friday, 15, 30)),
+            ("friday 3PM -1d", at(friday - Duration::days(1), 15, 0)),
+            ("eom -1d", (last_day_of_month(today) - Duration::days(1)).and_time(eod.time())),
+            ("2025-10-15 14:30 +1w", at(NaiveDate::from_ymd_opt(2025, 10, 22).unwrap(), 14, 30)),
+            ("tomorrow 9AM +2H", at(today + Duration::days(1), 11, 0)),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse_flexible_timestr(input).unwrap(), expected, "input '{}'", input);
+        }
+
+        // Modifiers need a base and a known unit
+        for input in ["+2h", "today +2", "today +h", "today +2y", "today 2h", "today +2h +1h"] {
+            assert!(parse_flexible_timestr(input).is_err(), "input '{}'", input);
+        }
+
+        // Too large for a duration, or for a date once applied
+        for input in ["today +99999999999999d", "today +9999999999w", "today -99999999d"] {
+            let err = parse_flexible_timestr(input).unwrap_err();
+            assert!(err.ends_with("is out of range"), "input '{}': {}", input, err);
+        }
+    }
+
     #[test]
     fn test_invalid_inputs() {
         // Collection of inputs that should fail to parse
@@ -463,6 +859,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unix_epoch_in_timezone() {
+        let dt = parse_flexible_timestr("2025-02-23 20:35").unwrap();
+        // Tokyo has no daylight saving, always UTC+9
+        let tokyo = naive_to_unix_epoch(&Tz::Asia__Tokyo, &dt).unwrap();
+        let utc = naive_to_unix_epoch(&Utc, &dt).unwrap();
+        assert_eq!(utc - tokyo, 9 * 3600);
+        assert_eq!(
+            Tz::Asia__Tokyo.timestamp_opt(tokyo, 0).unwrap().naive_local(),
+            dt
+        );
+    }
+
     #[test]
     fn test_recurring_valid_inputs() {
         let test_cases = [
@@ -506,6 +915,12 @@ mod tests {
             // Every - month/day patterns (maps to Yearly)
             ("Every 6/12", "59 23 12 6 *"),
             ("Every 2/14 5PM", "0 17 14 2 *"),
+            // Every - intervals
+            ("Every 30 minutes", "*/30 * * * *"),
+            ("Every 15 mins", "*/15 * * * *"),
+            ("Every 2 hours", "0 */2 * * *"),
+            ("Every 1 hour", "0 */1 * * *"),
+            ("Every 12 Hours", "0 */12 * * *"),
         ];
 
         for (input, expected) in test_cases {
@@ -544,5 +959,37 @@ mod tests {
         assert!(parse_recurring_timestr("Every InvalidDay").is_err());
         assert!(parse_recurring_timestr("Every 32nd of the Month").is_err());
         assert!(parse_recurring_timestr("Every 2/30").is_err()); // Invalid date
+        assert!(parse_recurring_timestr("Every 0 minutes").is_err());
+        assert!(parse_recurring_timestr("Every 60 minutes").is_err());
+        assert!(parse_recurring_timestr("Every 24 hours").is_err());
+        assert!(parse_recurring_timestr("Every 2 days").is_err());
+        let err = parse_recurring_timestr("Every 7 hours").unwrap_err();
+        assert!(err.contains("does not divide evenly into a day"), "{}", err);
+        assert!(err.contains("1, 2, 3, 4, 6, 8, 12"), "{}", err);
+    }
+
+    #[test]
+    fn test_duration_minutes() {
+        assert_eq!(parse_duration_minutes("45m").unwrap(), 45);
+        assert_eq!(parse_duration_minutes("2h").unwrap(), 120);
+        assert_eq!(parse_duration_minutes("1h30m").unwrap(), 90);
+        assert_eq!(parse_duration_minutes("1H30M").unwrap(), 90);
+        assert!(parse_duration_minutes("45").is_err());
+        assert!(parse_duration_minutes("m").is_err());
+        assert!(parse_duration_minutes("30m1h").is_err());
+        assert!(parse_duration_minutes("1h1h").is_err());
+        assert!(parse_duration_minutes("0m").is_err());
+        assert!(parse_duration_minutes("1.5h").is_err());
+        let too_large = format!("{}h", i64::MAX / 30);
+        assert_eq!(
+            parse_duration_minutes(&too_large).unwrap_err(),
+            format!("Duration '{}' is too large", too_large)
+        );
+        let too_large = format!("{}h{}m", i64::MAX / 60, i64::MAX);
+        assert!(parse_duration_minutes(&too_large).unwrap_err().ends_with("is too large"));
+
+        assert_eq!(format_duration_minutes(45), "45m");
+        assert_eq!(format_duration_minutes(120), "2h");
+        assert_eq!(format_duration_minutes(90), "1h30m");
     }
 }
@@ -1,19 +1,211 @@
 use std::{
+    collections::HashMap,
     fs,
     path::PathBuf,
+    sync::OnceLock,
 };
 
-use nanoserde::DeJson;
+use chrono::Weekday;
+use chrono_tz::Tz;
+use nanoserde::{
+    DeJson,
+    DeJsonErr,
+    DeJsonState,
+    DeJsonTok,
+};
+
+use crate::{
+    actions::display::DateFormat,
+    db::item::{
+        builtin_status,
+        validate_limit,
+    },
+};
 
 const DB_NAME: &str = "tascli.db";
 const DEFAULT_DATA_DIR: &[&str] = &[".local", "share", "tascli"];
 const CONFIG_PATH: &[&str] = &[".config", "tascli", "config.json"];
+const DEFAULT_MAX_CONTENT_LENGTH: usize = 10000;
+const DEFAULT_LIST_LIMIT: usize = 100;
+const CONFIG_FIELDS: &[&str] = &[
+    "data_dir",
+    "category_defaults",
+    "date_format",
+    "normalize_categories",
+    "week_start",
+    "timezone",
+    "max_content_length",
+    "category_colors",
+    "confirm_threshold",
+    "status_aliases",
+    "overdue_grace_minutes",
+    "default_list_limit",
+    "default_list",
+    "status_labels",
+];
+// ANSI foreground codes, bright variants are the base code plus 60.
+const COLOR_NAMES: &[(&str, u8)] = &[
+    ("black", 30),
+    ("red", 31),
+    ("green", 32),
+    ("yellow", 33),
+    ("blue", 34),
+    ("magenta", 35),
+    ("cyan", 36),
+    ("white", 37),
+];
 
-#[derive(Default, DeJson)]
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Default, DeJson)]
 pub struct Config {
     /// Only supports full path.
     #[nserde(default)]
     pub data_dir: String,
+    /// Category to timestr, used when adding without an explicit time.
+    #[nserde(default)]
+    pub category_defaults: HashMap<String, String>,
+    /// human, iso or a strftime format, overridden by --date-format.
+    #[nserde(default)]
+    pub date_format: String,
+    /// Trim, lowercase and collapse whitespace of categories on input.
+    #[nserde(default)]
+    pub normalize_categories: bool,
+    /// mon or sun, the day a week starts on, default to mon.
+    #[nserde(default)]
+    pub week_start: String,
+    /// IANA name like "America/New_York", used instead of the system timezone.
+    #[nserde(default)]
+    pub timezone: String,
+    /// Characters allowed in content, 0 or unset uses the default of 10000.
+    #[nserde(default)]
+    pub max_content_length: usize,
+    /// Category to color name like "blue" or "bright_red", used for the category cell.
+    #[nserde(default)]
+    pub category_colors: HashMap<String, String>,
+    /// Batch operations on more items than this ask for the count instead of y, 0 or unset disables.
+    #[nserde(default)]
+    pub confirm_threshold: usize,
+    /// Custom word to a built-in status like "cancelled", e.g. "wontfix".
+    #[nserde(default)]
+    pub status_aliases: HashMap<String, String>,
+    /// Minutes past its due time before a task counts as overdue, default to 0.
+    #[nserde(default)]
+    pub overdue_grace_minutes: u32,
+    /// Limit of list task and list record when -l is omitted, 0 or unset uses 100.
+    #[nserde(default)]
+    pub default_list_limit: usize,
+    /// task, record or done, what list alone lists, default to task.
+    #[nserde(default)]
+    pub default_list: String,
+    /// Built-in status like "completed" or its code to the label shown for it, e.g. "✓".
+    #[nserde(default)]
+    pub status_labels: HashMap<String, String>,
+}
+
+// Read on its own when other fields fail, so the database is still found.
+#[derive(Default, DeJson)]
+struct DataDirConfig {
+    #[nserde(default)]
+    data_dir: String,
+}
+
+impl Config {
+    pub fn week_start(&self) -> Weekday {
+        if self.week_start.eq_ignore_ascii_case("sun") {
+            Weekday::Sun
+        } else {
+            Weekday::Mon
+        }
+    }
+
+    // Unset or unknown names fall back to the system timezone.
+    pub fn timezone(&self) -> Option<Tz> {
+        self.timezone.parse().ok()
+    }
+
+    // ANSI code of the color for the category, None if unmapped.
+    pub fn category_color(&self, category: &str) -> Option<u8> {
+        self.category_colors
+            .get(category)
+            .and_then(|name| color_code(name))
+    }
+
+    pub fn max_content_length(&self) -> usize {
+        if self.max_content_length == 0 {
+            DEFAULT_MAX_CONTENT_LENGTH
+        } else {
+            self.max_content_length
+        }
+    }
+
+    pub fn default_list_limit(&self) -> usize {
+        if self.default_list_limit == 0 {
+            DEFAULT_LIST_LIMIT
+        } else {
+            self.default_list_limit
+        }
+    }
+
+    pub fn default_list(&self) -> &str {
+        if self.default_list.is_empty() {
+            "task"
+        } else {
+            &self.default_list
+        }
+    }
+
+    // Code of the status an alias stands for, aliases match case-insensitively.
+    pub fn status_alias(&self, word: &str) -> Option<u8> {
+        self.status_aliases
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(word))
+            .and_then(|(_, status)| builtin_status(status))
+    }
+
+    // Label configured for a status code, display only, parsing keeps the built-in names.
+    pub fn status_label(&self, status: u8) -> Option<&str> {
+        self.status_labels
+            .iter()
+            .find(|(name, _)| builtin_status(name) == Some(status))
+            .map(|(_, label)| label.as_str())
+    }
+
+    pub fn confirm_threshold(&self) -> Option<usize> {
+        (self.confirm_threshold > 0).then_some(self.confirm_threshold)
+    }
+
+    // Tasks due at or before the returned time are overdue.
+    pub fn overdue_cutoff(&self, now: i64) -> i64 {
+        now - i64::from(self.overdue_grace_minutes) * 60
+    }
+}
+
+// Read once per process, so an invalid config only warns once.
+pub fn get_config() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        // Unit tests run on the defaults, not the config of whoever runs them.
+        if cfg!(test) {
+            return Config::default();
+        }
+        config_path(std::env::var("TASCLI_CONFIG").ok(), home::home_dir())
+            .and_then(load_config)
+            .unwrap_or_default()
+    })
+}
+
+// TASCLI_CONFIG wins when it names an existing file, otherwise the default location is used.
+fn config_path(env_path: Option<String>, home_dir: Option<PathBuf>) -> Option<PathBuf> {
+    if let Some(path) = env_path.filter(|p| !p.is_empty()).map(PathBuf::from) {
+        if path.is_file() {
+            return Some(path);
+        }
+        eprintln!(
+            "Warning: TASCLI_CONFIG is set to {}, which is not a file; using the default config location",
+            path.display()
+        );
+    }
+    home_dir.map(|home| CONFIG_PATH.iter().fold(home, |p, d| p.join(d)))
 }
 
 pub fn get_data_path() -> Result<PathBuf, String> {
@@ -23,34 +215,190 @@ pub fn get_data_path() -> Result<PathBuf, String> {
     }
 
     let home_dir = home::home_dir().ok_or_else(|| String::from("cannot find home directory"))?;
-    let data_dir = match get_config_data_dir(home_dir.clone()) {
-        Some(dir_path) => str_to_pathbuf(dir_path)?,
-        None => DEFAULT_DATA_DIR.iter().fold(home_dir, |p, d| p.join(d)),
+    let config = get_config();
+    let data_dir = if config.data_dir.is_empty() {
+        DEFAULT_DATA_DIR.iter().fold(home_dir, |p, d| p.join(d))
+    } else {
+        str_to_pathbuf(config.data_dir.clone())?
     };
     fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
     Ok(data_dir.join(DB_NAME))
 }
 
-// Quick passthrough for reading config file
-// If config file do not exist, return quickly
-fn get_config_data_dir(home_dir: PathBuf) -> Option<String> {
-    let config_path = CONFIG_PATH.iter().fold(home_dir, |p, d| p.join(d));
+// A missing config file is normal and falls back quietly,
+// problems are reported on stderr, an unreadable one falls back to defaults.
+fn load_config(config_path: PathBuf) -> Option<Config> {
     if !config_path.exists() {
         return None;
     }
-    let config_content = match fs::read_to_string(&config_path) {
-        Ok(content) => content,
-        Err(_) => return None,
-    };
-    let config: Config = match DeJson::deserialize_json(&config_content) {
+    let result = fs::read_to_string(&config_path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| parse_config(&content));
+    match result {
+        Ok((config, warnings)) => {
+            for warning in warnings {
+                eprintln!("Warning: config at {}, {}", config_path.display(), warning);
+            }
+            Some(config)
+        }
+        Err(e) => {
+            eprintln!(
+                "Warning: ignoring config at {}, {}; using defaults",
+                config_path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+// Only JSON that cannot be read fails, other problems come back as warnings
+// next to a config that keeps whatever is usable.
+fn parse_config(content: &str) -> Result<(Config, Vec<String>), String> {
+    let fields = scan_fields(content).map_err(|e| format!("malformed JSON: {}", e))?;
+    let mut warnings: Vec<String> = fields
+        .iter()
+        .filter(|(name, _)| !CONFIG_FIELDS.contains(&name.as_str()))
+        .map(|(name, _)| {
+            format!(
+                "unknown field '{}' is ignored, expected one of: {}",
+                name,
+                CONFIG_FIELDS.join(", ")
+            )
+        })
+        .collect();
+    let mut config = match Config::deserialize_json(content) {
         Ok(config) => config,
-        Err(_) => return None,
+        Err(e) => {
+            // Attribute the error to the last field starting before it.
+            let error = match fields.iter().rev().find(|(_, pos)| *pos <= (e.line, e.col)) {
+                Some((name, _)) => format!("invalid value for field '{}': {}", name, e),
+                None => e.to_string(),
+            };
+            warnings.push(format!("{}; using defaults for every field but data_dir", error));
+            let data_dir = DataDirConfig::deserialize_json(content)
+                .map(|c| c.data_dir)
+                .unwrap_or_default();
+            Config {
+                data_dir,
+                ..Config::default()
+            }
+        }
     };
-    if config.data_dir.is_empty() {
-        None
-    } else {
-        Some(config.data_dir)
+    // An invalid field is reset on its own, the other fields still apply
+    if !["", "mon", "sun"].contains(&config.week_start.to_lowercase().as_str()) {
+        warnings.push(format!(
+            "invalid value for field 'week_start': '{}', expected mon or sun; using mon",
+            config.week_start
+        ));
+        config.week_start = String::new();
+    }
+    if !config.date_format.is_empty() && DateFormat::parse(&config.date_format).is_err() {
+        warnings.push(format!(
+            "invalid value for field 'date_format': '{}', expected human, iso or a strftime format like '%Y-%m-%d %H:%M'; using human",
+            config.date_format
+        ));
+        config.date_format = String::new();
+    }
+    if !["", "task", "record", "done"].contains(&config.default_list.as_str()) {
+        warnings.push(format!(
+            "invalid value for field 'default_list': '{}', expected task, record or done; using task",
+            config.default_list
+        ));
+        config.default_list = String::new();
+    }
+    if let Some((category, name)) = config
+        .category_colors
+        .iter()
+        .find(|(_, name)| color_code(name).is_none())
+    {
+        warnings.push(format!(
+            "invalid value for field 'category_colors': '{}' for '{}', expected one of: {}, optionally prefixed with bright_; ignoring category_colors",
+            name,
+            category,
+            COLOR_NAMES.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ")
+        ));
+        config.category_colors.clear();
+    }
+    if let Some((alias, status)) = config
+        .status_aliases
+        .iter()
+        .find(|(_, status)| builtin_status(status).is_none_or(|code| code >= 240))
+    {
+        warnings.push(format!(
+            "invalid value for field 'status_aliases': '{}' for '{}', expected a built-in status like cancelled or suspended; ignoring status_aliases",
+            status, alias
+        ));
+        config.status_aliases.clear();
+    }
+    // Aggregates like open have no label of their own
+    if let Some(name) = config
+        .status_labels
+        .keys()
+        .find(|name| builtin_status(name).is_none_or(|code| code >= 240))
+    {
+        warnings.push(format!(
+            "invalid value for field 'status_labels': '{}' is not a status like completed or pending; ignoring status_labels",
+            name
+        ));
+        config.status_labels.clear();
+    }
+    if config.default_list_limit > 0
+        && let Err(e) = validate_limit(&config.default_list_limit.to_string())
+    {
+        warnings.push(format!("invalid value for field 'default_list_limit': {}; using 100", e));
+        config.default_list_limit = 0;
+    }
+    Ok((config, warnings))
+}
+
+fn color_code(name: &str) -> Option<u8> {
+    let name = name.to_lowercase();
+    let (base, offset) = match name.strip_prefix("bright_") {
+        Some(base) => (base, 60),
+        None => (name.as_str(), 0),
+    };
+    COLOR_NAMES
+        .iter()
+        .find(|(n, _)| *n == base)
+        .map(|(_, code)| code + offset)
+}
+
+// Key name and the (line, col) of its value.
+type FieldPos = (String, (usize, usize));
+
+// Walks the top level object, returning each key with the position of its value.
+fn scan_fields(content: &str) -> Result<Vec<FieldPos>, DeJsonErr> {
+    let mut state = DeJsonState::default();
+    let mut chars = content.chars();
+    state.next(&mut chars);
+    state.next_tok(&mut chars)?;
+    state.curly_open(&mut chars)?;
+
+    let mut fields = Vec::new();
+    while state.tok != DeJsonTok::CurlyClose {
+        let name = state.strbuf.clone();
+        state.string(&mut chars)?;
+        state.colon(&mut chars)?;
+        fields.push((name, (state.line, state.col)));
+        let is_value = matches!(
+            state.tok,
+            DeJsonTok::Str
+                | DeJsonTok::U64(_)
+                | DeJsonTok::I64(_)
+                | DeJsonTok::F64(_)
+                | DeJsonTok::Bool(_)
+                | DeJsonTok::Null
+                | DeJsonTok::CurlyOpen
+                | DeJsonTok::BlockOpen
+        );
+        if !is_value {
+            return Err(state.err_token("a value"));
+        }
+        state.whole_field(&mut chars)?;
+        state.eat_comma_curly(&mut chars)?;
     }
+    Ok(fields)
 }
 
 pub fn str_to_pathbuf(dir_path: String) -> Result<PathBuf, String> {
@@ -83,4 +431,196 @@ mod tests {
         let err = str_to_pathbuf("relative/path".to_string()).unwrap_err();
         assert!(err.contains("path must be absolute or home relative"));
     }
+
+    fn parse(content: &str) -> Config {
+        parse_config(content).unwrap().0
+    }
+
+    fn parse_warnings(content: &str) -> Vec<String> {
+        parse_config(content).unwrap().1
+    }
+
+    fn default_path(home: &std::path::Path) -> PathBuf {
+        CONFIG_PATH.iter().fold(home.to_path_buf(), |p, d| p.join(d))
+    }
+
+    fn write_config(home: &std::path::Path, content: &str) {
+        let config_path = default_path(home);
+        fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        fs::write(config_path, content).unwrap();
+    }
+
+    #[test]
+    fn test_config_path_env_override() {
+        let home = tempfile::tempdir().unwrap();
+        let home_dir = Some(home.path().to_path_buf());
+        assert_eq!(config_path(None, home_dir.clone()), Some(default_path(home.path())));
+
+        let custom = home.path().join("tascli.json");
+        fs::write(&custom, r#"{"week_start": "sun"}"#).unwrap();
+        let env_path = Some(custom.to_str().unwrap().to_string());
+        assert_eq!(config_path(env_path.clone(), home_dir.clone()), Some(custom.clone()));
+        // Taken even without a home directory
+        assert_eq!(config_path(env_path, None), Some(custom.clone()));
+        let config = load_config(custom).unwrap();
+        assert_eq!(config.week_start(), Weekday::Sun);
+
+        // A missing or empty path falls back to the default location
+        let missing = Some(home.path().join("missing.json").to_str().unwrap().to_string());
+        assert_eq!(config_path(missing, home_dir.clone()), Some(default_path(home.path())));
+        assert_eq!(config_path(Some(String::new()), home_dir), Some(default_path(home.path())));
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let config = parse(r#"{"data_dir": "/var/tascli"}"#);
+        assert_eq!(config.data_dir, "/var/tascli");
+        let config = parse("{}");
+        assert!(config.data_dir.is_empty());
+
+        let home = tempfile::tempdir().unwrap();
+        assert!(load_config(default_path(home.path())).is_none());
+        write_config(home.path(), r#"{"data_dir": "~/notes"}"#);
+        let config = load_config(default_path(home.path())).unwrap();
+        assert_eq!(config.data_dir, "~/notes");
+        assert!(config.category_defaults.is_empty());
+
+        let config =
+            parse(r#"{"category_defaults": {"gym": "today 6PM", "feeding": "now"}}"#);
+        assert_eq!(config.category_defaults.len(), 2);
+        assert_eq!(config.category_defaults["gym"], "today 6PM");
+
+        assert_eq!(parse("{}").week_start(), Weekday::Mon);
+        let config = parse(r#"{"week_start": "sun"}"#);
+        assert_eq!(config.week_start(), Weekday::Sun);
+
+        assert!(parse("{}").timezone().is_none());
+        let config = parse(r#"{"timezone": "Asia/Tokyo"}"#);
+        assert_eq!(config.timezone(), Some(Tz::Asia__Tokyo));
+        let config = parse(r#"{"timezone": "Mars/Olympus"}"#);
+        assert!(config.timezone().is_none());
+
+        assert_eq!(parse("{}").max_content_length(), 10000);
+
+        assert_eq!(parse("{}").default_list(), "task");
+        let config = parse(r#"{"default_list": "record"}"#);
+        assert_eq!(config.default_list(), "record");
+        let config = parse(r#"{"max_content_length": 500}"#);
+        assert_eq!(config.max_content_length(), 500);
+
+        let config = parse(r#"{"status_aliases": {"wontfix": "cancelled"}}"#);
+        assert_eq!(config.status_alias("WontFix"), Some(2));
+        assert_eq!(config.status_alias("blocked"), None);
+
+        let config = parse(r#"{"status_labels": {"done": "✓", "6": "waiting"}}"#);
+        assert_eq!(config.status_label(1), Some("✓"));
+        assert_eq!(config.status_label(6), Some("waiting"));
+        assert_eq!(config.status_label(2), None);
+
+        assert_eq!(parse("{}").confirm_threshold(), None);
+        let config = parse(r#"{"confirm_threshold": 20}"#);
+        assert_eq!(config.confirm_threshold(), Some(20));
+
+        assert_eq!(parse("{}").overdue_cutoff(1000), 1000);
+        let config = parse(r#"{"overdue_grace_minutes": 5}"#);
+        assert_eq!(config.overdue_cutoff(1000), 700);
+        assert_eq!(parse(r#"{"overdue_grace_minutes": -5}"#).overdue_cutoff(1000), 1000);
+
+        assert_eq!(parse("{}").default_list_limit(), 100);
+        let config = parse(r#"{"default_list_limit": 20}"#);
+        assert_eq!(config.default_list_limit(), 20);
+        let (config, warnings) =
+            parse_config(r#"{"default_list_limit": 70000, "default_list": "done"}"#).unwrap();
+        assert!(warnings[0].contains("Limit cannot exceed 65536"), "{:?}", warnings);
+        assert_eq!(config.default_list_limit(), 100);
+        assert_eq!(config.default_list(), "done");
+
+        let config =
+            parse(r#"{"category_colors": {"work": "blue", "home": "Bright_Green"}}"#);
+        assert_eq!(config.category_color("work"), Some(34));
+        assert_eq!(config.category_color("home"), Some(92));
+        assert_eq!(config.category_color("gym"), None);
+    }
+
+    #[test]
+    fn test_malformed_config() {
+        let warnings = parse_warnings(r#"{"data_dirr": "/var/tascli"}"#);
+        assert!(warnings[0].contains("unknown field 'data_dirr'"), "{:?}", warnings);
+
+        let (config, warnings) = parse_config(r#"{"data_dir": 5}"#).unwrap();
+        assert!(
+            warnings[0].contains("invalid value for field 'data_dir'"),
+            "{:?}",
+            warnings
+        );
+        assert!(config.data_dir.is_empty());
+
+        // Other fields going wrong keep the data_dir, so the same database is used
+        let (config, warnings) = parse_config(r#"{"data_dir": "/x", "bogus": 1}"#).unwrap();
+        assert_eq!(config.data_dir, "/x");
+        assert_eq!(warnings.len(), 1);
+        let (config, warnings) =
+            parse_config(r#"{"data_dir": "/x", "week_start": "sun", "max_content_length": "long"}"#)
+                .unwrap();
+        assert_eq!(config.data_dir, "/x");
+        assert_eq!(config.week_start(), Weekday::Mon);
+        assert!(warnings[0].contains("'max_content_length'"), "{:?}", warnings);
+
+        let err = parse_config(r#"{"data_dir": "/var/tascli""#).unwrap_err();
+        assert!(err.contains("malformed JSON"), "{}", err);
+
+        let err = parse_config(r#"{"data_dir": nowhere}"#).unwrap_err();
+        assert!(err.contains("malformed JSON"), "{}", err);
+
+        // Startup stays resilient, falling back to defaults
+        let home = tempfile::tempdir().unwrap();
+        write_config(home.path(), r#"{"data_dir": "/var/tascli""#);
+        assert!(load_config(default_path(home.path())).is_none());
+        write_config(home.path(), r#"{"data_dir": "/x", "bogus": 1}"#);
+        assert_eq!(load_config(default_path(home.path())).unwrap().data_dir, "/x");
+
+        let (config, warnings) =
+            parse_config(r#"{"week_start": "tue", "default_list": "record"}"#).unwrap();
+        assert!(warnings[0].contains("invalid value for field 'week_start'"), "{:?}", warnings);
+        assert_eq!(config.week_start(), Weekday::Mon);
+        assert_eq!(config.default_list(), "record");
+
+        let (config, warnings) =
+            parse_config(r#"{"default_list": "show", "week_start": "sun"}"#).unwrap();
+        assert!(warnings[0].contains("invalid value for field 'default_list'"), "{:?}", warnings);
+        assert_eq!(config.default_list(), "task");
+        assert_eq!(config.week_start(), Weekday::Sun);
+
+        let (config, warnings) =
+            parse_config(r#"{"date_format": "yyyy-mm-dd", "week_start": "sun"}"#).unwrap();
+        assert!(warnings[0].contains("invalid value for field 'date_format'"), "{:?}", warnings);
+        assert!(config.date_format.is_empty());
+        assert_eq!(config.week_start(), Weekday::Sun);
+        assert!(parse_warnings(r#"{"date_format": "%d/%m/%Y"}"#).is_empty());
+
+        let (config, warnings) =
+            parse_config(r#"{"category_colors": {"work": "teal"}, "default_list": "record"}"#).unwrap();
+        assert!(warnings[0].contains("invalid value for field 'category_colors'"), "{:?}", warnings);
+        assert!(config.category_colors.is_empty());
+        assert_eq!(config.default_list(), "record");
+
+        for aliases in [r#"{"status_aliases": {"wontfix": "ignored"}}"#, r#"{"status_aliases": {"live": "open"}}"#] {
+            let (config, warnings) = parse_config(aliases).unwrap();
+            assert!(warnings[0].contains("invalid value for field 'status_aliases'"), "{:?}", warnings);
+            assert!(config.status_aliases.is_empty());
+        }
+
+        for labels in [r#"{"status_labels": {"finished": "✓"}}"#, r#"{"status_labels": {"open": "o"}}"#] {
+            let (config, warnings) = parse_config(labels).unwrap();
+            assert!(warnings[0].contains("invalid value for field 'status_labels'"), "{:?}", warnings);
+            assert!(config.status_labels.is_empty());
+        }
+
+        let warnings = parse_warnings(r#"{"category_defaults": {"gym": 18}}"#);
+        assert!(
+            warnings[0].contains("invalid value for field 'category_defaults'"),
+            "{:?}",
+            warnings
+        );
+    }
 }
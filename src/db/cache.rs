@@ -6,10 +6,12 @@ use std::time::{
 use rusqlite::{
     params,
     Connection,
-    Result,
 };
 
-use crate::db::item::Item;
+use crate::{
+    db::item::Item,
+    error::Result,
+};
 
 pub fn store(conn: &Connection, items: &[Item]) -> Result<()> {
     let current_time = SystemTime::now()
@@ -44,7 +46,13 @@ pub fn store_with_next(conn: &Connection, items: &[Item]) -> Result<()> {
 pub fn validate_cache(conn: &Connection) -> Result<bool> {
     let timestamp = match read(conn, 0)? {
         Some(t) => t,
-        None => return Ok(false),
+        None => {
+            // Entries without the timestamp cannot be trusted, drop them.
+            if count_entries(conn)? > 0 {
+                clear(conn)?;
+            }
+            return Ok(false);
+        }
     };
     let current_time = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -81,14 +89,27 @@ pub fn read(conn: &Connection, index: i64) -> Result<Option<i64>> {
     match result {
         Ok(value) => Ok(Some(value)),
         Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(err) => Err(err),
+        Err(err) => Err(err.into()),
     }
 }
 
+// Item ids of every cached index, in index order.
+pub fn read_all(conn: &Connection) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare("SELECT value FROM cache WHERE key > 0 ORDER BY key")?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<i64>>>()?;
+    Ok(ids)
+}
+
 pub fn get_next_index(conn: &Connection) -> Result<Option<i64>> {
     read(conn, -1)
 }
 
+pub fn count_entries(conn: &Connection) -> Result<i64> {
+    Ok(conn.query_row("SELECT COUNT(*) FROM cache", [], |row| row.get(0))?)
+}
+
 pub fn clear(conn: &Connection) -> Result<()> {
     conn.execute("DELETE FROM cache", [])?;
     Ok(())
@@ -113,6 +134,8 @@ mod tests {
         assert_eq!(val, Some(8));
         let val = read(&conn, 4).expect("Error reading key value");
         assert_eq!(val, None);
+        store_kv(&conn, vec![(0, 100), (-1, 3)]).unwrap();
+        assert_eq!(read_all(&conn).unwrap(), vec![3, 9, 8]);
 
         clear(&conn).unwrap();
         let val = read(&conn, 1).expect("Error reading key value");
@@ -154,6 +177,16 @@ mod tests {
         assert!(!valid);
     }
 
+    #[test]
+    fn test_validate_cache_malformed() {
+        // Indices left behind without the timestamp at key 0
+        let (conn, _temp_file) = get_test_conn();
+        store_kv(&conn, vec![(1, 3), (2, 9)]).expect("Failed to store indices");
+        let valid = validate_cache(&conn).expect("Failed to validate cache");
+        assert!(!valid);
+        assert_eq!(count_entries(&conn).unwrap(), 0);
+    }
+
     #[test]
     fn test_store() {
         let (conn, _temp_file) = get_test_conn();
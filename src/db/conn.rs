@@ -1,13 +1,45 @@
-use rusqlite::Connection;
+use std::{
+    path::Path,
+    sync::{
+        Arc,
+        OnceLock,
+    },
+    time::Duration,
+};
 
-use crate::config::get_data_path;
+use regex::Regex;
+use rusqlite::{
+    functions::FunctionFlags,
+    Connection,
+    Error,
+};
+
+use crate::{
+    config::get_data_path,
+    db::item::normalize_category,
+};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
 // Going forward, all schema changes require toggling
 // this DB_VERSION to a higher number.
-const SCHEMA_VERSION: i32 = 2;
+const SCHEMA_VERSION: i32 = 12;
+// How long a write waits on another process holding the lock.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
+// Schema version found by connect, before init_table migrated it.
+static OPENED_VERSION: OnceLock<i32> = OnceLock::new();
+
+pub fn schema_version(conn: &Connection) -> Result<i32, rusqlite::Error> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+pub fn opened_schema_version() -> Option<i32> {
+    OPENED_VERSION.get().copied()
+}
 
 pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let current_version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let current_version = schema_version(conn)?;
 
     if current_version == SCHEMA_VERSION {
         return Ok(());
@@ -20,6 +52,15 @@ pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
     // target_time is specific for type task
     // cron_schedule; human_schedule is specific for type recurring_task
     // recurring_task_id; good_until is for type recurring task record
+    // pinned applies to task and recurring_task
+    // note is an optional longer body kept apart from content
+    // source records how the item was created, "cli" or "import"
+    // estimate_minutes is the optional planned duration of a task
+    // reference is an optional link or ticket, kept out of the table
+    // do_date is the optional day a task is planned for, apart from its deadline
+    // closed_time is when a task last moved to a closed status
+    // remind_before_minutes is how long before its deadline ops remind lists a task
+    // tags is a comma separated list wrapped in commas, e.g. ",urgent,home,"
     conn.execute(
         "CREATE TABLE IF NOT EXISTS items (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -33,7 +74,16 @@ pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
             cron_schedule TEXT,
             human_schedule TEXT,
             recurring_task_id INTEGER,
-            good_until INTEGER
+            good_until INTEGER,
+            pinned INTEGER DEFAULT 0,
+            note TEXT,
+            source TEXT DEFAULT 'cli',
+            estimate_minutes INTEGER,
+            reference TEXT,
+            do_date INTEGER,
+            closed_time INTEGER,
+            remind_before_minutes INTEGER,
+            tags TEXT
         )",
         [],
     )?;
@@ -70,12 +120,66 @@ pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
         [],
     )?;
 
+    // Daily goal per category for record --goal, added in version 6
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS goals (
+            category TEXT PRIMARY KEY,
+            target INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
     // Migrate from version 1 to 2 - add columns for recurring task support
     if current_version < 2 && current_version > 0 {
-        conn.execute("ALTER TABLE items ADD COLUMN cron_schedule TEXT", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN human_schedule TEXT", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN recurring_task_id INTEGER", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN good_until INTEGER", [])?;
+        add_column(conn, "cron_schedule", "TEXT")?;
+        add_column(conn, "human_schedule", "TEXT")?;
+        add_column(conn, "recurring_task_id", "INTEGER")?;
+        add_column(conn, "good_until", "INTEGER")?;
+    }
+
+    // Migrate from version 2 to 3 - add column for pinned tasks
+    if current_version < 3 && current_version > 0 {
+        add_column(conn, "pinned", "INTEGER DEFAULT 0")?;
+    }
+
+    // Migrate from version 3 to 4 - add column for notes
+    if current_version < 4 && current_version > 0 {
+        add_column(conn, "note", "TEXT")?;
+    }
+
+    // Migrate from version 4 to 5 - add column for item source
+    if current_version < 5 && current_version > 0 {
+        add_column(conn, "source", "TEXT DEFAULT 'cli'")?;
+    }
+
+    // Migrate from version 6 to 7 - add column for task estimates
+    if current_version < 7 && current_version > 0 {
+        add_column(conn, "estimate_minutes", "INTEGER")?;
+    }
+
+    // Migrate from version 7 to 8 - add column for references
+    if current_version < 8 && current_version > 0 {
+        add_column(conn, "reference", "TEXT")?;
+    }
+
+    // Migrate from version 8 to 9 - add column for reminder leads
+    if current_version < 9 && current_version > 0 {
+        add_column(conn, "remind_before_minutes", "INTEGER")?;
+    }
+
+    // Migrate from version 9 to 10 - add column for do dates
+    if current_version < 10 && current_version > 0 {
+        add_column(conn, "do_date", "INTEGER")?;
+    }
+
+    // Migrate from version 10 to 11 - add column for closed times
+    if current_version < 11 && current_version > 0 {
+        add_column(conn, "closed_time", "INTEGER")?;
+    }
+
+    // Migrate from version 11 to 12 - add column for tags
+    if current_version < 12 && current_version > 0 {
+        add_column(conn, "tags", "TEXT")?;
     }
 
     conn.execute(
@@ -88,10 +192,74 @@ pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
     Ok(())
 }
 
+// Skips columns that already exist, so a migration interrupted midway,
+// or rerun with a stale user_version, can be applied again.
+fn add_column(conn: &Connection, column: &str, definition: &str) -> Result<(), rusqlite::Error> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info('items') WHERE name = ?1")?
+        .exists([column])?;
+    if !exists {
+        conn.execute(&format!("ALTER TABLE items ADD COLUMN {column} {definition}"), [])?;
+    }
+    Ok(())
+}
+
+// Registers the regexp() function so that `content REGEXP ?` works in queries.
+// SQLite declares the REGEXP operator but ships no implementation of it.
+// normalize_category() compares stored categories the way they are normalized on input.
+pub fn register_functions(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.create_scalar_function(
+        "normalize_category",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let category = ctx
+                .get_raw(0)
+                .as_str()
+                .map_err(|e| Error::UserFunctionError(e.into()))?;
+            Ok(normalize_category(category))
+        },
+    )?;
+    conn.create_scalar_function(
+        "regexp",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let regexp: Arc<Regex> = ctx.get_or_create_aux(0, |vr| -> Result<_, BoxError> {
+                Ok(Regex::new(vr.as_str()?)?)
+            })?;
+            let text = ctx
+                .get_raw(1)
+                .as_str()
+                .map_err(|e| Error::UserFunctionError(e.into()))?;
+            Ok(regexp.is_match(text))
+        },
+    )
+}
+
+// WAL lets readers proceed while another process writes,
+// and the busy timeout makes concurrent writers wait instead of failing.
+pub fn configure_connection(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get::<_, String>(0))?;
+    Ok(())
+}
+
+// Moves pending WAL content into the main file, so copying the file alone is complete.
+pub fn checkpoint(db_path: &Path) -> Result<(), rusqlite::Error> {
+    let conn = Connection::open(db_path)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+}
+
 pub fn connect() -> Result<Connection, String> {
     let db_path = get_data_path()?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    configure_connection(&conn).map_err(|e| e.to_string())?;
+    let version = schema_version(&conn).map_err(|e| e.to_string())?;
+    OPENED_VERSION.get_or_init(|| version);
     init_table(&conn).map_err(|e| e.to_string())?;
+    register_functions(&conn).map_err(|e| e.to_string())?;
 
     Ok(conn)
 }
@@ -178,4 +346,100 @@ mod tests {
             .unwrap();
         assert_eq!(SCHEMA_VERSION, final_version);
     }
+
+    #[test]
+    fn test_migrate_old_schema() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        // The version 1 layout, before recurring tasks
+        conn.execute(
+            "CREATE TABLE items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                action TEXT NOT NULL,
+                category TEXT NOT NULL,
+                content TEXT NOT NULL,
+                create_time INTEGER NOT NULL,
+                target_time INTEGER,
+                modify_time INTEGER,
+                status INTEGER DEFAULT 0
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO items (action, category, content, create_time) VALUES ('record', 'life', 'old entry', 1700000000)",
+            [],
+        )
+        .unwrap();
+        conn.execute("PRAGMA user_version = 1", []).unwrap();
+
+        init_table(&conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        let item = crate::db::crud::get_item(&conn, 1).unwrap();
+        assert_eq!(item.content, "old entry");
+        assert!(!item.pinned);
+        assert_eq!(item.source, "cli");
+        assert!(item.do_date.is_none());
+        assert!(item.remind_before_minutes.is_none());
+        assert!(item.tags.is_none());
+
+        // Rerunning with a stale version skips columns already added
+        conn.execute("PRAGMA user_version = 1", []).unwrap();
+        init_table(&conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_concurrent_read_while_writing() {
+        let (_conn, temp_file) = get_test_conn();
+        let open = || {
+            let conn = Connection::open(temp_file.path()).unwrap();
+            configure_connection(&conn).unwrap();
+            conn
+        };
+        let writer = open();
+        let reader = open();
+        let journal_mode: String = reader
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+
+        let count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+                .unwrap()
+        };
+        writer.execute_batch("BEGIN IMMEDIATE").unwrap();
+        writer
+            .execute(
+                "INSERT INTO items (action, category, content, create_time) VALUES ('record', 'c', 'x', 1)",
+                [],
+            )
+            .unwrap();
+        // The reader sees the last committed state instead of erroring on the lock
+        assert_eq!(count(&reader), 0);
+        writer.execute_batch("COMMIT").unwrap();
+        assert_eq!(count(&reader), 1);
+
+        checkpoint(temp_file.path()).unwrap();
+        let copy = tempfile::NamedTempFile::new().unwrap();
+        std::fs::copy(temp_file.path(), copy.path()).unwrap();
+        assert_eq!(count(&Connection::open(copy.path()).unwrap()), 1);
+    }
+
+    #[test]
+    fn test_register_functions() {
+        let (conn, _temp_file) = get_test_conn();
+        let is_match: bool = conn
+            .query_row("SELECT 'project alpha' REGEXP '^proj.*a$'", [], |row| row.get(0))
+            .unwrap();
+        assert!(is_match);
+        let is_match: bool = conn
+            .query_row("SELECT 'project alpha' REGEXP '^alpha'", [], |row| row.get(0))
+            .unwrap();
+        assert!(!is_match);
+        let normalized: String = conn
+            .query_row("SELECT normalize_category(' Home  Chores')", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(normalized, "home chores");
+    }
 }
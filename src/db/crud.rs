@@ -1,27 +1,40 @@
-use std::time::{
-    SystemTime,
-    UNIX_EPOCH,
+use std::{
+    ops::ControlFlow,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 
 use rusqlite::{
     params,
     params_from_iter,
     Connection,
-    Result,
 };
 
-use crate::db::item::{
-    Item,
-    ItemQuery,
-    Offset,
+use crate::{
+    config::get_config,
+    db::{
+        debug,
+        item::{
+            Item,
+            ItemQuery,
+            Offset,
+            CLOSED_STATUS_CODES,
+        },
+    },
+    error::{
+        Result,
+        TascliError,
+    },
 };
 
 const VALID_ORDER_COLUMNS: &[&str] = &["id", "create_time", "target_time"];
 
 pub fn insert_item(conn: &Connection, item: &Item) -> Result<i64> {
     conn.execute(
-        "INSERT INTO items (action, category, content, create_time, target_time, cron_schedule, human_schedule, recurring_task_id, good_until)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        "INSERT INTO items (action, category, content, create_time, target_time, cron_schedule, human_schedule, recurring_task_id, status, good_until, pinned, note, source, estimate_minutes, reference, do_date, closed_time, remind_before_minutes, tags)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
         params![
             item.action,
             item.category,
@@ -31,20 +44,64 @@ pub fn insert_item(conn: &Connection, item: &Item) -> Result<i64> {
             item.cron_schedule,
             item.human_schedule,
             item.recurring_task_id,
-            item.good_until
+            item.status,
+            item.good_until,
+            item.pinned,
+            item.note,
+            item.source,
+            item.estimate_minutes,
+            item.reference,
+            item.do_date,
+            item.closed_time,
+            item.remind_before_minutes,
+            item.tags
         ],
     )?;
 
     Ok(conn.last_insert_rowid())
 }
 
+// Writes every column as given, keeping the id and times, for restoring a dump.
+// An existing item with the same id is replaced.
+pub fn restore_item(conn: &Connection, item: &Item) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO items (id, action, category, content, create_time, target_time, modify_time, status, cron_schedule, human_schedule, recurring_task_id, good_until, pinned, note, source, estimate_minutes, reference, do_date, closed_time, remind_before_minutes, tags)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+        params![
+            item.id,
+            item.action,
+            item.category,
+            item.content,
+            item.create_time,
+            item.target_time,
+            item.modify_time,
+            item.status,
+            item.cron_schedule,
+            item.human_schedule,
+            item.recurring_task_id,
+            item.good_until,
+            item.pinned,
+            item.note,
+            item.source,
+            item.estimate_minutes,
+            item.reference,
+            item.do_date,
+            item.closed_time,
+            item.remind_before_minutes,
+            item.tags
+        ],
+    )?;
+
+    Ok(())
+}
+
 pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_secs() as i64;
 
-    conn.execute(
+    let query = format!(
         "UPDATE items SET
             category = ?1,
             content = ?2,
@@ -54,8 +111,21 @@ pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
             cron_schedule = ?6,
             human_schedule = ?7,
             recurring_task_id = ?8,
-            good_until = ?9
-        WHERE id = ?10",
+            good_until = ?9,
+            pinned = ?10,
+            note = ?11,
+            estimate_minutes = ?12,
+            action = ?13,
+            reference = ?14,
+            do_date = ?15,
+            remind_before_minutes = ?16,
+            tags = ?17,
+            closed_time = {}
+        WHERE id = ?18",
+        closed_time_expr("?5", "?4")
+    );
+    conn.execute(
+        &query,
         params![
             item.category,
             item.content,
@@ -66,6 +136,14 @@ pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
             item.human_schedule,
             item.recurring_task_id,
             item.good_until,
+            item.pinned,
+            item.note,
+            item.estimate_minutes,
+            item.action,
+            item.reference,
+            item.do_date,
+            item.remind_before_minutes,
+            item.tags,
             item.id
         ],
     )?;
@@ -73,14 +151,42 @@ pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
     Ok(())
 }
 
+// Stamps closed_time on the update that closes an item, keeps it while the
+// item stays closed and clears it when the item is reopened. The right hand
+// side of an UPDATE sees the old row, so status here is the previous one.
+pub(crate) fn closed_time_expr(new_status: &str, now: &str) -> String {
+    let closed = CLOSED_STATUS_CODES
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "CASE WHEN {new_status} NOT IN ({closed}) THEN NULL \
+         WHEN status IN ({closed}) THEN closed_time ELSE {now} END"
+    )
+}
+
+// With normalized categories, stored ones are compared normalized as well,
+// so entries from before normalization was turned on still match.
+// Needs the normalize_category function registered on the connection.
+pub(crate) fn category_condition(normalize: bool) -> &'static str {
+    if normalize {
+        "normalize_category(category) = ?"
+    } else {
+        "category = ?"
+    }
+}
+
 pub fn get_item(conn: &Connection, item_id: i64) -> Result<Item> {
-    let item = conn.query_row(
+    conn.query_row(
         "SELECT * FROM items WHERE id = ?1",
         params![item_id],
         Item::from_row,
-    )?;
-
-    Ok(item)
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => TascliError::ItemNotFound(item_id),
+        e => TascliError::Db(e),
+    })
 }
 
 pub fn delete_item(conn: &Connection, item_id: i64) -> Result<()> {
@@ -89,10 +195,8 @@ pub fn delete_item(conn: &Connection, item_id: i64) -> Result<()> {
     Ok(())
 }
 
-pub fn query_items(
-    conn: &Connection,
-    item_query: &ItemQuery,
-) -> Result<Vec<Item>, rusqlite::Error> {
+// Builds the WHERE conditions and their bound params shared by query and count.
+pub(crate) fn build_conditions(item_query: &ItemQuery) -> (Vec<String>, Vec<String>) {
     let mut conditions: Vec<String> = Vec::new();
     let mut params: Vec<String> = Vec::new();
 
@@ -111,7 +215,7 @@ pub fn query_items(
     }
 
     if let Some(c) = item_query.category {
-        conditions.push("category = ?".to_string());
+        conditions.push(category_condition(get_config().normalize_categories).to_string());
         params.push(c.to_string());
     }
 
@@ -120,6 +224,16 @@ pub fn query_items(
         params.push(format!("%{}%", content));
     }
 
+    if let Some(content) = item_query.content_exact {
+        conditions.push("content = ?".to_string());
+        params.push(content.to_string());
+    }
+
+    if let Some(pattern) = item_query.content_regex {
+        conditions.push("content REGEXP ?".to_string());
+        params.push(pattern.to_string());
+    }
+
     if let Offset::CreateTime(time, id) = item_query.offset {
         conditions.push("(create_time > ? OR (create_time = ? AND id > ?))".to_string());
         params.extend([time.to_string(), time.to_string(), id.to_string()]);
@@ -132,8 +246,39 @@ pub fn query_items(
         conditions.push("(target_time > ? OR (target_time = ? AND id > ?))".to_string());
         params.extend([time.to_string(), time.to_string(), id.to_string()]);
     } else if let Some(time) = item_query.target_time_min {
-        conditions.push("target_time > ?".to_string());
-        params.push(time.to_string());
+        match item_query.closed_since {
+            Some(closed_since) => {
+                conditions.push("(target_time > ? OR closed_time > ?)".to_string());
+                params.extend([time.to_string(), closed_since.to_string()]);
+            }
+            None => {
+                conditions.push("target_time > ?".to_string());
+                params.push(time.to_string());
+            }
+        }
+    }
+    if let Offset::PinnedTargetTime(pinned, time, id) = item_query.offset {
+        // Unpinned items start over from the earliest once the pinned ones are paged through
+        let pinned = (pinned as u8).to_string();
+        if time == i64::MIN {
+            // Items without a target time sort first, then every dated one follows
+            conditions.push(
+                "(pinned < ? OR (pinned = ? AND (target_time IS NOT NULL OR id > ?)))".to_string(),
+            );
+            params.extend([pinned.clone(), pinned, id.to_string()]);
+        } else {
+            conditions.push(
+                "(pinned < ? OR (pinned = ? AND (target_time > ? OR (target_time = ? AND id > ?))))"
+                    .to_string(),
+            );
+            params.extend([
+                pinned.clone(),
+                pinned,
+                time.to_string(),
+                time.to_string(),
+                id.to_string(),
+            ]);
+        }
     }
 
     if let Some(ct_max) = item_query.create_time_max {
@@ -146,6 +291,32 @@ pub fn query_items(
         params.push(tt_max.to_string());
     }
 
+    match item_query.target_time_null {
+        Some(true) => conditions.push("target_time IS NULL".to_string()),
+        Some(false) => conditions.push("target_time IS NOT NULL".to_string()),
+        None => {}
+    }
+
+    if let Some(dd_min) = item_query.do_date_min {
+        conditions.push("do_date > ?".to_string());
+        params.push(dd_min.to_string());
+    }
+
+    if let Some(dd_max) = item_query.do_date_max {
+        conditions.push("do_date <= ?".to_string());
+        params.push(dd_max.to_string());
+    }
+
+    if let Some(ct_min) = item_query.closed_time_min {
+        conditions.push("closed_time > ?".to_string());
+        params.push(ct_min.to_string());
+    }
+
+    if let Some(ct_max) = item_query.closed_time_max {
+        conditions.push("closed_time <= ?".to_string());
+        params.push(ct_max.to_string());
+    }
+
     if let Some(gu_min) = item_query.good_until_min {
         conditions.push("good_until > ?".to_string());
         params.push(gu_min.to_string());
@@ -161,6 +332,11 @@ pub fn query_items(
         params.push(rt_id.to_string());
     }
 
+    if let Some(source) = item_query.source {
+        conditions.push("source = ?".to_string());
+        params.push(source.to_string());
+    }
+
     if let Some(cc) = &item_query.statuses {
         let status_list = cc
             .iter()
@@ -170,11 +346,64 @@ pub fn query_items(
         conditions.push(format!("status IN ({})", status_list));
     }
 
+    if let Some(cc) = &item_query.statuses_not {
+        let status_list = cc
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        conditions.push(format!("status NOT IN ({})", status_list));
+    }
+
     if let Offset::Id(rowid) = item_query.offset {
         conditions.push("id > ?".to_string());
         params.push(rowid.to_string());
     }
 
+    (conditions, params)
+}
+
+pub fn query_items(
+    conn: &Connection,
+    item_query: &ItemQuery,
+) -> Result<Vec<Item>> {
+    let (querystr, params) = build_select(item_query)?;
+    debug::log_query(&querystr, &params);
+    let mut stmt = conn.prepare(&querystr)?;
+
+    let item_iter = stmt.query_map(params_from_iter(params), Item::from_row)?;
+
+    let mut items = Vec::new();
+    for item_result in item_iter {
+        items.push(item_result?);
+    }
+
+    Ok(items)
+}
+
+// Same query as query_items, but rows are handed to f one at a time instead of
+// collected, so memory stays flat. Returns how many items f was given.
+pub fn for_each_item(
+    conn: &Connection,
+    item_query: &ItemQuery,
+    mut f: impl FnMut(Item) -> ControlFlow<()>,
+) -> Result<usize> {
+    let (querystr, params) = build_select(item_query)?;
+    debug::log_query(&querystr, &params);
+    let mut stmt = conn.prepare(&querystr)?;
+
+    let mut count = 0;
+    for item_result in stmt.query_map(params_from_iter(params), Item::from_row)? {
+        count += 1;
+        if f(item_result?).is_break() {
+            break;
+        }
+    }
+    Ok(count)
+}
+
+fn build_select(item_query: &ItemQuery) -> Result<(String, Vec<String>)> {
+    let (conditions, mut params) = build_conditions(item_query);
     let mut querystr = String::from("SELECT * FROM items");
     if !conditions.is_empty() {
         querystr.push_str(" WHERE ");
@@ -184,32 +413,42 @@ pub fn query_items(
     let order_column = match item_query.offset {
         Offset::Id(_) => "id",
         Offset::CreateTime(_, _) => "create_time",
-        Offset::TargetTime(_, _) => "target_time",
+        Offset::TargetTime(_, _) | Offset::PinnedTargetTime(..) => "target_time",
         Offset::None => item_query.order_by.unwrap_or("id"),
     };
     if !VALID_ORDER_COLUMNS.contains(&order_column) {
-        return Err(rusqlite::Error::InvalidColumnName(format!(
-            "invalid column: {}",
-            order_column
-        )));
+        return Err(TascliError::Parse(format!("invalid column: {}", order_column)));
+    }
+    let direction = if item_query.order_desc { "DESC" } else { "ASC" };
+    querystr.push_str(" ORDER BY ");
+    if item_query.pinned_first {
+        querystr.push_str("pinned DESC, ");
+    }
+    querystr.push_str(&format!("{} {}", order_column, direction));
+    // Ties on a time column follow id, the order the offsets above page through
+    if order_column != "id" {
+        querystr.push_str(&format!(", id {}", direction));
     }
-    querystr.push_str(&format!(" ORDER BY {} ASC", order_column));
 
     if let Some(limit) = item_query.limit {
         querystr.push_str(" LIMIT ?");
         params.push(limit.to_string());
     }
+    Ok((querystr, params))
+}
 
-    let mut stmt = conn.prepare(&querystr)?;
-
-    let item_iter = stmt.query_map(params_from_iter(params), Item::from_row)?;
-
-    let mut items = Vec::new();
-    for item_result in item_iter {
-        items.push(item_result?);
+// Counts matching items without loading them, limit and order are ignored.
+pub fn count_items(conn: &Connection, item_query: &ItemQuery) -> Result<usize> {
+    let (conditions, params) = build_conditions(item_query);
+    let mut querystr = String::from("SELECT COUNT(*) FROM items");
+    if !conditions.is_empty() {
+        querystr.push_str(" WHERE ");
+        querystr.push_str(&conditions.join(" AND "));
     }
 
-    Ok(items)
+    debug::log_query(&querystr, &params);
+    let count: i64 = conn.query_row(&querystr, params_from_iter(params), |row| row.get(0))?;
+    Ok(count as usize)
 }
 
 #[cfg(test)]
@@ -221,6 +460,8 @@ mod tests {
             Item,
             RECORD,
             RECURRING_TASK_RECORD,
+            SOURCE_CLI,
+            SOURCE_IMPORT,
             TASK,
         },
         tests::{
@@ -323,6 +564,10 @@ mod tests {
         delete_item(&conn, item1_id).expect("Unable to delete item");
         let items = query_items(&conn, &item_query).unwrap();
         assert_eq!(items.len(), 0);
+        assert!(matches!(
+            get_item(&conn, item1_id),
+            Err(TascliError::ItemNotFound(id)) if id == item1_id
+        ));
     }
 
     #[test]
@@ -373,6 +618,42 @@ mod tests {
         assert_eq!(limited_items.len(), 4);
     }
 
+    #[test]
+    fn test_count_items() {
+        let (conn, _temp_file) = get_test_conn();
+        for i in 1..=3 {
+            insert_task(&conn, "work", &format!("meeting{}", i), "today");
+        }
+        insert_task(&conn, "life", "feeding", "yesterday");
+        insert_record(&conn, "life", "fed", "yesterday");
+
+        assert_eq!(count_items(&conn, &ItemQuery::new()).unwrap(), 5);
+        let work_query = ItemQuery::new().with_action(TASK).with_category("work");
+        assert_eq!(count_items(&conn, &work_query).unwrap(), 3);
+        let past_query = ItemQuery::new()
+            .with_action(TASK)
+            .with_target_time_max(timestr::to_unix_epoch("yesterday").unwrap())
+            .with_limit(1);
+        assert_eq!(count_items(&conn, &past_query).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_query_source() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "feeding", "typed", "today");
+        let mut imported = get_test_item(RECORD, "feeding", "imported");
+        imported.source = SOURCE_IMPORT.to_string();
+        insert_item(&conn, &imported).unwrap();
+
+        let import_query = ItemQuery::new().with_source(SOURCE_IMPORT);
+        let results = query_items(&conn, &import_query).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "imported");
+        assert_eq!(results[0].source, SOURCE_IMPORT);
+        let cli_query = ItemQuery::new().with_source(SOURCE_CLI);
+        assert_eq!(count_items(&conn, &cli_query).unwrap(), 1);
+    }
+
     #[test]
     fn test_query_statuses() {
         let (conn, _temp_file) = get_test_conn();
@@ -419,6 +700,26 @@ mod tests {
         assert!(closed_tasks
             .iter()
             .all(|t| t.category == "done" || t.category == "cancelled"));
+
+        let not_cancelled = query_items(
+            &conn,
+            &ItemQuery::new().with_status_not(vec![2]).with_action(TASK),
+        )
+        .expect("Unable to execute query");
+        assert_eq!(not_cancelled.len(), 9);
+        assert!(not_cancelled.iter().all(|t| t.category != "cancelled"));
+
+        // Both filters apply together
+        let closed_not_cancelled = query_items(
+            &conn,
+            &ItemQuery::new()
+                .with_statuses(vec![1, 2, 3])
+                .with_status_not(vec![2])
+                .with_action(TASK),
+        )
+        .expect("Unable to execute query");
+        assert_eq!(closed_not_cancelled.len(), 3);
+        assert!(closed_not_cancelled.iter().all(|t| t.category == "done"));
     }
 
     // Test pagination capability for tasks
@@ -557,6 +858,38 @@ mod tests {
         assert_eq!(page2.len(), 3, "tie-breaking must not skip items with same create_time");
     }
 
+    #[test]
+    fn test_paginate_shared_times() {
+        let (conn, _temp_file) = get_test_conn();
+        let shared_time = timestr::to_unix_epoch("tomorrow").unwrap();
+        let earlier = timestr::to_unix_epoch("today").unwrap();
+        // Interleave two times so ids within a time are not contiguous
+        let (mut earlier_ids, mut shared_ids) = (Vec::new(), Vec::new());
+        for i in 0..30 {
+            let time = if i % 3 == 0 { earlier } else { shared_time };
+            let item = Item::with_target_time(TASK.to_string(), "test".to_string(), format!("task {}", i), Some(time));
+            let id = insert_item(&conn, &item).unwrap();
+            if time == earlier { earlier_ids.push(id) } else { shared_ids.push(id) }
+        }
+        let expected = [earlier_ids, shared_ids].concat();
+
+        let mut seen = Vec::new();
+        let mut offset = Offset::None;
+        loop {
+            let page = query_items(&conn, &ItemQuery::new().with_action(TASK).with_limit(4)
+                .with_order_by("target_time").with_offset(offset)).unwrap();
+            let Some(last) = page.last() else { break };
+            offset = Offset::TargetTime(last.target_time.unwrap(), last.id.unwrap());
+            seen.extend(page.iter().map(|t| t.id.unwrap()));
+        }
+        assert_eq!(seen, expected);
+
+        let (querystr, _) = build_select(&ItemQuery::new().with_order_by("create_time").with_order_desc()).unwrap();
+        assert!(querystr.ends_with("ORDER BY create_time DESC, id DESC"), "{}", querystr);
+        let (querystr, _) = build_select(&ItemQuery::new()).unwrap();
+        assert!(querystr.ends_with("ORDER BY id ASC"), "{}", querystr);
+    }
+
     #[test]
     fn test_offset_id() {
         let (conn, _temp_file) = get_test_conn();
@@ -580,6 +913,19 @@ mod tests {
         assert_eq!(final_item[0].content, "index 11");
     }
 
+    #[test]
+    fn test_category_condition() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "Home  Chores", "entered before normalizing", "tomorrow");
+        insert_task(&conn, "home chores", "entered after", "tomorrow");
+        let count = |normalize: bool| -> i64 {
+            let query = format!("SELECT COUNT(*) FROM items WHERE {}", category_condition(normalize));
+            conn.query_row(&query, params!["home chores"], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(count(false), 1);
+        assert_eq!(count(true), 2);
+    }
+
     #[test]
     fn test_order_by() {
         let (conn, _temp_file) = get_test_conn();
@@ -601,6 +947,19 @@ mod tests {
         .unwrap();
         assert_eq!(result.first().unwrap().content, "task3");
         assert_eq!(result.last().unwrap().content, "task2");
+        let result = query_items(
+            &conn,
+            &ItemQuery::new()
+                .with_action(TASK)
+                .with_order_by("target_time")
+                .with_order_desc()
+                .with_limit(1),
+        )
+        .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].content, "task2");
+        let result = query_items(&conn, &ItemQuery::new().with_order_by("content"));
+        assert!(matches!(result, Err(TascliError::Parse(_))));
     }
 
     #[test]
@@ -676,6 +1035,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_query_content_exact() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "status", "up", "yesterday");
+        insert_record(&conn, "status", "down", "yesterday");
+        insert_record(&conn, "status", "up again", "yesterday");
+        insert_record(&conn, "status", "backup", "yesterday");
+
+        let like = query_items(&conn, &ItemQuery::new().with_content_like("up")).unwrap();
+        assert_eq!(like.len(), 3);
+        let exact = query_items(&conn, &ItemQuery::new().with_content_exact("up")).unwrap();
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].content, "up");
+        // Equality is case sensitive, unlike LIKE
+        assert!(query_items(&conn, &ItemQuery::new().with_content_exact("UP")).unwrap().is_empty());
+    }
+
     #[test]
     fn test_query_multiple_actions() {
         let (conn, _temp_file) = get_test_conn();
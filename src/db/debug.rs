@@ -0,0 +1,22 @@
+use std::sync::atomic::{
+    AtomicBool,
+    Ordering,
+};
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+// Enabled by --verbose or a non-empty TASCLI_DEBUG.
+fn is_verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed) || std::env::var("TASCLI_DEBUG").is_ok_and(|v| !v.is_empty())
+}
+
+// Printed to stderr so piped output stays clean.
+pub fn log_query(query: &str, params: &[String]) {
+    if is_verbose() {
+        eprintln!("[tascli debug] sql: {} params: {:?}", query, params);
+    }
+}
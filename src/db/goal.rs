@@ -0,0 +1,52 @@
+use rusqlite::{
+    params,
+    Connection,
+    OptionalExtension,
+};
+
+use crate::error::Result;
+
+// A target of 0 removes the goal for the category.
+pub fn set(conn: &Connection, category: &str, target: usize) -> Result<()> {
+    if target == 0 {
+        conn.execute("DELETE FROM goals WHERE category = ?1", params![category])?;
+    } else {
+        conn.execute(
+            "INSERT OR REPLACE INTO goals (category, target) VALUES (?1, ?2)",
+            params![category, target as i64],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn get(conn: &Connection, category: &str) -> Result<Option<usize>> {
+    let target = conn
+        .query_row(
+            "SELECT target FROM goals WHERE category = ?1",
+            params![category],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()?;
+    Ok(target.map(|t| t as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    #[test]
+    fn test_goal() {
+        let (conn, _temp_file) = get_test_conn();
+        assert_eq!(get(&conn, "water").unwrap(), None);
+
+        set(&conn, "water", 8).unwrap();
+        assert_eq!(get(&conn, "water").unwrap(), Some(8));
+        set(&conn, "water", 6).unwrap();
+        assert_eq!(get(&conn, "water").unwrap(), Some(6));
+        assert_eq!(get(&conn, "steps").unwrap(), None);
+
+        set(&conn, "water", 0).unwrap();
+        assert_eq!(get(&conn, "water").unwrap(), None);
+    }
+}
@@ -1,11 +1,20 @@
-use std::time::{
-    SystemTime,
-    UNIX_EPOCH,
+use std::{
+    str::Chars,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 
+use nanoserde::{
+    DeJson,
+    DeJsonErr,
+    DeJsonState,
+    SerJson,
+};
 use rusqlite::Row;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, SerJson)]
 pub struct Item {
     // Optional id field as when item is first created at runtime it
     // has not received an id from the db yet.
@@ -16,7 +25,6 @@ pub struct Item {
     pub create_time: i64,
     // This field is dedicated for tasks (deadline)
     pub target_time: Option<i64>,
-    #[allow(dead_code)]
     pub modify_time: Option<i64>,
     pub status: u8,
     // cron and human schedule are specific to recurring tasks.
@@ -26,8 +34,28 @@ pub struct Item {
     // these records are generated when a recurring task is "done"
     pub recurring_task_id: Option<i64>,
     pub good_until: Option<i64>,
+    // Pinned tasks are listed before the others.
+    pub pinned: bool,
+    // Longer body shown by list show --full, content stays the short title.
+    pub note: Option<String>,
+    // Where the item came from, "cli" or "import". Older dumps lack it.
+    pub source: String,
+    // Planned duration of a task in minutes, summed by list task --budget.
+    pub estimate_minutes: Option<i64>,
+    // Link or ticket the item refers to, shown by list show --full.
+    pub reference: Option<String>,
+    // Day a task is planned to be worked on, listed by list task --do,
+    // the deadline stays in target_time.
+    pub do_date: Option<i64>,
+    // When a task last moved to a closed status, cleared when it is reopened.
+    pub closed_time: Option<i64>,
+    // How long before its deadline a task is listed by ops remind.
+    pub remind_before_minutes: Option<i64>,
+    // Tags wrapped in commas like ",urgent,home," so a whole tag matches ",tag,".
+    pub tags: Option<String>,
     // Runtime-only field applicable to recurring task, not persisted to db
     // Computed at application layer indicating if a recurring_task is completed.
+    #[nserde(skip)]
     pub recurring_interval_complete: bool,
 }
 
@@ -36,6 +64,82 @@ pub const RECORD: &str = "record";
 pub const RECURRING_TASK: &str = "recurring_task";
 pub const RECURRING_TASK_RECORD: &str = "recurring_task_record";
 
+pub const OPEN_STATUS_CODES: &[u8] = &[0, 4, 6, 7];
+pub const CLOSED_STATUS_CODES: &[u8] = &[1, 2, 3, 5];
+
+pub const SOURCE_CLI: &str = "cli";
+pub const SOURCE_IMPORT: &str = "import";
+
+fn default_source() -> String {
+    SOURCE_CLI.to_string()
+}
+
+// Status code for a built-in name, aggregates like open are 240 and above.
+pub fn builtin_status(s: &str) -> Option<u8> {
+    match s.to_lowercase().as_str() {
+        "ongoing" => Some(0),
+        "done" | "complete" | "completed" => Some(1),
+        "cancelled" | "canceled" | "cancel" => Some(2),
+        "duplicate" => Some(3),
+        "deferred" | "suspended" | "shelved" => Some(4),
+        "removed" | "remove" | "unneeded" | "unnecessary" => Some(5),
+        "pending" => Some(6),
+        "in_progress" | "in-progress" | "inprogress" | "started" => Some(7),
+        "closed" => Some(253), // combination of done | cancelled | duplicate | removed
+        "open" => Some(254), // combination of ongoing | pending | suspended | in_progress
+        "all" => Some(255), // all status
+        _ => s.parse::<u8>().ok(),
+    }
+}
+
+// Bounds for a query limit, shared by --limit and default_list_limit.
+pub fn validate_limit(s: &str) -> Result<usize, String> {
+    let limit: usize = s.parse().map_err(|_| "Must be a number".to_string())?;
+    if limit < 1 {
+        return Err("Limit cannot be less than 1".to_string());
+    }
+    if limit > 65536 {
+        return Err("Limit cannot exceed 65536".to_string());
+    }
+    Ok(limit)
+}
+
+// Trim, lowercase and collapse internal whitespace.
+pub fn normalize_category(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// Tags of a stored tags value, without the wrapping commas.
+pub fn tag_list(tags: Option<&str>) -> Vec<&str> {
+    tags.unwrap_or("").split(',').filter(|t| !t.is_empty()).collect()
+}
+
+// A tag already present is kept once.
+pub fn add_tag(tags: Option<&str>, tag: &str) -> Option<String> {
+    let mut list = tag_list(tags);
+    if !list.contains(&tag) {
+        list.push(tag);
+    }
+    join_tags(&list)
+}
+
+pub fn remove_tag(tags: Option<&str>, tag: &str) -> Option<String> {
+    let list: Vec<&str> = tag_list(tags).into_iter().filter(|t| *t != tag).collect();
+    join_tags(&list)
+}
+
+// No tags is stored as NULL rather than a lone comma.
+fn join_tags(list: &[&str]) -> Option<String> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(format!(",{},", list.join(",")))
+    }
+}
+
 impl Item {
     pub fn new(action: String, category: String, content: String) -> Self {
         let now = SystemTime::now()
@@ -56,6 +160,15 @@ impl Item {
             human_schedule: None,
             recurring_task_id: None,
             good_until: None,
+            pinned: false,
+            note: None,
+            source: default_source(),
+            estimate_minutes: None,
+            reference: None,
+            do_date: None,
+            closed_time: None,
+            remind_before_minutes: None,
+            tags: None,
             recurring_interval_complete: false,
         }
     }
@@ -121,6 +234,102 @@ impl Item {
             human_schedule: row.get("human_schedule")?,
             recurring_task_id: row.get("recurring_task_id")?,
             good_until: row.get("good_until")?,
+            pinned: row.get("pinned")?,
+            note: row.get("note")?,
+            source: row
+                .get::<_, Option<String>>("source")?
+                .unwrap_or_else(default_source),
+            estimate_minutes: row.get("estimate_minutes")?,
+            reference: row.get("reference")?,
+            do_date: row.get("do_date")?,
+            closed_time: row.get("closed_time")?,
+            remind_before_minutes: row.get("remind_before_minutes")?,
+            tags: row.get("tags")?,
+            recurring_interval_complete: false,
+        })
+    }
+}
+
+// Written out rather than derived, the derive turns every Option field into a
+// nested Option that clippy flags.
+impl DeJson for Item {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let mut id = None;
+        let mut action = None;
+        let mut category = None;
+        let mut content = None;
+        let mut create_time = None;
+        let mut target_time = None;
+        let mut modify_time = None;
+        let mut status = None;
+        let mut cron_schedule = None;
+        let mut human_schedule = None;
+        let mut recurring_task_id = None;
+        let mut good_until = None;
+        let mut pinned = None;
+        let mut note = None;
+        let mut source = None;
+        let mut estimate_minutes = None;
+        let mut reference = None;
+        let mut do_date = None;
+        let mut closed_time = None;
+        let mut remind_before_minutes = None;
+        let mut tags = None;
+
+        s.curly_open(i)?;
+        while s.next_str().is_some() {
+            let key = std::mem::take(&mut s.strbuf);
+            s.next_colon(i)?;
+            match key.as_str() {
+                "id" => id = DeJson::de_json(s, i)?,
+                "action" => action = Some(DeJson::de_json(s, i)?),
+                "category" => category = Some(DeJson::de_json(s, i)?),
+                "content" => content = Some(DeJson::de_json(s, i)?),
+                "create_time" => create_time = Some(DeJson::de_json(s, i)?),
+                "target_time" => target_time = DeJson::de_json(s, i)?,
+                "modify_time" => modify_time = DeJson::de_json(s, i)?,
+                "status" => status = Some(DeJson::de_json(s, i)?),
+                "cron_schedule" => cron_schedule = DeJson::de_json(s, i)?,
+                "human_schedule" => human_schedule = DeJson::de_json(s, i)?,
+                "recurring_task_id" => recurring_task_id = DeJson::de_json(s, i)?,
+                "good_until" => good_until = DeJson::de_json(s, i)?,
+                "pinned" => pinned = Some(DeJson::de_json(s, i)?),
+                "note" => note = DeJson::de_json(s, i)?,
+                "source" => source = Some(DeJson::de_json(s, i)?),
+                "estimate_minutes" => estimate_minutes = DeJson::de_json(s, i)?,
+                "reference" => reference = DeJson::de_json(s, i)?,
+                "do_date" => do_date = DeJson::de_json(s, i)?,
+                "closed_time" => closed_time = DeJson::de_json(s, i)?,
+                "remind_before_minutes" => remind_before_minutes = DeJson::de_json(s, i)?,
+                "tags" => tags = DeJson::de_json(s, i)?,
+                _ => s.whole_field(i)?,
+            }
+            s.eat_comma_curly(i)?;
+        }
+        s.curly_close(i)?;
+
+        Ok(Item {
+            id,
+            action: action.ok_or_else(|| s.err_nf("action"))?,
+            category: category.ok_or_else(|| s.err_nf("category"))?,
+            content: content.ok_or_else(|| s.err_nf("content"))?,
+            create_time: create_time.ok_or_else(|| s.err_nf("create_time"))?,
+            target_time,
+            modify_time,
+            status: status.ok_or_else(|| s.err_nf("status"))?,
+            cron_schedule,
+            human_schedule,
+            recurring_task_id,
+            good_until,
+            pinned: pinned.ok_or_else(|| s.err_nf("pinned"))?,
+            note,
+            source: source.unwrap_or_else(default_source),
+            estimate_minutes,
+            reference,
+            do_date,
+            closed_time,
+            remind_before_minutes,
+            tags,
             recurring_interval_complete: false,
         })
     }
@@ -132,17 +341,29 @@ pub struct ItemQuery<'a> {
     pub actions: Option<Vec<&'a str>>,
     pub category: Option<&'a str>,
     pub content_like: Option<&'a str>,
+    pub content_exact: Option<&'a str>,
+    pub content_regex: Option<&'a str>,
     pub create_time_min: Option<i64>,
     pub create_time_max: Option<i64>,
     pub target_time_min: Option<i64>,
     pub target_time_max: Option<i64>,
+    pub target_time_null: Option<bool>,
+    pub do_date_min: Option<i64>,
+    pub do_date_max: Option<i64>,
+    pub closed_time_min: Option<i64>,
+    pub closed_time_max: Option<i64>,
+    pub closed_since: Option<i64>,
     pub good_until_min: Option<i64>,
     pub good_until_max: Option<i64>,
     pub recurring_task_id: Option<i64>,
+    pub source: Option<&'a str>,
     pub statuses: Option<Vec<u8>>,
+    pub statuses_not: Option<Vec<u8>>,
     pub limit: Option<usize>,
     pub offset: Offset,
     pub order_by: Option<&'a str>,
+    pub order_desc: bool,
+    pub pinned_first: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -152,6 +373,7 @@ pub enum Offset {
     Id(i64),
     CreateTime(i64, i64), // (time, id) for tie-breaking
     TargetTime(i64, i64), // (time, id) for tie-breaking
+    PinnedTargetTime(bool, i64, i64), // (pinned, time, id) for pinned first listings
 }
 
 #[allow(dead_code)]
@@ -161,17 +383,29 @@ impl<'a> ItemQuery<'a> {
             actions: None,
             category: None,
             content_like: None,
+            content_exact: None,
+            content_regex: None,
             create_time_min: None,
             create_time_max: None,
             target_time_min: None,
             target_time_max: None,
+            target_time_null: None,
+            do_date_min: None,
+            do_date_max: None,
+            closed_time_min: None,
+            closed_time_max: None,
+            closed_since: None,
             good_until_min: None,
             good_until_max: None,
             recurring_task_id: None,
+            source: None,
             statuses: None,
+            statuses_not: None,
             limit: None,
             offset: Offset::None,
             order_by: None,
+            order_desc: false,
+            pinned_first: false,
         }
     }
 
@@ -195,6 +429,17 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    pub fn with_content_exact(mut self, content: &'a str) -> Self {
+        self.content_exact = Some(content);
+        self
+    }
+
+    // Requires the regexp function registered on the connection.
+    pub fn with_content_regex(mut self, pattern: &'a str) -> Self {
+        self.content_regex = Some(pattern);
+        self
+    }
+
     pub fn with_create_time_range(mut self, min: Option<i64>, max: Option<i64>) -> Self {
         self.create_time_min = min;
         self.create_time_max = max;
@@ -207,6 +452,18 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    pub fn with_do_date_range(mut self, min: Option<i64>, max: Option<i64>) -> Self {
+        self.do_date_min = min;
+        self.do_date_max = max;
+        self
+    }
+
+    pub fn with_closed_time_range(mut self, min: Option<i64>, max: Option<i64>) -> Self {
+        self.closed_time_min = min;
+        self.closed_time_max = max;
+        self
+    }
+
     pub fn with_create_time_min(mut self, create_time_min: i64) -> Self {
         self.create_time_min = Some(create_time_min);
         self
@@ -227,11 +484,35 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    // Only items without a target time, e.g. tasks with no deadline.
+    // Items closed after the given time match whatever the target time min.
+    pub fn with_closed_since(mut self, closed_since: i64) -> Self {
+        self.closed_since = Some(closed_since);
+        self
+    }
+
+    pub fn with_no_target_time(mut self) -> Self {
+        self.target_time_null = Some(true);
+        self
+    }
+
+    // Only items with a target time, SQLite orders NULL before any time.
+    pub fn with_any_target_time(mut self) -> Self {
+        self.target_time_null = Some(false);
+        self
+    }
+
     pub fn with_statuses(mut self, statuses: Vec<u8>) -> Self {
         self.statuses = Some(statuses);
         self
     }
 
+    // Applies together with with_statuses, an item must pass both.
+    pub fn with_status_not(mut self, statuses: Vec<u8>) -> Self {
+        self.statuses_not = Some(statuses);
+        self
+    }
+
     pub fn with_limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
         self
@@ -247,6 +528,17 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    pub fn with_order_desc(mut self) -> Self {
+        self.order_desc = true;
+        self
+    }
+
+    // Pinned items lead in the order, ahead of the limit.
+    pub fn with_pinned_first(mut self) -> Self {
+        self.pinned_first = true;
+        self
+    }
+
     pub fn with_good_until_range(mut self, min: Option<i64>, max: Option<i64>) -> Self {
         self.good_until_min = min;
         self.good_until_max = max;
@@ -267,6 +559,11 @@ impl<'a> ItemQuery<'a> {
         self.recurring_task_id = Some(recurring_task_id);
         self
     }
+
+    pub fn with_source(mut self, source: &'a str) -> Self {
+        self.source = Some(source);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -292,6 +589,9 @@ mod tests {
         assert!(item.human_schedule.is_none());
         assert!(item.recurring_task_id.is_none());
         assert!(item.good_until.is_none());
+        assert!(!item.pinned);
+        assert!(item.note.is_none());
+        assert_eq!(item.source, SOURCE_CLI);
         assert!(!item.recurring_interval_complete);
     }
 
@@ -377,6 +677,21 @@ mod tests {
         assert!(item.human_schedule.is_none());
     }
 
+    #[test]
+    fn test_tags() {
+        let tags = add_tag(None, "urgent");
+        assert_eq!(tags.as_deref(), Some(",urgent,"));
+        let tags = add_tag(tags.as_deref(), "home");
+        assert_eq!(tags.as_deref(), Some(",urgent,home,"));
+        assert_eq!(add_tag(tags.as_deref(), "urgent"), tags);
+        assert_eq!(tag_list(tags.as_deref()), vec!["urgent", "home"]);
+
+        assert_eq!(remove_tag(tags.as_deref(), "urgent").as_deref(), Some(",home,"));
+        assert_eq!(remove_tag(tags.as_deref(), "work"), tags);
+        assert_eq!(remove_tag(Some(",home,"), "home"), None);
+        assert_eq!(remove_tag(None, "home"), None);
+    }
+
     #[test]
     fn test_item_query_builder() {
         // Test default values from new()
@@ -394,6 +709,7 @@ mod tests {
         assert_eq!(query.limit, None);
         assert_eq!(query.offset, Offset::None);
         assert_eq!(query.order_by, None);
+        assert!(!query.order_desc);
 
         let query = ItemQuery::new().with_action(TASK);
         assert_eq!(query.actions, Some(vec![TASK]));
@@ -427,6 +743,10 @@ mod tests {
         let query = ItemQuery::new().with_statuses(vec![0]);
         assert_eq!(query.statuses, Some(vec![0]));
 
+        let query = ItemQuery::new().with_status_not(vec![5]);
+        assert_eq!(query.statuses_not, Some(vec![5]));
+        assert_eq!(query.statuses, None);
+
         let query = ItemQuery::new().with_limit(100);
         assert_eq!(query.limit, Some(100));
 
@@ -1,5 +1,7 @@
 pub mod cache;
 pub mod conn;
 pub mod crud;
+pub mod debug;
+pub mod goal;
 pub mod item;
 pub mod ops;
@@ -1,7 +1,37 @@
+use nanoserde::{
+    DeJson,
+    SerJson,
+};
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
 use rusqlite::{
+    params,
     params_from_iter,
     Connection,
-    Result,
+};
+
+use crate::{
+    config::get_config,
+    db::{
+        crud::{
+            build_conditions,
+            category_condition,
+            closed_time_expr,
+        },
+        debug,
+        item::{
+            add_tag,
+            remove_tag,
+            ItemQuery,
+        },
+    },
+    error::{
+        Result,
+        TascliError,
+    },
 };
 
 #[derive(Debug)]
@@ -9,9 +39,11 @@ pub struct ItemUpdates {
     pub category: Option<String>,
     pub status: Option<u8>,
     pub target_time: Option<i64>,
+    pub add_tag: Option<String>,
+    pub remove_tag: Option<String>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, SerJson, DeJson)]
 pub struct StatRow {
     pub category: String,
     pub task: usize,
@@ -21,7 +53,7 @@ pub struct StatRow {
     pub total: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, SerJson, DeJson)]
 pub struct StatTable {
     pub rows: Vec<StatRow>,
     pub totals: StatRow,
@@ -38,7 +70,7 @@ pub fn build_stat_where_clause(
     let mut params: Vec<String> = Vec::new();
 
     if let Some(c) = category {
-        conditions.push("category = ?".to_string());
+        conditions.push(category_condition(get_config().normalize_categories).to_string());
         params.push(c.to_string());
     }
 
@@ -84,20 +116,32 @@ pub fn batch_update_items(
     let mut set_params: Vec<String> = Vec::new();
 
     if let Some(cat) = &updates.category {
-        set_parts.push("category = ?");
+        set_parts.push("category = ?".to_string());
         set_params.push(cat.to_string());
     }
     if let Some(status) = updates.status {
-        set_parts.push("status = ?");
+        set_parts.push("status = ?".to_string());
         set_params.push(status.to_string());
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        set_parts.push(format!(
+            "closed_time = {}",
+            closed_time_expr(&status.to_string(), &now.to_string())
+        ));
     }
     if let Some(target_time) = updates.target_time {
-        set_parts.push("target_time = ?");
+        set_parts.push("target_time = ?".to_string());
         set_params.push(target_time.to_string());
     }
 
+    let changes_tags = updates.add_tag.is_some() || updates.remove_tag.is_some();
+    if set_parts.is_empty() && !changes_tags {
+        return Err(TascliError::Parse("No fields to update".to_string()));
+    }
     if set_parts.is_empty() {
-        return Err(rusqlite::Error::InvalidQuery);
+        return update_tags(conn, item_ids, updates);
     }
 
     let set_clause = set_parts.join(", ");
@@ -108,8 +152,34 @@ pub fn batch_update_items(
     all_params.extend(item_ids.iter().map(|id| id.to_string()));
 
     let query = format!("UPDATE items SET {} WHERE id IN ({})", set_clause, placeholders);
+    debug::log_query(&query, &all_params);
     let affected = conn.execute(&query, params_from_iter(all_params))?;
+    if changes_tags {
+        update_tags(conn, item_ids, updates)?;
+    }
+
+    Ok(affected)
+}
 
+// Tags are a list within one column, so each item is read and rewritten.
+// Adding a tag already there or removing one that is not leaves it as is.
+fn update_tags(conn: &Connection, item_ids: &[i64], updates: &ItemUpdates) -> Result<usize> {
+    let mut select = conn.prepare("SELECT tags FROM items WHERE id = ?1")?;
+    let mut affected = 0;
+    for id in item_ids {
+        let mut tags: Option<String> = match select.query_row([id], |row| row.get(0)) {
+            Ok(tags) => tags,
+            Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(tag) = &updates.add_tag {
+            tags = add_tag(tags.as_deref(), tag);
+        }
+        if let Some(tag) = &updates.remove_tag {
+            tags = remove_tag(tags.as_deref(), tag);
+        }
+        affected += conn.execute("UPDATE items SET tags = ?1 WHERE id = ?2", params![tags, id])?;
+    }
     Ok(affected)
 }
 
@@ -125,11 +195,59 @@ pub fn batch_delete_items(
     let params: Vec<String> = item_ids.iter().map(|id| id.to_string()).collect();
 
     let query = format!("DELETE FROM items WHERE id IN ({})", placeholders);
+    debug::log_query(&query, &params);
     let affected = conn.execute(&query, params_from_iter(params))?;
 
     Ok(affected)
 }
 
+// Item counts per category and action, ordered by category then action.
+fn count_by_category_action(
+    conn: &Connection,
+    where_clause: &str,
+    params: Vec<String>,
+) -> Result<Vec<(String, String, usize)>> {
+    let query = format!(
+        "SELECT category, action, COUNT(*) as count FROM items{} GROUP BY category, action ORDER BY category, action",
+        where_clause
+    );
+
+    debug::log_query(&query, &params);
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(params_from_iter(params), |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)? as usize,
+        ))
+    })?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// Matching items counted per category, largest first, limit and order are ignored.
+pub fn count_items_by_category(
+    conn: &Connection,
+    item_query: &ItemQuery,
+) -> Result<Vec<(String, usize)>> {
+    let (conditions, params) = build_conditions(item_query);
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for (category, _, count) in count_by_category_action(conn, &where_clause, params)? {
+        // Actions of the same category come in next to each other
+        match counts.last_mut() {
+            Some((last, total)) if *last == category => *total += count,
+            _ => counts.push((category, count)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(counts)
+}
+
 pub fn get_stats(
     conn: &Connection,
     category: Option<&str>,
@@ -146,26 +264,11 @@ pub fn get_stats(
         target_time_max,
     );
 
-    let query = format!(
-        "SELECT category, action, COUNT(*) as count FROM items{} GROUP BY category, action ORDER BY category, action",
-        where_clause
-    );
-
-    let mut stmt = conn.prepare(&query)?;
-    let rows = stmt.query_map(params_from_iter(params), |row| {
-        Ok((
-            row.get::<_, String>(0)?,
-            row.get::<_, String>(1)?,
-            row.get::<_, i64>(2)? as usize,
-        ))
-    })?;
-
     use std::collections::HashMap;
     let mut data: HashMap<String, HashMap<String, usize>> = HashMap::new();
 
-    for row_result in rows {
-        let (cat, action, count) = row_result?;
-        data.entry(cat).or_insert_with(HashMap::new).insert(action, count);
+    for (cat, action, count) in count_by_category_action(conn, &where_clause, params)? {
+        data.entry(cat).or_default().insert(action, count);
     }
 
     let mut stat_rows = Vec::new();
@@ -196,7 +299,7 @@ pub fn get_stats(
         });
     }
 
-    stat_rows.sort_by(|a, b| b.total.cmp(&a.total));
+    stat_rows.sort_by_key(|r| std::cmp::Reverse(r.total));
 
     let totals = StatRow {
         category: "TOTAL".to_string(),
@@ -220,6 +323,7 @@ mod tests {
         db::item::{
             Item,
             RECORD,
+            TASK,
         },
         tests::{
             get_test_conn,
@@ -319,6 +423,8 @@ mod tests {
             category: Some("meetings".to_string()),
             status: None,
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         let affected = batch_update_items(&conn, &[id1, id2], &updates).unwrap();
         assert_eq!(affected, 2);
@@ -336,6 +442,8 @@ mod tests {
             category: None,
             status: Some(1), // completed
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         let affected = batch_update_items(&conn, &[id1, id3], &updates).unwrap();
         assert_eq!(affected, 2);
@@ -346,6 +454,8 @@ mod tests {
         assert_eq!(item1.status, 1);
         assert_eq!(item2.status, 0); // Unchanged
         assert_eq!(item3.status, 1);
+        assert!(item1.closed_time.is_some());
+        assert_eq!(item2.closed_time, None);
 
         // Test 3: Update multiple fields
         let new_target = 9999999;
@@ -353,6 +463,8 @@ mod tests {
             category: Some("urgent".to_string()),
             status: Some(2), // cancelled
             target_time: Some(new_target),
+            add_tag: None,
+            remove_tag: None,
         };
         let affected = batch_update_items(&conn, &[id2], &updates).unwrap();
         assert_eq!(affected, 1);
@@ -367,6 +479,8 @@ mod tests {
             category: Some("test".to_string()),
             status: None,
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         let affected = batch_update_items(&conn, &[], &updates).unwrap();
         assert_eq!(affected, 0);
@@ -376,9 +490,11 @@ mod tests {
             category: None,
             status: None,
             target_time: None,
+            add_tag: None,
+            remove_tag: None,
         };
         let result = batch_update_items(&conn, &[id1], &updates);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(TascliError::Parse(_))));
     }
 
     #[test]
@@ -425,6 +541,28 @@ mod tests {
         assert!(get_item(&conn, id4).is_ok());
     }
 
+    #[test]
+    fn test_count_items_by_category() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "meeting", "today");
+        insert_task(&conn, "work", "review", "today");
+        insert_task(&conn, "life", "feeding", "today");
+        insert_task(&conn, "home", "laundry", "today");
+        insert_record(&conn, "life", "fed", "today");
+
+        let counts = count_items_by_category(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(
+            counts,
+            vec![
+                ("work".to_string(), 2),
+                ("home".to_string(), 1),
+                ("life".to_string(), 1),
+            ]
+        );
+        let counts = count_items_by_category(&conn, &ItemQuery::new()).unwrap();
+        assert_eq!(counts[0], ("life".to_string(), 2));
+    }
+
     #[test]
     fn test_get_stats() {
         let (conn, _temp_file) = get_test_conn();
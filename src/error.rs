@@ -0,0 +1,60 @@
+use std::fmt;
+
+pub type Result<T, E = TascliError> = std::result::Result<T, E>;
+
+// Errors of the db layer and index lookups, handlers still report them as strings.
+#[derive(Debug)]
+pub enum TascliError {
+    /// The index cache is missing or older than an hour
+    CacheInvalid,
+    /// No item is cached at this index of the last listing
+    IndexNotFound(usize),
+    /// No item has this id
+    ItemNotFound(i64),
+    /// A query or value that cannot be used as given
+    Parse(String),
+    Db(rusqlite::Error),
+}
+
+impl fmt::Display for TascliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TascliError::CacheInvalid => {
+                write!(f, "Cache is not valid, considering running list command first")
+            }
+            TascliError::IndexNotFound(index) => write!(f, "index {} does not exist", index),
+            TascliError::ItemNotFound(id) => write!(f, "item with id {} does not exist", id),
+            TascliError::Parse(msg) => write!(f, "{}", msg),
+            TascliError::Db(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TascliError {}
+
+impl From<rusqlite::Error> for TascliError {
+    fn from(e: rusqlite::Error) -> Self {
+        TascliError::Db(e)
+    }
+}
+
+impl From<TascliError> for String {
+    fn from(e: TascliError) -> Self {
+        e.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(TascliError::IndexNotFound(3).to_string(), "index 3 does not exist");
+        assert_eq!(TascliError::Parse("bad".to_string()).to_string(), "bad");
+        let db_error = TascliError::from(rusqlite::Error::QueryReturnedNoRows);
+        assert!(matches!(db_error, TascliError::Db(rusqlite::Error::QueryReturnedNoRows)));
+        let message: String = TascliError::CacheInvalid.into();
+        assert!(message.starts_with("Cache is not valid"));
+    }
+}
@@ -2,6 +2,7 @@ mod actions;
 mod args;
 mod config;
 mod db;
+mod error;
 
 use std::process::exit;
 
@@ -18,9 +19,8 @@ fn main() {
             exit(1)
         }
     };
-    let result = actions::handler::handle_commands(&conn, cli_args);
-    if result.is_err() {
-        print_red(&format!("Error: {}", result.unwrap_err()));
+    if let Err(err) = actions::handler::handle_commands(&conn, cli_args) {
+        print_red(&format!("Error: {}", err));
         exit(1)
     }
 }
@@ -4,7 +4,10 @@ use tempfile::NamedTempFile;
 use crate::{
     args::timestr,
     db::{
-        conn::init_table,
+        conn::{
+            init_table,
+            register_functions,
+        },
         crud::{
             get_item,
             insert_item,
@@ -19,6 +22,7 @@ pub fn get_test_conn() -> (Connection, NamedTempFile) {
     let db_path = temp_file.path().to_str().unwrap();
     let conn = Connection::open(db_path).unwrap();
     init_table(&conn).unwrap();
+    register_functions(&conn).unwrap();
     (conn, temp_file)
 }
 
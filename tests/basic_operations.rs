@@ -7,9 +7,12 @@ fn get_test_db() -> NamedTempFile {
     NamedTempFile::new().expect("Failed to create temp file")
 }
 
-/// Helper to run tascli command with test database
+/// Helper to run tascli command with test database,
+/// HOME points away from the config of whoever runs the tests
 fn tascli() -> Command {
-    Command::new(env!("CARGO_BIN_EXE_tascli"))
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_tascli"));
+    cmd.env("HOME", env!("CARGO_TARGET_TMPDIR"));
+    cmd
 }
 
 #[test]
@@ -20,7 +23,7 @@ fn test_task_create_and_list() {
     // Create a task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "work", "Complete project", "tomorrow"])
+        .args(["task", "-c", "work", "Complete project", "tomorrow"])
         .assert()
         .success()
         .stdout(predicate::str::contains("Inserted Task"))
@@ -29,7 +32,7 @@ fn test_task_create_and_list() {
     // List tasks
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "work"])
+        .args(["list", "task", "-c", "work"])
         .assert()
         .success()
         .get_output()
@@ -49,7 +52,7 @@ fn test_record_create_and_list() {
     // Create a record
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["record", "-c", "fitness", "Ran 5km"])
+        .args(["record", "-c", "fitness", "Ran 5km"])
         .assert()
         .success()
         .stdout(predicate::str::contains("Inserted Record"))
@@ -58,7 +61,7 @@ fn test_record_create_and_list() {
     // List records
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "fitness", "-d", "1"])
+        .args(["list", "record", "-c", "fitness", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -78,20 +81,20 @@ fn test_task_done_creates_record() {
     // Create and list task to populate cache
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "work", "Write tests", "today"])
+        .args(["task", "-c", "work", "Write tests", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task"])
+        .args(["list", "task"])
         .assert()
         .success();
 
     // Complete the task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "1"])
+        .args(["done", "1"])
         .assert()
         .success()
         .stdout(predicate::str::contains("Completed Task"));
@@ -99,7 +102,7 @@ fn test_task_done_creates_record() {
     // Verify record was created
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "work", "-d", "1"])
+        .args(["list", "record", "-c", "work", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -118,20 +121,20 @@ fn test_task_update() {
     // Create and list task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "home", "Original task", "today"])
+        .args(["task", "-c", "home", "Original task", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task"])
+        .args(["list", "task"])
         .assert()
         .success();
 
     // Update the task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["update", "1", "-w", "Updated task content"])
+        .args(["update", "1", "-w", "Updated task content"])
         .assert()
         .success()
         .stdout(predicate::str::contains("Updated Task"))
@@ -140,7 +143,7 @@ fn test_task_update() {
     // Verify update persisted
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "home"])
+        .args(["list", "task", "-c", "home"])
         .assert()
         .success()
         .get_output()
@@ -160,26 +163,26 @@ fn test_task_delete() {
     // Create two tasks
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "test", "Task 1", "today"])
+        .args(["task", "-c", "test", "Task 1", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "test", "Task 2", "today"])
+        .args(["task", "-c", "test", "Task 2", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task"])
+        .args(["list", "task"])
         .assert()
         .success();
 
     // Delete first task (with confirmation)
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["delete", "1"])
+        .args(["delete", "1"])
         .write_stdin("y\n")
         .assert()
         .success()
@@ -188,7 +191,7 @@ fn test_task_delete() {
     // Verify only task 2 remains
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "test"])
+        .args(["list", "task", "-c", "test"])
         .assert()
         .success()
         .get_output()
@@ -208,7 +211,7 @@ fn test_recurring_task_workflow() {
     // Create recurring task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "routine", "Daily exercise", "Daily 6AM"])
+        .args(["task", "-c", "routine", "Daily exercise", "Daily 6AM"])
         .assert()
         .success()
         .stdout(predicate::str::contains("Inserted Recurring Task"))
@@ -217,7 +220,7 @@ fn test_recurring_task_workflow() {
     // List and verify it shows as recurring
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task"])
+        .args(["list", "task"])
         .assert()
         .success()
         .get_output()
@@ -231,7 +234,7 @@ fn test_recurring_task_workflow() {
     // Complete the recurring task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "1"])
+        .args(["done", "1"])
         .assert()
         .success()
         .stdout(predicate::str::contains("Completed Recurring Task"));
@@ -239,7 +242,7 @@ fn test_recurring_task_workflow() {
     // Verify recurring record was created
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "routine", "-d", "1"])
+        .args(["list", "record", "-c", "routine", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -258,33 +261,33 @@ fn test_list_with_status_filter() {
     // Create tasks with different statuses
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "test", "Open task", "today"])
+        .args(["task", "-c", "test", "Open task", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "test", "To be completed", "today"])
+        .args(["task", "-c", "test", "To be completed", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task"])
+        .args(["list", "task"])
         .assert()
         .success();
 
     // Complete one task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "2"])
+        .args(["done", "2"])
         .assert()
         .success();
 
     // List only open tasks
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "test", "--status", "open"])
+        .args(["list", "task", "-c", "test", "--status", "open"])
         .assert()
         .success()
         .get_output()
@@ -298,7 +301,7 @@ fn test_list_with_status_filter() {
     // List only done tasks
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "test", "--status", "done"])
+        .args(["list", "task", "-c", "test", "--status", "done"])
         .assert()
         .success()
         .get_output()
@@ -318,20 +321,20 @@ fn test_record_with_time_filters() {
     // Create records
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["record", "-c", "log", "Event 1"])
+        .args(["record", "-c", "log", "Event 1"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["record", "-c", "log", "Event 2"])
+        .args(["record", "-c", "log", "Event 2"])
         .assert()
         .success();
 
     // List records from today
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "log", "-d", "1"])
+        .args(["list", "record", "-c", "log", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -351,27 +354,27 @@ fn test_task_with_comment() {
     // Create task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "work", "Task with notes", "today"])
+        .args(["task", "-c", "work", "Task with notes", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task"])
+        .args(["list", "task"])
         .assert()
         .success();
 
     // Complete with comment
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "1", "-c", "Finished early!"])
+        .args(["done", "1", "-c", "Finished early!"])
         .assert()
         .success();
 
     // Verify comment is in record
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "work", "-d", "1"])
+        .args(["list", "record", "-c", "work", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -390,27 +393,27 @@ fn test_show_content_command() {
     // Create task with multiline content
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "work", "Task with details", "today"])
+        .args(["task", "-c", "work", "Task with details", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task"])
+        .args(["list", "task"])
         .assert()
         .success();
 
     // Add content to the task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["update", "1", "-a", "Additional details here"])
+        .args(["update", "1", "-a", "Additional details here"])
         .assert()
         .success();
 
     // Show the content
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "show", "1"])
+        .args(["list", "show", "1"])
         .assert()
         .success()
         .get_output()
@@ -421,3 +424,2052 @@ fn test_show_content_command() {
     assert!(stdout.contains("Task with details"));
     assert!(stdout.contains("Additional details here"));
 }
+
+#[test]
+fn test_date_format_flag() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Plan launch", "2030-01-15 14:30"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--date-format", "iso", "--days", "10000"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2030-01-15T14:30:00"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["--date-format", "%d.%m.%Y %H:%M", "list", "task", "--days", "10000"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("15.01.2030 14:30"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--date-format", "isoo"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Invalid date format"));
+}
+
+#[test]
+fn test_show_full_command() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Write quarterly report", "2030-01-15 14:30"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--days", "10000"])
+        .assert()
+        .success();
+
+    // Default output stays bare content for piping
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1"])
+        .assert()
+        .success()
+        .stdout("Write quarterly report\n");
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--full"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Category:   work"));
+    assert!(stdout.contains("Status:     ongoing"));
+    assert!(stdout.contains("Action:     task"));
+    assert!(stdout.contains("Due:        2030-01-15 14:30:00"));
+    assert!(stdout.contains("  Write quarterly report"));
+}
+
+#[test]
+fn test_normalize_categories_config() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config").join("tascli");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.json"),
+        r#"{"normalize_categories": true}"#,
+    )
+    .unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["task", "-c", "  Home  Chores ", "Vacuum", "tomorrow"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["task", "-c", "home chores", "Dishes", "tomorrow"])
+        .assert()
+        .success();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["list", "task", "-c", "HOME CHORES"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Vacuum"));
+    assert!(stdout.contains("Dishes"));
+    assert!(stdout.contains("| home chores "));
+
+    // Without the flag, categories are matched exactly
+    let plain_home = tempfile::tempdir().unwrap();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", plain_home.path())
+        .args(["list", "task", "-c", "Home Chores"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Vacuum").not());
+}
+
+#[test]
+fn test_verbose_prints_sql() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Check query logging", "tomorrow"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env_remove("TASCLI_DEBUG")
+        .args(["list", "task", "-c", "work", "--verbose"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[tascli debug] sql: SELECT * FROM items"))
+        .stderr(predicate::str::contains("\"work\""))
+        .stdout(predicate::str::contains("[tascli debug]").not());
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("TASCLI_DEBUG", "1")
+        .args(["ops", "stat"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[tascli debug] sql: SELECT category, action"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env_remove("TASCLI_DEBUG")
+        .args(["list", "task", "-c", "work"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[tascli debug]").not());
+}
+
+#[test]
+fn test_list_include_done() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Ship release", "today"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Write changelog", "tomorrow"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "1"])
+        .assert()
+        .success();
+
+    // Hidden from the default listing
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ship release").not());
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env_remove("NO_COLOR")
+        .args(["list", "task", "--include-done", "--color", "always"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("\x1b[2;9mShip release\x1b[0m"));
+    assert!(stdout.contains("(completed)"));
+    // Completed tasks are listed after open ones
+    assert!(stdout.find("Write changelog").unwrap() < stdout.find("Ship release").unwrap());
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("NO_COLOR", "1")
+        .args(["list", "task", "--include-done"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ship release"))
+        .stdout(predicate::str::contains("\x1b[2;9m").not());
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--include-done"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("closed").not());
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--include-done", "--show-closed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("closed Today"));
+}
+
+#[test]
+fn test_ops_cron_check() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "cron-check", "Weekly Monday-Friday 9AM"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Cron: 0 9 * * 1-5"));
+    let times: Vec<&str> = stdout.lines().filter(|l| l.ends_with(" 09:00")).collect();
+    assert_eq!(times.len(), 5);
+    assert!(times.iter().all(|t| !t.contains("Sat") && !t.contains("Sun")));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "cron-check", "30 14 1 * *"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cron: 30 14 1 * *"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "cron-check", "Every so often"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "'Every so often' is neither a recurring schedule",
+        ));
+}
+
+#[test]
+fn test_list_done_shows_completions() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Ship release", "today"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-c", "work", "Unrelated note"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "done"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No completed tasks found"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "1"])
+        .assert()
+        .success();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "done", "-d", "1"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Recently Completed:"));
+    assert!(stdout.contains("Completed Task: Ship release"));
+    assert!(!stdout.contains("Unrelated note"));
+
+    // Indices from list done are cached for show
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Completed Task: Ship release"));
+}
+
+#[test]
+fn test_insert_output_and_quiet() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Review PR", "tomorrow"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Inserted Task:"));
+    assert!(stdout.contains("| Review PR"));
+    // No raw Debug output of the item
+    assert!(!stdout.contains("Item {"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-q", "-c", "work", "Quiet task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "--quiet", "-c", "work", "Quiet record"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "-c", "work"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert!(String::from_utf8_lossy(&output).contains("Quiet task"));
+}
+
+#[test]
+fn test_done_reports_late_completion() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Late task", "yesterday"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Early task", "tomorrow"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--overdue"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Completed Task (completed 1 day late):"))
+        .stdout(predicate::str::contains("Late task"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Completed Task:"))
+        .stdout(predicate::str::contains("late").not());
+}
+
+#[test]
+fn test_backup_quiet_prints_path() {
+    let work_dir = tempfile::tempdir().unwrap();
+
+    // A relative database path still yields an absolute backup path
+    tascli()
+        .env("TASCLI_TEST_DB", "tascli.db")
+        .current_dir(work_dir.path())
+        .args(["record", "-c", "notes", "before backup"])
+        .assert()
+        .success();
+    let output = tascli()
+        .env("TASCLI_TEST_DB", "tascli.db")
+        .current_dir(work_dir.path())
+        .args(["ops", "backup", "--quiet"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let printed = String::from_utf8_lossy(&output);
+    let backup = std::path::Path::new(printed.trim_end());
+    assert!(backup.is_absolute());
+    assert_eq!(backup, work_dir.path().canonicalize().unwrap().join("tascli_bak.db"));
+    let bytes = std::fs::read(backup).unwrap();
+    assert!(bytes.starts_with(b"SQLite format 3\0"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", backup.to_str().unwrap())
+        .args(["list", "record"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("before backup"));
+}
+
+#[test]
+fn test_csv_export_import_round_trip() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let csv_file = NamedTempFile::new().unwrap();
+    let csv_path = csv_file.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Plan Q3, then review", "tomorrow"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-c", "notes", "said \"hello\"", "-t", "yesterday 9AM"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "export", "--format", "csv", "--file", csv_path])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 items"));
+
+    // Import into a fresh database
+    let fresh_db = get_test_db();
+    let fresh_path = fresh_db.path().to_str().unwrap();
+    tascli()
+        .env("TASCLI_TEST_DB", fresh_path)
+        .args(["ops", "import", "--format", "csv", "--file", csv_path])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 items, failed 0"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", fresh_path)
+        .args(["list", "task", "-c", "work"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Plan Q3, then review"))
+        .stdout(predicate::str::contains("Tomorrow"));
+    tascli()
+        .env("TASCLI_TEST_DB", fresh_path)
+        .args(["list", "record", "-c", "notes", "-d", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("said \"hello\""))
+        .stdout(predicate::str::contains(" 9:00AM"));
+
+    // Exporting the fresh database reproduces the same rows apart from ids
+    let first = std::fs::read_to_string(csv_path).unwrap();
+    let output = tascli()
+        .env("TASCLI_TEST_DB", fresh_path)
+        .args(["ops", "export"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let strip_ids = |csv: &str| -> Vec<String> {
+        csv.lines()
+            .map(|l| l.split_once(',').unwrap().1.to_string())
+            .collect()
+    };
+    assert_eq!(strip_ids(&first), strip_ids(&String::from_utf8_lossy(&output)));
+}
+
+#[test]
+fn test_list_task_without_deadline() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let csv_file = NamedTempFile::new().unwrap();
+    let csv_path = csv_file.path().to_str().unwrap();
+    std::fs::write(
+        csv_path,
+        "id,action,category,content,create_time_iso,target_time_iso,status\n\
+         1,task,home,clean garage,2025-06-11T09:00:00,,0\n\
+         2,task,work,send invoice,2025-06-11T09:00:00,2025-06-12T09:00:00,0\n",
+    )
+    .unwrap();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "import", "--file", csv_path])
+        .assert()
+        .success();
+
+    for args in [["list", "task", "--overdue"], ["list", "task", "--no-deadline"]] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(args)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("clean garage"))
+            .stdout(predicate::str::contains("No deadline"));
+    }
+
+    // It leads the overdue listing, a page ending on it continues with the dated tasks
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--overdue", "-l", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("clean garage"))
+        .stdout(predicate::str::contains("send invoice").not());
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--overdue", "-l", "1", "--next-page"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("send invoice"))
+        .stdout(predicate::str::contains("clean garage").not());
+}
+
+#[test]
+fn test_list_by_source() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let csv_file = NamedTempFile::new().unwrap();
+    let csv_path = csv_file.path().to_str().unwrap();
+    std::fs::write(
+        csv_path,
+        "id,action,category,content,create_time_iso,target_time_iso,status\n\
+         1,record,notes,imported note,2025-06-11T09:00:00,,0\n",
+    )
+    .unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "import", "--file", csv_path])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 item, failed 0"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-c", "notes", "typed note", "-t", "2025-06-11 10:00"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record", "-s", "2025-06-10", "--source", "import"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("imported note"))
+        .stdout(predicate::str::contains("typed note").not());
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record", "-s", "2025-06-10", "--source", "cli"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("typed note"))
+        .stdout(predicate::str::contains("imported note").not());
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Source:"));
+}
+
+#[test]
+fn test_pin_task_lists_first() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Due soon", "today"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "goals", "Anchor goal", "tomorrow"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["pin", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pinned Task:"))
+        .stdout(predicate::str::contains("goals [pin]"));
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.find("Anchor goal").unwrap() < stdout.find("Due soon").unwrap());
+
+    // The pinned task is now index 1
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["unpin", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unpinned Task:"));
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.find("Due soon").unwrap() < stdout.find("Anchor goal").unwrap());
+    assert!(!stdout.contains("[pin]"));
+}
+
+#[test]
+fn test_seconds_flag_distinguishes_records() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-c", "log", "first entry"])
+        .assert()
+        .success();
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-c", "log", "second entry"])
+        .assert()
+        .success();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record", "--seconds"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    let time_of = |content: &str| {
+        let line = stdout.lines().find(|l| l.contains(content)).unwrap();
+        line.split('|').nth(4).unwrap().trim().to_string()
+    };
+    let first = time_of("first entry");
+    let second = time_of("second entry");
+    assert_ne!(first, second);
+    // Today h:mm:ssAM
+    assert_eq!(first.matches(':').count(), 2, "{}", first);
+}
+
+#[test]
+fn test_note_shown_only_in_full() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Plan offsite", "tomorrow", "--note", "book the venue early"])
+        .assert()
+        .success();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Plan offsite"));
+    assert!(!stdout.contains("book the venue"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1"])
+        .assert()
+        .success()
+        .stdout("Plan offsite\n");
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Note:\n  book the venue early"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["update", "1", "--note", "venue booked"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Note:\n  venue booked"));
+}
+
+#[test]
+fn test_overdue_only_and_footer() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Upcoming review", "tomorrow"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("overdue").not());
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Missed filing", "yesterday"])
+        .assert()
+        .success();
+
+    // Overdue tasks are hidden by default, but counted in the footer
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Upcoming review"));
+    assert!(!stdout.contains("Missed filing"));
+    assert!(stdout.contains("1 overdue"));
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--overdue-only"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Missed filing"));
+    assert!(!stdout.contains("Upcoming review"));
+    assert!(stdout.contains("1 overdue"));
+}
+
+#[test]
+fn test_delete_index_range() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for content in ["Task 1", "Task 2", "Task 3"] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", "-c", "test", content, "today"])
+            .assert()
+            .success();
+    }
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+
+    // A single confirmation covers every index
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["delete", "1,3"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("delete these 2 items?"))
+        .stdout(predicate::str::contains("Deletion success"));
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "-c", "test"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(!stdout.contains("Task 1"));
+    assert!(stdout.contains("Task 2"));
+    assert!(!stdout.contains("Task 3"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["delete", "2-1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("start is after end"));
+}
+
+#[test]
+fn test_content_length_limit() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let long_content = "a".repeat(10001);
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", &long_content])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("over the limit of 10000"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-q", "--truncate", &long_content])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["update", "1", "-a", &long_content])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("over the limit of 10000"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["update", "1", "-w", "short again"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("short again"));
+}
+
+#[test]
+fn test_category_colors() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config").join("tascli");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.json"),
+        r#"{"category_colors": {"work": "blue"}}"#,
+    )
+    .unwrap();
+
+    for category in ["work", "home"] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", "-q", "-c", category, "Colored task", "tomorrow"])
+            .assert()
+            .success();
+    }
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .env_remove("NO_COLOR")
+        .args(["list", "task", "--color", "always"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("\x1b[34mwork\x1b[0m"));
+    // Unmapped categories stay plain
+    assert!(stdout.contains("| home "));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .env("NO_COLOR", "1")
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[34m").not());
+}
+
+#[test]
+fn test_recent_lists_newest_first() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-q", "-c", "feeding", "Oldest record", "-t", "yesterday 2PM"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-q", "-c", "feeding", "Middle record", "-t", "yesterday 5PM"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-q", "-c", "work", "Newest task", "tomorrow"])
+        .assert()
+        .success();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["recent", "2"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("| Type "));
+    assert!(stdout.contains("| 1      | task    | work "));
+    assert!(stdout.contains("| 2      | record  | feeding "));
+    assert!(stdout.find("Newest task").unwrap() < stdout.find("Middle record").unwrap());
+    assert!(!stdout.contains("Oldest record"));
+
+    // Indices are cached for follow up commands
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Middle record"));
+}
+
+#[test]
+fn test_count_due() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["count-due"])
+        .assert()
+        .success()
+        .stdout("0\n");
+
+    for (content, timestr) in [("Missed", "yesterday"), ("Due", "today"), ("Later", "tomorrow")] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", "-q", content, timestr])
+            .assert()
+            .success();
+    }
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["count-due"])
+        .assert()
+        .success()
+        .stdout("2\n");
+}
+
+#[test]
+fn test_update_schedule_preview() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-q", "Standup", "Daily 9AM"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+
+    // Declining keeps the old schedule
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["update", "1", "-t", "Weekly Monday 9AM", "--preview"])
+        .write_stdin("n\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Cron: 0 9 * * 1"))
+        .stdout(predicate::str::contains("Mon 09:00"))
+        .stdout(predicate::str::contains("Not updating the schedule"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Daily 9AM"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["update", "1", "-t", "Weekly Monday 9AM", "--preview"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Updated Recurring Task:"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Weekly Monday 9AM"));
+}
+
+#[test]
+fn test_match_by_content() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for content in ["Alpha report", "Beta report"] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", "-q", content, "tomorrow"])
+            .assert()
+            .success();
+    }
+
+    // Ambiguous matches are shown and nothing is changed
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["delete", "--match", "report"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Alpha report"))
+        .stdout(predicate::str::contains("Beta report"))
+        .stdout(predicate::str::contains("2 open tasks match 'report'"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["delete", "--match", "alpha"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deletion success"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "--match", "report"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Beta report"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks found"));
+}
+
+#[test]
+fn test_batch_confirm_threshold() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for content in ["one", "two", "three"] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["record", "-c", "old", content])
+            .assert()
+            .success();
+    }
+
+    // Above the threshold a plain y cancels
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "batch", "-c", "old", "-t", "new", "--confirm-threshold", "2"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Type 3 to proceed"))
+        .stdout(predicate::str::contains("Cancelled"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "batch", "-c", "old", "-t", "new", "--confirm-threshold", "2"])
+        .write_stdin("3\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Successfully updated 3 items"));
+
+    // At the threshold the usual y is enough
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "batch", "-c", "new", "-t", "old", "--confirm-threshold", "3"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Proceed? (y/n)"))
+        .stdout(predicate::str::contains("Successfully updated 3 items"));
+}
+
+#[test]
+fn test_batch_tags() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for (category, content) in [("work", "write docs"), ("work", "fix build"), ("home", "clean")] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", "-c", category, content, "tomorrow"])
+            .assert()
+            .success();
+    }
+
+    for _ in 0..2 {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["ops", "batch", "-c", "work", "--add-tag", "urgent"])
+            .write_stdin("y\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Add tag: urgent"))
+            .stdout(predicate::str::contains("Successfully updated 2 items"));
+    }
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "batch", "-c", "work", "--add-tag", "q3"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("tags: urgent -> urgent,q3"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "-c", "work"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Category:   work"))
+        .stdout(predicate::str::contains("Tags:       urgent, q3"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "batch", "-c", "work", "--remove-tag", "urgent"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("tags: urgent,q3 -> q3"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "-c", "home"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tags:").not());
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "batch", "-c", "work", "--add-tag", "a,b"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_stat_total_only() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    let items: &[&[&str]] = &[
+        &["task", "-c", "work", "Write docs", "tomorrow"],
+        &["task", "-c", "home", "Fix sink", "tomorrow"],
+        &["record", "-c", "work", "Standup notes"],
+        &["record", "-c", "home", "Watered plants"],
+        &["record", "-c", "home", "Took out trash"],
+    ];
+    for args in items {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(*args)
+            .assert()
+            .success();
+    }
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "stat", "--total-only"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", items.len()));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "stat", "--total-only", "-c", "home"])
+        .assert()
+        .success()
+        .stdout("3\n");
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "stat", "--total-only", "-c", "nothing"])
+        .assert()
+        .success()
+        .stdout("0\n");
+}
+
+#[test]
+fn test_list_no_cache_keeps_previous_indices() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for (category, content) in [("work", "Write report"), ("home", "Fix sink")] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", "-c", category, content, "tomorrow"])
+            .assert()
+            .success();
+    }
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "-c", "work"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "-c", "home", "--no-cache"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fix sink"));
+
+    // Index 1 still refers to the work listing
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Write report"));
+}
+
+#[test]
+fn test_ops_migrate_old_database() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    // A database left behind by a version 3 release, before notes and sources
+    let conn = rusqlite::Connection::open(db_path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            category TEXT NOT NULL,
+            content TEXT NOT NULL,
+            create_time INTEGER NOT NULL,
+            target_time INTEGER,
+            modify_time INTEGER,
+            status INTEGER DEFAULT 0,
+            cron_schedule TEXT,
+            human_schedule TEXT,
+            recurring_task_id INTEGER,
+            good_until INTEGER,
+            pinned INTEGER DEFAULT 0
+        );
+        INSERT INTO items (action, category, content, create_time)
+            VALUES ('record', 'life', 'kept through upgrade', strftime('%s', 'now'));
+        PRAGMA user_version = 3;",
+    )
+    .unwrap();
+    drop(conn);
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "migrate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Migrated schema version 3 ->"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "migrate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already up to date"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("kept through upgrade"));
+}
+
+#[test]
+fn test_list_task_due_on_day() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for (content, due) in [
+        ("Thursday prep", "friday -1d"),
+        ("Friday demo", "friday 10AM"),
+        ("Friday retro", "friday"),
+        ("Saturday hike", "friday +1d"),
+    ] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", content, due])
+            .assert()
+            .success();
+    }
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--due", "friday"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Friday demo"))
+        .stdout(predicate::str::contains("Friday retro"))
+        .stdout(predicate::str::contains("Thursday prep").not())
+        .stdout(predicate::str::contains("Saturday hike").not());
+}
+
+#[test]
+fn test_record_daily_goal_progress() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record", "-c", "water", "--today-progress"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("No daily goal for 'water'"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-c", "water", "--goal", "8"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Daily goal for 'water' set to 8"));
+    for _ in 0..4 {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["record", "-c", "water", "glass"])
+            .assert()
+            .success();
+    }
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record", "-c", "water", "--today-progress"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("water: 4/8 today [##########----------]"));
+    // Content is still required without --goal
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-c", "water"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_reschedule_overdue_to_today() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for (content, due) in [
+        ("Late report", "yesterday"),
+        ("Late bill", "yesterday 9AM"),
+        ("Next week", "today +7d"),
+    ] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", content, due])
+            .assert()
+            .success();
+    }
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "reschedule-overdue"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Rescheduled 2 overdue tasks to today"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--overdue-only"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks found"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--due", "today"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Late report"))
+        .stdout(predicate::str::contains("Late bill"))
+        .stdout(predicate::str::contains("Next week").not());
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["ops", "reschedule-overdue"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No overdue tasks found"));
+}
+
+#[test]
+fn test_list_task_budget() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for (content, estimate) in [("Write draft", "45m"), ("Edit draft", "1h30m")] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", content, "-e", estimate])
+            .assert()
+            .success();
+    }
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Send draft"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Bad estimate", "-e", "90"])
+        .assert()
+        .failure();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--budget"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[~1h30m]"))
+        .stdout(predicate::str::contains("Budget: 2h15m (2/3 tasks estimated)"));
+}
+
+#[test]
+fn test_list_record_by_day() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for (content, time) in [
+        ("Morning run", "2025/03/01 10AM"),
+        ("Evening walk", "2025/03/01 6PM"),
+        ("Swim", "2025/03/02 9AM"),
+    ] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["record", "-c", "fitness", content, "-t", time])
+            .assert()
+            .success();
+    }
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record", "-s", "2025/02/28", "-e", "2025/03/02", "--by-day"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let first = stdout.find("2025-03-01 Sat (2 records)").expect(&stdout);
+    let second = stdout.find("2025-03-02 Sun (1 record)").expect(&stdout);
+    assert!(first < stdout.find("Evening walk").unwrap());
+    assert!(stdout.find("Evening walk").unwrap() < second);
+    assert!(second < stdout.find("Swim").unwrap());
+}
+
+#[test]
+fn test_days_in_configured_timezone() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config").join("tascli");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    // UTC+14, so its morning is still the previous day in UTC
+    std::fs::write(config_dir.join("config.json"), r#"{"timezone": "Pacific/Kiritimati"}"#).unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .env("TZ", "UTC")
+        .args(["record", "-c", "journal", "Early swim", "-t", "2025/03/14 8AM"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .env("TZ", "UTC")
+        .args(["list", "record", "-s", "2025/03/13", "-e", "2025/03/15", "--by-day"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2025-03-14 Fri (1 record)"));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .env("TZ", "UTC")
+        .args(["ops", "on", "2025/03/14"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Recorded on 2025-03-14 Fri:"))
+        .stdout(predicate::str::contains("Early swim"));
+}
+
+#[test]
+fn test_list_show_json() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Write \"Q1\" report", "2030-01-15 14:30"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--days", "10000"])
+        .assert()
+        .success();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let json = stdout.trim();
+    assert!(json.starts_with('{') && json.ends_with('}'), "{}", json);
+    assert_eq!(json.lines().count(), 1);
+    assert!(json.contains(r#""content":"Write \"Q1\" report""#), "{}", json);
+    assert!(json.contains(r#""category":"work""#), "{}", json);
+    assert!(json.contains(r#""action":"task""#), "{}", json);
+}
+
+#[test]
+fn test_table_width_override() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Plan the week"])
+        .assert()
+        .success();
+
+    // The separator spans the width less the margin, plus the delimiters
+    let separator_of = |width: usize| format!("\n{}\n", "-".repeat(width - 10 + 4));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("COLUMNS", "150")
+        .args(["list", "task", "--width", "200"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(separator_of(200)));
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("COLUMNS", "150")
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(separator_of(150)))
+        .stdout(predicate::str::contains(separator_of(200)).not());
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--width", "40"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_plain_output() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Plan the week\nand\tthe weekend", "tomorrow"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Missed deadline", "yesterday"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "-c", "gym", "Leg day"])
+        .assert()
+        .success();
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--plain"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    // Only the row, no header, status summary or overdue footer
+    let stdout = String::from_utf8_lossy(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 1, "{}", stdout);
+    let row: Vec<&str> = lines[0].split('\t').collect();
+    assert_eq!(row.len(), 4);
+    assert_eq!(row[0], "1");
+    assert_eq!(row[2], "Plan the week\\nand\\tthe weekend");
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["--plain", "list", "record"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 1, "{}", stdout);
+    assert_eq!(lines[0].split('\t').collect::<Vec<_>>()[..3], ["1", "gym", "Leg day"]);
+}
+
+#[test]
+fn test_item_reference() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Fix login bug", "--ref", "https://"])
+        .assert()
+        .failure();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-q", "Fix login bug", "--ref", "https://example.com/issues/42"])
+        .assert()
+        .success();
+
+    // The table leaves the reference out
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fix login bug"))
+        .stdout(predicate::str::contains("example.com").not());
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Reference:  https://example.com/issues/42"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["update", "1", "--ref", "BUG-42"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Reference:  BUG-42"));
+}
+
+#[test]
+fn test_list_task_agenda() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    for (content, time) in [("Draft plan", "2030/03/02 10AM"), ("Fix sink", "2030/03/04 9AM")] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", "-c", "work", content, time])
+            .assert()
+            .success();
+    }
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--due-after", "2030/03/02", "--due-before", "2030/03/05", "--agenda"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    // Every day of the window has a header in order, empty days included
+    let headers = [
+        "2030-03-02 Sat (1 task)",
+        "2030-03-03 Sun\n  No tasks",
+        "2030-03-04 Mon (1 task)",
+        "2030-03-05 Tue\n  No tasks",
+    ];
+    let positions: Vec<usize> = headers.iter().map(|h| stdout.find(h).expect(&stdout)).collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "{}", stdout);
+    assert!(positions[0] < stdout.find("Draft plan").unwrap());
+    assert!(stdout.find("Draft plan").unwrap() < positions[1]);
+    assert!(positions[2] < stdout.find("Fix sink").unwrap());
+    assert!(!stdout.contains("2030-03-01"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--agenda"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--agenda needs a window end"));
+}
+
+#[test]
+fn test_list_titles_only() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Quarterly review\nCollect the numbers from finance", "tomorrow"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task", "--titles-only"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Quarterly review"))
+        .stdout(predicate::str::contains("Collect the numbers").not());
+
+    // The full content is still stored and shown
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "show", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Quarterly review\nCollect the numbers from finance"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Collect the numbers"));
+}
+
+#[test]
+fn test_status_labels() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config").join("tascli");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.json"), r#"{"status_labels": {"done": "✓"}}"#).unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-q", "Ship release", "today"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["done", "1"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["list", "task", "--include-done"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Today (✓)"))
+        .stdout(predicate::str::contains("(completed)").not());
+    // Parsing keeps the built-in names
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["list", "task", "-s", "done"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ship release"));
+}
+
+#[test]
+fn test_config_env_override() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config").join("tascli");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.json"), r#"{"default_list_limit": 2}"#).unwrap();
+    let custom = home.path().join("tascli.json");
+    std::fs::write(&custom, r#"{"default_list_limit": 1}"#).unwrap();
+
+    for (content, time) in [("First", "tomorrow 1AM"), ("Second", "tomorrow 2AM"), ("Third", "tomorrow 3AM")] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .args(["task", content, time])
+            .assert()
+            .success();
+    }
+
+    // TASCLI_CONFIG is read before the default location
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .env("TASCLI_CONFIG", &custom)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("First"))
+        .stdout(predicate::str::contains("Second").not());
+
+    // A path that does not exist warns and falls back
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .env("TASCLI_CONFIG", home.path().join("missing.json"))
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("TASCLI_CONFIG is set to"))
+        .stdout(predicate::str::contains("Second"))
+        .stdout(predicate::str::contains("Third").not());
+}
+
+#[test]
+fn test_normalized_category_filter() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let config = home.path().join("tascli.json");
+    std::fs::write(&config, r#"{"normalize_categories": true}"#).unwrap();
+
+    // Added before normalization was turned on
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "Home  Chores", "Fix sink", "tomorrow"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("TASCLI_CONFIG", &config)
+        .args(["list", "task", "-c", "HOME chores"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fix sink"));
+}
+
+#[test]
+fn test_bare_list() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Water plants", "today"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["record", "Watered the lawn"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Water plants"))
+        .stdout(predicate::str::contains("Watered the lawn").not());
+
+    // Explicit subcommands keep working
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Watered the lawn"));
+
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config").join("tascli");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.json"), r#"{"default_list": "record"}"#).unwrap();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Watered the lawn"))
+        .stdout(predicate::str::contains("Water plants").not());
+}
+
+#[test]
+fn test_default_list_limit_config() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config").join("tascli");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.json"), r#"{"default_list_limit": 2}"#).unwrap();
+
+    for (content, time) in [("First", "tomorrow 1AM"), ("Second", "tomorrow 2AM"), ("Third", "tomorrow 3AM")] {
+        tascli()
+            .env("TASCLI_TEST_DB", db_path)
+            .env("HOME", home.path())
+            .args(["task", content, time])
+            .assert()
+            .success();
+    }
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Second"))
+        .stdout(predicate::str::contains("Third").not());
+
+    // An explicit -l still wins
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .env("HOME", home.path())
+        .args(["list", "task", "-l", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Third"));
+}
+
+#[test]
+fn test_color_modes() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "Ship release", "tomorrow"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success();
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "1"])
+        .assert()
+        .success();
+
+    let list = |color: &str, no_color: Option<&str>| {
+        let mut cmd = tascli();
+        cmd.env("TASCLI_TEST_DB", db_path);
+        match no_color {
+            Some(v) => cmd.env("NO_COLOR", v),
+            None => cmd.env_remove("NO_COLOR"),
+        };
+        let output = cmd
+            .args(["list", "task", "--include-done", "--color", color])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output).unwrap()
+    };
+
+    // always forces escapes into a pipe, even over NO_COLOR
+    for no_color in [None, Some("1")] {
+        let stdout = list("always", no_color);
+        assert!(stdout.contains("\x1b[1mTasks List:\x1b[0m"), "{}", stdout);
+        assert!(stdout.contains("\x1b[2;9mShip release\x1b[0m"), "{}", stdout);
+    }
+    // never and auto into a pipe print plain text
+    for color in ["never", "auto"] {
+        let stdout = list(color, None);
+        assert!(stdout.contains("Tasks List:"), "{}", stdout);
+        assert!(!stdout.contains('\x1b'), "{}", stdout);
+    }
+}
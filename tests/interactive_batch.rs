@@ -7,9 +7,12 @@ fn get_test_db() -> NamedTempFile {
     NamedTempFile::new().expect("Failed to create temp file")
 }
 
-/// Helper to run tascli command with test database
+/// Helper to run tascli command with test database,
+/// HOME points away from the config of whoever runs the tests
 fn tascli() -> Command {
-    Command::new(env!("CARGO_BIN_EXE_tascli"))
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_tascli"));
+    cmd.env("HOME", env!("CARGO_TARGET_TMPDIR"));
+    cmd
 }
 
 /// Helper to setup tasks for testing
@@ -17,7 +20,7 @@ fn setup_tasks(db_path: &str, tasks: &[(&str, &str)]) {
     for (content, category) in tasks {
         tascli()
             .env("TASCLI_TEST_DB", db_path)
-            .args(&["task", "-c", category, content, "today"])
+            .args(["task", "-c", category, content, "today"])
             .assert()
             .success();
     }
@@ -28,7 +31,7 @@ fn setup_records(db_path: &str, records: &[(&str, &str)]) {
     for (content, category) in records {
         tascli()
             .env("TASCLI_TEST_DB", db_path)
-            .args(&["record", "-c", category, content])
+            .args(["record", "-c", category, content])
             .assert()
             .success();
     }
@@ -45,7 +48,7 @@ fn test_batch_interactive_update_category_all_yes() {
     // Test: interactively update category, accept all
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&[
+        .args([
             "ops",
             "batch",
             "-c",
@@ -64,7 +67,7 @@ fn test_batch_interactive_update_category_all_yes() {
     // Verify: tasks now in "new" category
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "new"])
+        .args(["list", "task", "-c", "new"])
         .assert()
         .success()
         .get_output()
@@ -94,7 +97,7 @@ fn test_batch_interactive_delete_with_skip_and_quit() {
     // Test: interactive delete - yes, no, quit
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["ops", "batch", "-c", "test", "--delete", "--interactive"])
+        .args(["ops", "batch", "-c", "test", "--delete", "--interactive"])
         .write_stdin("y\nn\nq\n")
         .assert()
         .success()
@@ -104,7 +107,7 @@ fn test_batch_interactive_delete_with_skip_and_quit() {
     // Verify: only record 1 was deleted, records 2 and 3 remain
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "test", "-d", "1"])
+        .args(["list", "record", "-c", "test", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -131,7 +134,7 @@ fn test_batch_interactive_update_task_status() {
     // Test: interactively mark as cancelled (status 2) - yes, yes, no
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&[
+        .args([
             "ops",
             "batch",
             "--action",
@@ -150,7 +153,7 @@ fn test_batch_interactive_update_task_status() {
     // Verify: first 2 tasks cancelled, third still open
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "work", "--status", "cancelled"])
+        .args(["list", "task", "-c", "work", "--status", "cancelled"])
         .assert()
         .success()
         .get_output()
@@ -171,7 +174,7 @@ fn test_batch_interactive_no_items() {
     // Test: batch with no matching items
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&[
+        .args([
             "ops",
             "batch",
             "-c",
@@ -193,13 +196,13 @@ fn test_batch_interactive_with_time_filters() {
     // Setup: create tasks for today and tomorrow
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "work", "Today task", "today"])
+        .args(["task", "-c", "work", "Today task", "today"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "work", "Tomorrow task", "tomorrow"])
+        .args(["task", "-c", "work", "Tomorrow task", "tomorrow"])
         .assert()
         .success();
 
@@ -207,7 +210,7 @@ fn test_batch_interactive_with_time_filters() {
     // Note: filter might find multiple items, provide enough responses
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&[
+        .args([
             "ops",
             "batch",
             "--action",
@@ -227,7 +230,7 @@ fn test_batch_interactive_with_time_filters() {
     // Verify: at least today's task was updated to urgent category
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "urgent"])
+        .args(["list", "task", "-c", "urgent"])
         .assert()
         .success()
         .get_output()
@@ -249,20 +252,20 @@ fn test_batch_interactive_with_status_filter() {
     // Complete one task
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "work"])
+        .args(["list", "task", "-c", "work"])
         .assert()
         .success();
 
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "1"])
+        .args(["done", "1"])
         .assert()
         .success();
 
     // Test: batch only on open tasks with status filter
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&[
+        .args([
             "ops",
             "batch",
             "--action",
@@ -283,7 +286,7 @@ fn test_batch_interactive_with_status_filter() {
     // Verify: only the open task was updated
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "pending"])
+        .args(["list", "task", "-c", "pending"])
         .assert()
         .success()
         .get_output()
@@ -306,7 +309,7 @@ fn test_batch_non_interactive_still_works() {
     // Test: non-interactive batch (single confirmation prompt, not per-item)
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["ops", "batch", "-c", "old", "--category-to", "new"])
+        .args(["ops", "batch", "-c", "old", "--category-to", "new"])
         .write_stdin("y\n")
         .assert()
         .success()
@@ -316,7 +319,7 @@ fn test_batch_non_interactive_still_works() {
     // Verify: all tasks updated
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "new"])
+        .args(["list", "task", "-c", "new"])
         .assert()
         .success()
         .get_output()
@@ -340,7 +343,7 @@ fn test_batch_interactive_mixed_actions() {
     // Test: batch on "all" action type updates both
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&[
+        .args([
             "ops",
             "batch",
             "--action",
@@ -359,7 +362,7 @@ fn test_batch_interactive_mixed_actions() {
     // Verify: both task and record updated
     let task_output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task", "-c", "updated"])
+        .args(["list", "task", "-c", "updated"])
         .assert()
         .success()
         .get_output()
@@ -368,7 +371,7 @@ fn test_batch_interactive_mixed_actions() {
 
     let record_output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "updated", "-d", "1"])
+        .args(["list", "record", "-c", "updated", "-d", "1"])
         .assert()
         .success()
         .get_output()
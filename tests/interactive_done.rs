@@ -7,9 +7,12 @@ fn get_test_db() -> NamedTempFile {
     NamedTempFile::new().expect("Failed to create temp file")
 }
 
-/// Helper to run tascli command with test database
+/// Helper to run tascli command with test database,
+/// HOME points away from the config of whoever runs the tests
 fn tascli() -> Command {
-    Command::new(env!("CARGO_BIN_EXE_tascli"))
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_tascli"));
+    cmd.env("HOME", env!("CARGO_TARGET_TMPDIR"));
+    cmd
 }
 
 /// Helper to setup tasks for testing
@@ -17,7 +20,7 @@ fn setup_tasks(db_path: &str, tasks: &[(&str, &str)]) {
     for (content, category) in tasks {
         tascli()
             .env("TASCLI_TEST_DB", db_path)
-            .args(&["task", "-c", category, content, "today"])
+            .args(["task", "-c", category, content, "today"])
             .assert()
             .success();
     }
@@ -27,7 +30,7 @@ fn setup_tasks(db_path: &str, tasks: &[(&str, &str)]) {
 fn prime_cache(db_path: &str) {
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "task"])
+        .args(["list", "task"])
         .assert()
         .success();
 }
@@ -44,7 +47,7 @@ fn test_done_today_complete_all_tasks() {
     // Test: complete both tasks (y with empty comment, y with empty comment)
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "today"])
+        .args(["done", "today"])
         .write_stdin("y\n\ny\n\n")
         .assert()
         .success()
@@ -54,7 +57,7 @@ fn test_done_today_complete_all_tasks() {
     // Verify: check completion records were created
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "work", "-d", "1"])
+        .args(["list", "record", "-c", "work", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -78,7 +81,7 @@ fn test_done_today_with_comments() {
     // Test: complete with a comment
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "today"])
+        .args(["done", "today"])
         .write_stdin("y\nFinished the analysis section\n")
         .assert()
         .success()
@@ -87,7 +90,7 @@ fn test_done_today_with_comments() {
     // Verify: check comment is in the record
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "work", "-d", "1"])
+        .args(["list", "record", "-c", "work", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -114,7 +117,7 @@ fn test_done_today_skip_and_quit() {
     // Test: complete first, skip second, quit on third
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "today"])
+        .args(["done", "today"])
         .write_stdin("y\nDone!\nn\nq\n")
         .assert()
         .success()
@@ -130,7 +133,7 @@ fn test_done_today_skip_and_quit() {
     // Verify: only task 1 has a completion record
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "work", "-d", "1"])
+        .args(["list", "record", "-c", "work", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -151,7 +154,7 @@ fn test_done_today_no_tasks() {
     // Test: run done today with no tasks
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "today"])
+        .args(["done", "today"])
         .assert()
         .success()
         .stdout(predicate::str::contains("No open tasks found for today"));
@@ -165,7 +168,7 @@ fn test_done_overdue_lists_overdue_tasks() {
     // Setup: create an overdue task (yesterday)
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["task", "-c", "work", "Overdue task", "yesterday"])
+        .args(["task", "-c", "work", "Overdue task", "yesterday"])
         .assert()
         .success();
 
@@ -174,7 +177,7 @@ fn test_done_overdue_lists_overdue_tasks() {
     // Test: done overdue should find it
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "overdue"])
+        .args(["done", "overdue"])
         .write_stdin("y\nCaught up\n")
         .assert()
         .success()
@@ -184,7 +187,7 @@ fn test_done_overdue_lists_overdue_tasks() {
     // Verify: check the task was completed
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-c", "work", "-d", "2"])
+        .args(["list", "record", "-c", "work", "-d", "2"])
         .assert()
         .success()
         .get_output()
@@ -208,7 +211,7 @@ fn test_done_with_index_still_works() {
     // Test: complete by index (traditional way)
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "1", "-c", "Done via index"])
+        .args(["done", "1", "-c", "Done via index"])
         .assert()
         .success()
         .stdout(predicate::str::contains("Completed Task"));
@@ -216,7 +219,7 @@ fn test_done_with_index_still_works() {
     // Verify
     let output = tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["list", "record", "-d", "1"])
+        .args(["list", "record", "-d", "1"])
         .assert()
         .success()
         .get_output()
@@ -236,10 +239,133 @@ fn test_done_today_rejects_comment_flag() {
     // Note: errors are printed to stdout (with red color codes), not stderr
     tascli()
         .env("TASCLI_TEST_DB", db_path)
-        .args(&["done", "today", "-c", "this should fail"])
+        .args(["done", "today", "-c", "this should fail"])
         .assert()
         .failure()
         .stdout(predicate::str::contains(
             "--comment is not supported with 'today'",
         ));
 }
+
+#[test]
+fn test_done_category_completes_open_tasks() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    // Setup: two open work tasks, one of them overdue, and an unrelated home task
+    setup_tasks(db_path, &[("Work task today", "work"), ("Home task", "home")]);
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["task", "-c", "work", "Work task overdue", "yesterday"])
+        .assert()
+        .success();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "--category", "work"])
+        .write_stdin("y\n\ny\n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Interactive done: 2 tasks found"))
+        .stdout(predicate::str::contains("Completed 2 tasks, skipped 0"));
+
+    // Verify: completion records exist for the work tasks only
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record", "-d", "1"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8_lossy(&output);
+    assert!(stdout.contains("Completed Task: Work task today"));
+    assert!(stdout.contains("Completed Task: Work task overdue"));
+    assert!(!stdout.contains("Home task"));
+
+    // Nothing left open in the category
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "--category", "work"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No open tasks found in category"));
+}
+
+#[test]
+fn test_done_requires_target_or_category() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Expected an index, 'today', 'overdue', or --category"));
+}
+
+#[test]
+fn test_done_today_all_completes_without_prompts() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    setup_tasks(
+        db_path,
+        &[("Task A", "work"), ("Task B", "work"), ("Task C", "home")],
+    );
+
+    // A single confirmation, no per-task prompts or comments
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "today", "--all"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 3 tasks"))
+        .stdout(predicate::str::contains("Completed 3 tasks, failed 0"))
+        .stdout(predicate::str::contains("Comment (optional)").not());
+
+    let output = tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "record", "-d", "1"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+    assert_eq!(stdout.matches("Completed Task:").count(), 3);
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "today", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No open tasks found for today"));
+}
+
+#[test]
+fn test_done_today_all_cancelled() {
+    let db = get_test_db();
+    let db_path = db.path().to_str().unwrap();
+
+    setup_tasks(db_path, &[("Task A", "work"), ("Task B", "work")]);
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["done", "today", "--all"])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cancelled"));
+
+    tascli()
+        .env("TASCLI_TEST_DB", db_path)
+        .args(["list", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Task A"))
+        .stdout(predicate::str::contains("Task B"));
+}